@@ -0,0 +1,74 @@
+use futures::{Stream, StreamExt};
+use serde::Serialize;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::ApiError;
+
+/// Number of lines written between each flush of the writer.
+const FLUSH_EVERY: usize = 100;
+
+/// Writes each item in the stream as a single line of JSON (NDJSON) to `writer`.
+/// Stops at and returns the first error encountered in the stream, otherwise
+/// returns the number of items written.
+pub async fn export_ndjson<T, S, W>(stream: S, writer: &mut W) -> Result<usize, ApiError>
+where
+    T: Serialize,
+    S: Stream<Item = Result<T, ApiError>>,
+    W: AsyncWrite + Unpin,
+{
+    let mut stream = Box::pin(stream);
+    let mut count = 0;
+    while let Some(item) = stream.next().await {
+        let mut line = serde_json::to_vec(&item?)?;
+        line.push(b'\n');
+        writer.write_all(&line).await.map_err(anyhow::Error::from)?;
+
+        count += 1;
+        if count % FLUSH_EVERY == 0 {
+            writer.flush().await.map_err(anyhow::Error::from)?;
+        }
+    }
+
+    writer.flush().await.map_err(anyhow::Error::from)?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod test {
+    use super::export_ndjson;
+    use crate::ApiError;
+
+    #[tokio::test]
+    async fn test_export_ndjson() {
+        let items = vec![
+            Ok(serde_json::json!({ "id": 1 })),
+            Ok(serde_json::json!({ "id": 2 })),
+        ];
+
+        let mut buffer = Vec::new();
+        let count = export_ndjson(futures::stream::iter(items), &mut buffer)
+            .await
+            .expect("Unable to export");
+
+        assert_eq!(count, 2);
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "{\"id\":1}\n{\"id\":2}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_ndjson_error() {
+        let items = vec![
+            Ok(serde_json::json!({ "id": 1 })),
+            Err(ApiError::BadRequest("bad".into())),
+            Ok(serde_json::json!({ "id": 3 })),
+        ];
+
+        let mut buffer = Vec::new();
+        let res = export_ndjson(futures::stream::iter(items), &mut buffer).await;
+
+        assert!(matches!(res, Err(ApiError::BadRequest(_))));
+        assert_eq!(String::from_utf8(buffer).unwrap(), "{\"id\":1}\n");
+    }
+}
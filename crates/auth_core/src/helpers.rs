@@ -1,32 +1,51 @@
 use anyhow::anyhow;
 use oauth2::basic::BasicTokenResponse;
 use oauth2::CsrfToken;
+use scraper::Html;
 use std::io::{BufRead, BufReader, Write};
 use std::net::TcpListener;
-use std::path::Path;
 use url::Url;
 
+use crate::store::CredentialStore;
 use crate::AuthorizeOptions;
 
 use super::{ApiClient, Credentials};
 
-const SAVED_CREDS_DIR: &str = "credentials";
+/// Strip the tags from an HTML fragment, returning only its text content.
+pub fn html_to_text(html: &str) -> String {
+    if html.is_empty() {
+        return String::new();
+    }
 
-/// Helper function to load saved credentials from the filesystem.
-/// SHOULD ONLY BE USED FOR EXAMPLES AND TESTS
-pub async fn load_credentials(client: &mut impl ApiClient, scopes: &[String], use_pkce: bool) {
-    let dir = Path::new(SAVED_CREDS_DIR);
-    if !dir.exists() {
-        let _ = std::fs::create_dir_all(SAVED_CREDS_DIR);
+    let html = Html::parse_fragment(html);
+    let mut buffer = String::new();
+    for node in html.tree.nodes() {
+        if let scraper::Node::Text(text) = node.value() {
+            buffer.push_str(text);
+        }
     }
 
-    let file_name = format!("{}.json", client.id());
-    let path = dir.join(file_name);
+    buffer
+}
+
+/// Helper function to load saved credentials from `store`, keyed by the client
+/// id. Runs through the authorization flow if there are none.
+/// SHOULD ONLY BE USED FOR EXAMPLES AND TESTS
+pub async fn load_credentials<S>(
+    client: &mut impl ApiClient,
+    store: &S,
+    scopes: &[String],
+    use_pkce: bool,
+) where
+    S: CredentialStore + Clone + 'static,
+{
+    let id = client.id();
 
     // Setup a refresh callback when a new token is needed.
-    // When called this will save the new credentials to disk
+    // When called this will save the new credentials to the store
     {
-        let path = path.clone();
+        let id = id.clone();
+        let store = store.clone();
         let mut watcher = client.watch_on_refresh();
         tokio::spawn(async move {
             loop {
@@ -34,28 +53,26 @@ pub async fn load_credentials(client: &mut impl ApiClient, scopes: &[String], us
                 if watcher.changed().await.is_err() {
                     break;
                 }
-                let new_creds = watcher.borrow_and_update();
+                let new_creds = watcher.borrow_and_update().clone();
                 // save new credentials.
-                let _ = new_creds.save_to_file(path.to_path_buf());
+                let _ = store.save(&id, &new_creds).await;
             }
         });
     }
 
-    // Load from file system (if exists) or run through token authorization process.
-    let credentials = if path.exists() {
-        let saved: Credentials = serde_json::from_str(&std::fs::read_to_string(path).unwrap())
-            .expect("Unable to deserialize token");
-
-        saved
-    } else {
-        let token = get_token(client, scopes, use_pkce)
-            .await
-            .expect("Unable to request token");
-
-        let mut saved = Credentials::default();
-        saved.refresh_token(&token);
-        let _ = saved.save_to_file(path.to_path_buf());
-        saved
+    // Load from the store (if saved) or run through token authorization process.
+    let credentials = match store.load(&id).await.expect("Unable to load credentials") {
+        Some(saved) => saved,
+        None => {
+            let token = get_token(client, scopes, use_pkce)
+                .await
+                .expect("Unable to request token");
+
+            let mut saved = Credentials::default();
+            saved.refresh_token(&token);
+            let _ = store.save(&id, &saved).await;
+            saved
+        }
     };
 
     let _ = client.set_credentials(&credentials);
@@ -127,7 +144,7 @@ pub async fn get_token(
             request.csrf_token.secret()
         );
 
-        client.token_exchange(&code, request.pkce_verifier).await
+        Ok(client.token_exchange(&code, request.pkce_verifier).await?)
     } else {
         Err(anyhow!("Invalid request"))
     }
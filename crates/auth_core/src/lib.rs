@@ -3,20 +3,34 @@ use std::path::PathBuf;
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use oauth2::basic::{BasicClient, BasicTokenResponse};
-use oauth2::TokenResponse;
+use oauth2::basic::{BasicClient, BasicErrorResponse, BasicErrorResponseType, BasicTokenResponse};
 pub use oauth2::{AccessToken, RefreshToken};
 use oauth2::{AuthUrl, ClientId, ClientSecret, RedirectUrl, RevocationUrl, TokenUrl};
 use oauth2::{CsrfToken, PkceCodeChallenge};
-use reqwest::{header, Client, StatusCode};
+use oauth2::{RequestTokenError, TokenResponse};
+pub use paginate::paginate_stream;
+use reqwest::{header, Client, Method, StatusCode};
+pub use retry::RetryPolicy;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::sync::Arc;
+use std::time::Instant;
 use thiserror::Error;
-use tokio::sync::watch;
+use tokio::sync::{watch, Mutex};
+use tracing::Instrument;
 use url::Url;
 
+pub mod export;
 pub mod helpers;
-const DEFAULT_USER_AGENT: &str = "spyglass-search";
+#[cfg(feature = "test-util")]
+pub mod mock;
+pub mod paginate;
+pub mod retry;
+pub mod store;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+/// User agent sent by clients unless overridden with `set_user_agent`.
+pub const DEFAULT_USER_AGENT: &str = "spyglass-search";
 
 pub type ApiClientBox = Box<dyn ApiClient>;
 
@@ -28,26 +42,195 @@ pub enum ApiError {
     BadRequest(String),
     #[error(transparent)]
     RequestError(#[from] reqwest::Error),
+    /// Request error along with the endpoint that failed. Query params are
+    /// stripped from the url since they may contain secrets.
+    #[error("{method} {url} failed: {source}")]
+    EndpointError {
+        method: reqwest::Method,
+        url: String,
+        source: reqwest::Error,
+    },
+    /// Error status returned by the API, along with the response body which
+    /// usually explains what went wrong, e.g. HubSpot's `category` field.
+    #[error("{} returned {status}: {body}", .url.as_deref().unwrap_or("Request"))]
+    ApiStatus {
+        status: u16,
+        body: String,
+        /// Set by `with_endpoint`, without query params.
+        url: Option<String>,
+    },
+    /// The token used to resume an incremental sync (a Google sync token or a
+    /// Microsoft Graph delta token) has expired or been invalidated, a full
+    /// sync is needed to get a new one.
+    #[error("Incremental sync token is no longer valid, a full sync is required")]
+    SyncTokenExpired,
+    /// Response body wasn't valid JSON, e.g. an HTML error page. `body` holds
+    /// the start of the response to show what was returned instead.
+    #[error("Invalid JSON response: {source}, body: {body}")]
+    InvalidJson {
+        source: serde_json::Error,
+        body: String,
+    },
     #[error(transparent)]
     Other(#[from] anyhow::Error),
     #[error("Serialization error: {0}")]
     SerdeError(#[from] serde_json::Error),
 }
 
+/// Failure to exchange a code or refresh token for an access token.
+#[derive(Debug, Error)]
+pub enum TokenError {
+    /// The code or refresh token is invalid, expired or was revoked, the user
+    /// needs to authorize again.
+    #[error("invalid_grant{}", .0.as_deref().map(|desc| format!(": {desc}")).unwrap_or_default())]
+    InvalidGrant(Option<String>),
+    #[error("Network error: {0}")]
+    Network(String),
+    /// Any other error returned by the token endpoint, e.g. "invalid_client".
+    #[error("{error}{}", .description.as_deref().map(|desc| format!(": {desc}")).unwrap_or_default())]
+    Server {
+        error: String,
+        description: Option<String>,
+    },
+    /// The token endpoint's response couldn't be parsed.
+    #[error("Unable to parse token response: {0}")]
+    Parse(String),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl<RE> From<RequestTokenError<RE, BasicErrorResponse>> for TokenError
+where
+    RE: std::error::Error + 'static,
+{
+    fn from(err: RequestTokenError<RE, BasicErrorResponse>) -> Self {
+        match err {
+            RequestTokenError::ServerResponse(resp) => {
+                let description = resp.error_description().cloned();
+                match resp.error() {
+                    BasicErrorResponseType::InvalidGrant => TokenError::InvalidGrant(description),
+                    error => TokenError::Server {
+                        error: error.as_ref().to_string(),
+                        description,
+                    },
+                }
+            }
+            RequestTokenError::Request(err) => TokenError::Network(err.to_string()),
+            RequestTokenError::Parse(err, body) => {
+                TokenError::Parse(format!("{err}, body: {}", String::from_utf8_lossy(&body)))
+            }
+            RequestTokenError::Other(msg) => TokenError::Server {
+                error: msg,
+                description: None,
+            },
+        }
+    }
+}
+
+impl ApiError {
+    /// Attach the endpoint to request errors so logs show which call failed.
+    pub fn with_endpoint(self, method: reqwest::Method, endpoint: &str) -> Self {
+        let url = endpoint
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        match self {
+            ApiError::RequestError(err) => ApiError::EndpointError {
+                method,
+                url,
+                source: err.without_url(),
+            },
+            ApiError::ApiStatus { status, body, .. } => ApiError::ApiStatus {
+                status,
+                body,
+                url: Some(format!("{method} {url}")),
+            },
+            err => err,
+        }
+    }
+
+    /// HTTP status of the failed request, if there was a response.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            ApiError::ApiStatus { status, .. } => StatusCode::from_u16(*status).ok(),
+            _ => self.request_error().and_then(|err| err.status()),
+        }
+    }
+
+    fn request_error(&self) -> Option<&reqwest::Error> {
+        match self {
+            ApiError::RequestError(err) | ApiError::EndpointError { source: err, .. } => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Whether the user needs to re-authenticate, e.g. the token was revoked or
+    /// the refresh token is no longer valid (`invalid_grant`).
+    pub fn is_auth_error(&self) -> bool {
+        match self {
+            ApiError::AuthError(_) => true,
+            ApiError::RequestError(_)
+            | ApiError::EndpointError { .. }
+            | ApiError::ApiStatus { .. } => self.status() == Some(StatusCode::UNAUTHORIZED),
+            ApiError::Other(err) => {
+                if let Some(err) = err.downcast_ref::<TokenError>() {
+                    matches!(err, TokenError::InvalidGrant(_))
+                } else if let Some(RequestTokenError::ServerResponse(resp)) = err.downcast_ref::<
+                    RequestTokenError<oauth2::reqwest::Error<reqwest::Error>, BasicErrorResponse>,
+                >() {
+                    *resp.error() == BasicErrorResponseType::InvalidGrant
+                } else {
+                    false
+                }
+            }
+            ApiError::BadRequest(_)
+            | ApiError::SerdeError(_)
+            | ApiError::InvalidJson { .. }
+            | ApiError::SyncTokenExpired => false,
+        }
+    }
+
+    /// Whether the request may succeed if retried later, i.e. rate limits (429),
+    /// server errors (5xx) and transient network errors.
+    pub fn is_retryable(&self) -> bool {
+        if let Some(status) = self.status() {
+            status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+        } else {
+            self.request_error()
+                .is_some_and(|err| err.is_timeout() || err.is_connect())
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct AuthorizeOptions {
     pub pkce: bool,
     pub extra_params: Vec<(String, String)>,
 }
 
+/// Basic profile of the authenticated account. `metadata` holds the full
+/// service-specific user object.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct AccountInfo {
+    pub id: String,
+    pub display_name: String,
+    pub email: Option<String>,
+    pub avatar_url: Option<String>,
+    pub metadata: Value,
+}
+
 #[async_trait]
 pub trait ApiClient {
     /// Unique identifier for this API client.
     fn id(&self) -> String;
-    /// Authenticated account/user identifier.
+    /// Authenticated account/user identifier. Implementations should cache this
+    /// value since it's used internally by API calls & is not expected to change.
     async fn account_id(&mut self) -> Result<String>;
     /// Authenticated account optional metadata
     async fn account_metadata(&mut self) -> Option<Value>;
+    /// Profile of the authenticated account, normalized across services.
+    async fn account_info(&mut self) -> Result<AccountInfo>;
     /// Begin OAuth process w/ list of scopes
     fn authorize(&self, scopes: &[String], options: &AuthorizeOptions) -> AuthorizationRequest;
     /// Get the current credentials
@@ -57,48 +240,98 @@ pub trait ApiClient {
 
     /// Update credentials used by this ApiClient
     fn set_credentials(&mut self, credentials: &Credentials) -> Result<()>;
+    /// Receive credentials whenever they are refreshed. Cloned clients share the
+    /// same channel, so a refresh by any clone is seen by every receiver. Each
+    /// clone keeps its own `credentials` until `set_credentials` is called.
     fn watch_on_refresh(&mut self) -> watch::Receiver<Credentials>;
+    /// Lock held while refreshing credentials. Clones share the same lock so
+    /// only one token exchange is in flight at a time, which matters for
+    /// providers that rotate refresh tokens on use.
+    fn refresh_lock(&self) -> Arc<Mutex<()>>;
 
     /// Handle a token exchange
     async fn token_exchange(
         &self,
         code: &str,
         pkce_verifier: Option<String>,
-    ) -> Result<BasicTokenResponse>;
-    async fn refresh_credentials(&mut self) -> Result<()>;
+    ) -> Result<BasicTokenResponse, TokenError>;
+    async fn refresh_credentials(&mut self) -> Result<(), TokenError>;
+    /// Invalidate the grant with the provider, e.g. when the user logs out.
+    async fn revoke(&self) -> Result<()> {
+        Err(anyhow::anyhow!("{}: revocation not supported", self.id()))
+    }
+
+    /// Retry policy used by `call` & `call_json`, defaults to no retries.
+    fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy::default()
+    }
+    /// Retry failed requests according to `policy`. Clients that don't store
+    /// a policy ignore this.
+    fn set_retry_policy(&mut self, _policy: RetryPolicy) {
+        log::warn!("{} does not support retry policies", self.id());
+    }
 
     /// Utility function to get a valid HTTP client after checking a credential
     /// for expiration and refreshing as necessary.
     async fn get_check_client(&mut self) -> Result<Client, ApiError> {
         // See if the token is expired
         if self.credentials().is_expired() {
-            log::debug!("Refreshing expired token");
-            if let Err(err) = self.refresh_credentials().await {
-                return Err(ApiError::AuthError(format!(
-                    "Unable to refresh credentials: {err}"
-                )));
+            let span = tracing::debug_span!(
+                "refresh_credentials",
+                provider = %self.id(),
+                outcome = tracing::field::Empty,
+                elapsed_ms = tracing::field::Empty,
+            );
+            let start = Instant::now();
+            let result = async {
+                let lock = self.refresh_lock();
+                let _guard = lock.lock().await;
+
+                // Another clone may have refreshed the credentials while we waited.
+                let latest = self.watch_on_refresh().borrow().clone();
+                if !latest.is_expired() {
+                    log::debug!("Using credentials refreshed by another client");
+                    if let Err(err) = self.set_credentials(&latest) {
+                        return Err(ApiError::AuthError(format!(
+                            "Unable to update credentials: {err}"
+                        )));
+                    }
+                    Ok("reused")
+                } else {
+                    log::debug!("Refreshing expired token");
+                    match self.refresh_credentials().await {
+                        Ok(()) => Ok("refreshed"),
+                        // Worth retrying later, the grant itself may still be valid.
+                        Err(err @ TokenError::Network(_)) => Err(anyhow::Error::from(err).into()),
+                        Err(err) => Err(ApiError::AuthError(format!(
+                            "Unable to refresh credentials: {err}"
+                        ))),
+                    }
+                }
             }
+            .instrument(span.clone())
+            .await;
+
+            span.record("elapsed_ms", start.elapsed().as_millis() as u64);
+            span.record("outcome", result.as_ref().copied().unwrap_or("failed"));
+            span.in_scope(|| match &result {
+                Ok(_) => tracing::debug!("credentials updated"),
+                Err(err) => tracing::warn!(error = %err, "unable to refresh credentials"),
+            });
+            result?;
         }
 
         Ok(self.http_client())
     }
 
     /// Utility functions to call RESTful api endpoints
+    #[allow(clippy::ptr_arg)]
     async fn call(
         &mut self,
         endpoint: &str,
         query: &Vec<(String, String)>,
     ) -> Result<reqwest::Response, ApiError> {
-        let client = self.get_check_client().await?;
-        let mut req = client.get(endpoint);
-        if !query.is_empty() {
-            req = req.query(query);
-        }
-
-        match req.send().await {
-            Ok(resp) => Ok(resp),
-            Err(err) => Err(err.into()),
-        }
+        call_with_retry(self, endpoint, query).await
     }
 
     async fn call_json(
@@ -106,21 +339,7 @@ pub trait ApiClient {
         endpoint: &str,
         query: &[(String, String)],
     ) -> anyhow::Result<serde_json::Value, ApiError> {
-        let resp = self.call(endpoint, &query.to_vec()).await?;
-        match resp.error_for_status() {
-            Ok(resp) => match resp.json().await {
-                Ok(res) => Ok(res),
-                Err(err) => Err(err.into()),
-            },
-            // Any status code from 400..599
-            Err(err) => {
-                if let Some(StatusCode::UNAUTHORIZED) = err.status() {
-                    Err(ApiError::AuthError("Unauthorized".to_owned()))
-                } else {
-                    Err(err.into())
-                }
-            }
-        }
+        get_json(self, endpoint, query).await
     }
 
     async fn post_json(
@@ -128,24 +347,272 @@ pub trait ApiClient {
         endpoint: &str,
         body: serde_json::Value,
     ) -> anyhow::Result<serde_json::Value, ApiError> {
-        let client = self.get_check_client().await?;
-        let resp = client.post(endpoint).json(&body).send().await?;
+        send_json(self, Method::POST, endpoint, Some(body)).await
+    }
 
-        match resp.error_for_status() {
-            Ok(resp) => match resp.json().await {
-                Ok(res) => Ok(res),
-                Err(err) => Err(err.into()),
-            },
-            // Any status code from 400..599
-            Err(err) => {
-                if let Some(StatusCode::UNAUTHORIZED) = err.status() {
-                    Err(ApiError::AuthError("Unauthorized".to_owned()))
-                } else {
-                    Err(err.into())
+    async fn put_json(
+        &mut self,
+        endpoint: &str,
+        body: serde_json::Value,
+    ) -> anyhow::Result<serde_json::Value, ApiError> {
+        send_json(self, Method::PUT, endpoint, Some(body)).await
+    }
+
+    /// Returns `Value::Null` if the API responds without a body, e.g. a 204.
+    async fn delete(&mut self, endpoint: &str) -> anyhow::Result<serde_json::Value, ApiError> {
+        send_json(self, Method::DELETE, endpoint, None).await
+    }
+}
+
+/// Default `ApiClient::call_json`: `call` & parse the JSON response. Exposed
+/// so clients overriding `call_json` to check provider specific error bodies
+/// can still reuse it.
+pub async fn get_json<C>(
+    client: &mut C,
+    endpoint: &str,
+    query: &[(String, String)],
+) -> Result<serde_json::Value, ApiError>
+where
+    C: ApiClient + Send + ?Sized,
+{
+    let span = tracing::debug_span!(
+        "call_json",
+        provider = %client.id(),
+        endpoint = %redact_url(endpoint, query),
+    );
+    async {
+        let resp = client.call(endpoint, &query.to_vec()).await?;
+        json_response(resp)
+            .await
+            .map_err(|err| err.with_endpoint(Method::GET, endpoint))
+    }
+    .instrument(span)
+    .await
+}
+
+/// Default `ApiClient::post_json`, `put_json` & `delete`: sends `body` as JSON
+/// and parses the JSON response. Exposed for the same reason as `get_json`.
+pub async fn send_json<C>(
+    client: &mut C,
+    method: Method,
+    endpoint: &str,
+    body: Option<serde_json::Value>,
+) -> Result<serde_json::Value, ApiError>
+where
+    C: ApiClient + Send + ?Sized,
+{
+    let span = tracing::debug_span!(
+        "send_json",
+        provider = %client.id(),
+        method = %method,
+        endpoint = %redact_url(endpoint, &[]),
+        status = tracing::field::Empty,
+        elapsed_ms = tracing::field::Empty,
+    );
+    let start = Instant::now();
+    async {
+        let http = client.get_check_client().await?;
+        let mut req = http.request(method.clone(), endpoint);
+        if let Some(body) = &body {
+            req = req.json(body);
+        }
+
+        let resp = req
+            .send()
+            .await
+            .map_err(|err| ApiError::from(err).with_endpoint(method.clone(), endpoint))?;
+        let span = tracing::Span::current();
+        span.record("status", resp.status().as_u16());
+        span.record("elapsed_ms", start.elapsed().as_millis() as u64);
+        json_response(resp)
+            .await
+            .map_err(|err| err.with_endpoint(method.clone(), endpoint))
+    }
+    .instrument(span)
+    .await
+}
+
+/// Default `ApiClient::call`: GET `endpoint`, retrying transient failures
+/// according to the client's `RetryPolicy`. Exposed so clients overriding
+/// `call` to inspect responses can still reuse it.
+pub async fn call_with_retry<C>(
+    client: &mut C,
+    endpoint: &str,
+    query: &[(String, String)],
+) -> Result<reqwest::Response, ApiError>
+where
+    C: ApiClient + Send + ?Sized,
+{
+    call_with_headers(client, endpoint, query, header::HeaderMap::new()).await
+}
+
+/// Same as `call_with_retry`, with extra `headers` sent on every attempt, e.g.
+/// a provider specific header some queries require.
+pub async fn call_with_headers<C>(
+    client: &mut C,
+    endpoint: &str,
+    query: &[(String, String)],
+    headers: header::HeaderMap,
+) -> Result<reqwest::Response, ApiError>
+where
+    C: ApiClient + Send + ?Sized,
+{
+    let span = tracing::debug_span!(
+        "api_call",
+        provider = %client.id(),
+        method = "GET",
+        endpoint = %redact_url(endpoint, query),
+        status = tracing::field::Empty,
+        attempts = tracing::field::Empty,
+        elapsed_ms = tracing::field::Empty,
+    );
+    let start = Instant::now();
+    let policy = client.retry_policy();
+    let mut attempt = 0;
+    let result = async {
+        loop {
+            // Checked on every attempt in case the token expires while waiting.
+            let http = client.get_check_client().await?;
+            let mut req = http.get(endpoint).headers(headers.clone());
+            if !query.is_empty() {
+                req = req.query(query);
+            }
+
+            let retry = attempt < policy.max_retries;
+            let delay = match req.send().await {
+                Ok(resp) if retry && retry::is_retryable_status(resp.status()) => {
+                    policy.delay(attempt, Some(resp.headers()))
+                }
+                Err(err) if retry && (err.is_timeout() || err.is_connect()) => {
+                    policy.delay(attempt, None)
                 }
+                Ok(resp) => return Ok(resp),
+                Err(err) => return Err(ApiError::from(err).with_endpoint(Method::GET, endpoint)),
+            };
+
+            attempt += 1;
+            log::debug!(
+                "Retrying {endpoint} in {delay:?} ({attempt}/{})",
+                policy.max_retries
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+    .instrument(span.clone())
+    .await;
+
+    span.record("attempts", attempt + 1);
+    span.record("elapsed_ms", start.elapsed().as_millis() as u64);
+    span.in_scope(|| match &result {
+        Ok(resp) => {
+            span.record("status", resp.status().as_u16());
+            tracing::debug!("request complete");
+        }
+        Err(err) => tracing::debug!(error = %err, "request failed"),
+    });
+    result
+}
+
+/// Query params whose values are never logged, e.g. "access_token" or "key".
+const SENSITIVE_PARAMS: &[&str] = &["token", "secret", "key", "code", "password", "signature"];
+
+/// Full url of a request for logging, with the values of any params that may
+/// contain credentials replaced.
+fn redact_url(endpoint: &str, query: &[(String, String)]) -> String {
+    let Ok(mut url) = Url::parse(endpoint) else {
+        // Can't tell which params are safe, so drop them all.
+        return endpoint
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default()
+            .to_string();
+    };
+
+    let params = url
+        .query_pairs()
+        .into_owned()
+        .chain(query.iter().cloned())
+        .collect::<Vec<_>>();
+    url.set_query(None);
+    url.set_fragment(None);
+    if !params.is_empty() {
+        let mut pairs = url.query_pairs_mut();
+        for (key, value) in params {
+            let lower = key.to_lowercase();
+            if SENSITIVE_PARAMS.iter().any(|param| lower.contains(param)) {
+                pairs.append_pair(&key, "REDACTED");
+            } else {
+                pairs.append_pair(&key, &value);
             }
         }
     }
+
+    url.to_string()
+}
+
+/// Parse a JSON response body, converting any error status codes into an `ApiError`.
+pub async fn json_response(resp: reqwest::Response) -> Result<serde_json::Value, ApiError> {
+    let resp = check_status(resp).await?;
+    if resp.status() == StatusCode::NO_CONTENT {
+        return Ok(serde_json::Value::Null);
+    }
+
+    let body = resp.text().await?;
+    parse_json(&body)
+}
+
+/// Map error statuses to an `ApiError`, for responses that aren't JSON, e.g.
+/// file downloads. 401 is an `AuthError`, any other status from 400..599 is
+/// an `ApiStatus` keeping the body since most APIs return a machine-readable
+/// description of the error.
+pub async fn check_status(resp: reqwest::Response) -> Result<reqwest::Response, ApiError> {
+    let status = resp.status();
+    if status == StatusCode::UNAUTHORIZED {
+        return Err(ApiError::AuthError("Unauthorized".to_owned()));
+    }
+
+    if status.is_client_error() || status.is_server_error() {
+        let body = resp.text().await.unwrap_or_default();
+        return Err(ApiError::ApiStatus {
+            status: status.as_u16(),
+            body,
+            url: None,
+        });
+    }
+
+    Ok(resp)
+}
+
+/// Max number of bytes of the body kept in `ApiError::InvalidJson`.
+const INVALID_JSON_BODY_LIMIT: usize = 512;
+
+/// Parse a response body as JSON. Providers sometimes reply with HTML or form
+/// encoded bodies, so the start of the body is kept in the error.
+pub fn parse_json(body: &str) -> Result<serde_json::Value, ApiError> {
+    serde_json::from_str(body).map_err(|source| {
+        let mut end = body.len().min(INVALID_JSON_BODY_LIMIT);
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        ApiError::InvalidJson {
+            source,
+            body: body[..end].to_string(),
+        }
+    })
+}
+
+/// Common view over content from any provider (issues, posts, files, etc.) so
+/// they can be indexed the same way.
+pub trait Document {
+    /// Provider specific unique identifier.
+    fn id(&self) -> String;
+    fn title(&self) -> String;
+    /// Plain-text content of the document.
+    fn body_text(&self) -> String;
+    /// Link to view the document in a browser, if available.
+    fn url(&self) -> Option<String>;
+    fn updated_at(&self) -> Option<DateTime<Utc>>;
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -154,6 +621,14 @@ pub struct Credentials {
     pub access_token: AccessToken,
     pub refresh_token: Option<RefreshToken>,
     pub expires_in: Option<std::time::Duration>,
+    /// How long before the actual expiry the token is considered expired, so
+    /// it's refreshed before requests start failing.
+    #[serde(default = "default_refresh_skew")]
+    pub refresh_skew: std::time::Duration,
+}
+
+fn default_refresh_skew() -> std::time::Duration {
+    std::time::Duration::from_secs(60)
 }
 
 impl Default for Credentials {
@@ -163,6 +638,7 @@ impl Default for Credentials {
             access_token: AccessToken::new("".into()),
             refresh_token: None,
             expires_in: None,
+            refresh_skew: default_refresh_skew(),
         }
     }
 }
@@ -176,16 +652,35 @@ pub struct AuthorizationRequest {
 }
 
 impl Credentials {
+    /// True if the token expires within `refresh_skew`.
     pub fn is_expired(&self) -> bool {
-        if let Some(duration) = self.expires_in {
-            let now = Utc::now();
-            let dur = chrono::Duration::from_std(duration).expect("Unable to convert duration");
-            return (now - self.requested_at) > dur;
+        if let Some(expires_at) = self.expires_at() {
+            let skew = chrono::Duration::from_std(self.refresh_skew)
+                .unwrap_or_else(|_| chrono::Duration::zero());
+            return Utc::now() + skew > expires_at;
         }
 
         false
     }
 
+    /// Absolute time at which the access token expires, if known.
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_in.map(|duration| {
+            let dur = chrono::Duration::from_std(duration).expect("Unable to convert duration");
+            self.requested_at + dur
+        })
+    }
+
+    /// Time left until the access token expires, if known. Returns a zero duration
+    /// if the token has already expired.
+    pub fn time_until_expiry(&self) -> Option<std::time::Duration> {
+        self.expires_at().map(|expires_at| {
+            (expires_at - Utc::now())
+                .to_std()
+                .unwrap_or(std::time::Duration::ZERO)
+        })
+    }
+
     pub fn refresh_token(&mut self, resp: &BasicTokenResponse) {
         self.requested_at = Utc::now();
         self.access_token = resp.access_token().clone();
@@ -199,18 +694,162 @@ impl Credentials {
     }
 }
 
+/// Request settings every provider client keeps & reapplies whenever its HTTP
+/// client is rebuilt, e.g. after a credential refresh or `set_user_agent`.
+#[derive(Clone, Debug)]
+pub struct ClientSettings {
+    /// Returned by `ApiClient::retry_policy`.
+    pub retry_policy: RetryPolicy,
+    pub user_agent: String,
+    pub config: ClientConfig,
+}
+
+impl Default for ClientSettings {
+    fn default() -> Self {
+        ClientSettings::new(ClientConfig::default())
+    }
+}
+
+impl ClientSettings {
+    /// No retries & the `DEFAULT_USER_AGENT`.
+    pub fn new(config: ClientConfig) -> Self {
+        ClientSettings {
+            retry_policy: RetryPolicy::default(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            config,
+        }
+    }
+
+    /// A `reqwest::ClientBuilder` with the config & user agent applied, for
+    /// requests that must not send the bearer token, e.g. pre-signed URLs.
+    pub fn client_builder(&self) -> Result<reqwest::ClientBuilder> {
+        Ok(self.config.client_builder()?.user_agent(&self.user_agent))
+    }
+
+    /// HTTP client sending `token` as the bearer token w/ every request.
+    pub fn auth_http_client(&self, token: &str) -> Result<Client> {
+        auth_http_client_with_config(token, &self.user_agent, &self.config)
+    }
+}
+
+/// HTTP settings applied to every request made by a client. Clients keep their
+/// config so it's reapplied whenever the HTTP client is rebuilt, e.g. after a
+/// credential refresh.
+#[derive(Clone, Debug)]
+pub struct ClientConfig {
+    /// Total time allowed for a request, including reading the response body.
+    /// Unset by default since it would cut off large downloads/uploads.
+    pub timeout: Option<std::time::Duration>,
+    /// Time allowed to establish a connection, defaults to 30s.
+    pub connect_timeout: Option<std::time::Duration>,
+    /// How long idle connections are kept open, defaults to reqwest's 90s.
+    pub pool_idle_timeout: Option<std::time::Duration>,
+    /// Send all requests through this HTTP/HTTPS proxy, e.g. "http://proxy:8080".
+    /// When unset, the `HTTP_PROXY`/`HTTPS_PROXY` environment variables are used.
+    pub proxy: Option<String>,
+    /// Comma separated hosts that bypass `proxy`, e.g. "localhost,.internal".
+    /// Defaults to the `NO_PROXY` environment variable.
+    pub no_proxy: Option<String>,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        ClientConfig {
+            timeout: None,
+            connect_timeout: Some(std::time::Duration::from_secs(30)),
+            pool_idle_timeout: None,
+            proxy: None,
+            no_proxy: None,
+        }
+    }
+}
+
+impl ClientConfig {
+    /// A `reqwest::ClientBuilder` with this config applied. Fails if `proxy`
+    /// isn't a valid URL.
+    pub fn client_builder(&self) -> Result<reqwest::ClientBuilder> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(proxy) = &self.proxy {
+            let proxy = Url::parse(proxy)?;
+            let no_proxy = self
+                .no_proxy
+                .clone()
+                .or_else(|| std::env::var("NO_PROXY").ok())
+                .or_else(|| std::env::var("no_proxy").ok())
+                .unwrap_or_default();
+            builder = builder.proxy(reqwest::Proxy::custom(move |url| match url.host_str() {
+                Some(host) if bypass_proxy(&no_proxy, host) => None,
+                _ => Some(proxy.clone()),
+            }));
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        Ok(builder)
+    }
+}
+
+/// Whether `host` matches an entry in a `NO_PROXY` style list. Entries match
+/// the host itself & its subdomains, "*" matches everything.
+fn bypass_proxy(no_proxy: &str, host: &str) -> bool {
+    no_proxy
+        .split(',')
+        .map(|entry| entry.trim().trim_start_matches('.'))
+        .filter(|entry| !entry.is_empty())
+        .any(|entry| {
+            entry == "*"
+                || host.eq_ignore_ascii_case(entry)
+                || host
+                    .to_lowercase()
+                    .ends_with(&format!(".{}", entry.to_lowercase()))
+        })
+}
+
 pub fn auth_http_client(token: &str) -> Result<Client> {
+    auth_http_client_with_user_agent(token, DEFAULT_USER_AGENT)
+}
+
+/// Same as `auth_http_client`, for APIs that require a specific user agent.
+pub fn auth_http_client_with_user_agent(token: &str, user_agent: &str) -> Result<Client> {
+    auth_http_client_with_config(token, user_agent, &ClientConfig::default())
+}
+
+/// Same as `auth_http_client_with_user_agent` with timeouts etc. from `config`.
+pub fn auth_http_client_with_config(
+    token: &str,
+    user_agent: &str,
+    config: &ClientConfig,
+) -> Result<Client> {
     let mut headers = header::HeaderMap::new();
     let value = header::HeaderValue::from_str(&format!("Bearer {token}"))?;
     headers.insert("Authorization", value);
 
-    Ok(reqwest::Client::builder()
-        .user_agent(DEFAULT_USER_AGENT)
+    Ok(config
+        .client_builder()?
+        .user_agent(user_agent)
         .default_headers(headers)
         .build()?)
 }
 
-#[derive(Debug, Default)]
+/// HTTP client for OAuth token requests that sets the `user_agent`, e.g.
+/// `.request_async(|req| oauth_http_client(req, user_agent))`.
+pub async fn oauth_http_client(
+    mut request: oauth2::HttpRequest,
+    user_agent: String,
+) -> Result<oauth2::HttpResponse, oauth2::reqwest::Error<reqwest::Error>> {
+    if let Ok(user_agent) = header::HeaderValue::from_str(&user_agent) {
+        request.headers.insert(header::USER_AGENT, user_agent);
+    }
+    oauth2::reqwest::async_http_client(request).await
+}
+
+#[derive(Clone, Debug, Default)]
 pub struct OAuthParams {
     pub auth_url: String,
     pub token_url: Option<String>,
@@ -220,31 +859,318 @@ pub struct OAuthParams {
     pub redirect_url: Option<String>,
 }
 
-pub fn oauth_client(params: &OAuthParams) -> BasicClient {
-    let auth_url =
-        AuthUrl::new(params.auth_url.clone()).expect("Invalid authorization endpoint URL");
+pub fn oauth_client(params: &OAuthParams) -> Result<BasicClient, ApiError> {
+    let auth_url = AuthUrl::new(params.auth_url.clone()).map_err(|err| {
+        ApiError::BadRequest(format!(
+            "Invalid authorization endpoint URL \"{}\": {err}",
+            params.auth_url
+        ))
+    })?;
 
     let client_secret = params.client_secret.clone();
-    let token_url = params.token_url.clone();
+    let token_url = match &params.token_url {
+        Some(url) => Some(TokenUrl::new(url.to_string()).map_err(|err| {
+            ApiError::BadRequest(format!("Invalid token endpoint URL \"{url}\": {err}"))
+        })?),
+        None => None,
+    };
 
     let mut client = BasicClient::new(
         ClientId::new(params.client_id.clone()),
         client_secret.map(ClientSecret::new),
         auth_url,
-        token_url.map(|url| TokenUrl::new(url).expect("Invalid token endpoint URL")),
+        token_url,
     );
 
     if let Some(redirect_url) = &params.redirect_url {
-        client = client.set_redirect_uri(
-            RedirectUrl::new(redirect_url.to_string()).expect("Invalid redirect URL"),
-        );
+        let redirect_url = RedirectUrl::new(redirect_url.to_string()).map_err(|err| {
+            ApiError::BadRequest(format!("Invalid redirect URL \"{redirect_url}\": {err}"))
+        })?;
+        client = client.set_redirect_uri(redirect_url);
     }
 
     if let Some(revoke_url) = &params.revoke_url {
-        client = client.set_revocation_uri(
-            RevocationUrl::new(revoke_url.to_string()).expect("Invalid revocation endpoint URL"),
+        let revoke_url = RevocationUrl::new(revoke_url.to_string()).map_err(|err| {
+            ApiError::BadRequest(format!(
+                "Invalid revocation endpoint URL \"{revoke_url}\": {err}"
+            ))
+        })?;
+        client = client.set_revocation_uri(revoke_url);
+    }
+
+    Ok(client)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        bypass_proxy, oauth_client, parse_json, redact_url, ApiError, ClientConfig, Credentials,
+        OAuthParams, TokenError,
+    };
+    use chrono::Utc;
+    use oauth2::basic::{BasicErrorResponse, BasicErrorResponseType};
+    use oauth2::RequestTokenError;
+    use reqwest::Client;
+    use std::time::Duration;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn status_error(status: u16) -> ApiError {
+        let resp = oauth2::http::Response::builder()
+            .status(status)
+            .body("")
+            .expect("Invalid response");
+        reqwest::Response::from(resp)
+            .error_for_status()
+            .expect_err("Expected error status")
+            .into()
+    }
+
+    #[test]
+    fn test_is_auth_error() {
+        assert!(ApiError::AuthError("Unauthorized".into()).is_auth_error());
+        assert!(status_error(401).is_auth_error());
+        assert!(!status_error(403).is_auth_error());
+        assert!(!ApiError::BadRequest("bad".into()).is_auth_error());
+
+        let invalid_grant: RequestTokenError<
+            oauth2::reqwest::Error<reqwest::Error>,
+            BasicErrorResponse,
+        > = RequestTokenError::ServerResponse(BasicErrorResponse::new(
+            BasicErrorResponseType::InvalidGrant,
+            None,
+            None,
+        ));
+        assert!(ApiError::Other(invalid_grant.into()).is_auth_error());
+        assert!(!ApiError::Other(anyhow::anyhow!("something else")).is_auth_error());
+        assert!(ApiError::Other(TokenError::InvalidGrant(None).into()).is_auth_error());
+        assert!(!ApiError::Other(TokenError::Network("timed out".into()).into()).is_auth_error());
+    }
+
+    #[test]
+    fn test_with_endpoint() {
+        let err = status_error(503)
+            .with_endpoint(reqwest::Method::GET, "https://example.com/files?key=secret");
+        assert_eq!(
+            err.to_string(),
+            "GET https://example.com/files failed: HTTP status server error (503 Service Unavailable)"
+        );
+        assert!(err.is_retryable());
+        assert!(status_error(401)
+            .with_endpoint(reqwest::Method::GET, "https://example.com/files")
+            .is_auth_error());
+
+        // Other errors are left as is
+        let err = ApiError::BadRequest("bad".into())
+            .with_endpoint(reqwest::Method::GET, "https://example.com/files");
+        assert!(matches!(err, ApiError::BadRequest(_)));
+    }
+
+    #[test]
+    fn test_api_status() {
+        let err = ApiError::ApiStatus {
+            status: 400,
+            body: r#"{"category":"VALIDATION_ERROR"}"#.into(),
+            url: None,
+        };
+        assert_eq!(
+            err.to_string(),
+            r#"Request returned 400: {"category":"VALIDATION_ERROR"}"#
+        );
+        assert!(!err.is_retryable());
+
+        let err = err.with_endpoint(reqwest::Method::GET, "https://example.com/files?key=secret");
+        assert_eq!(
+            err.to_string(),
+            r#"GET https://example.com/files returned 400: {"category":"VALIDATION_ERROR"}"#
+        );
+        assert_eq!(err.status(), Some(reqwest::StatusCode::BAD_REQUEST));
+
+        let err = ApiError::ApiStatus {
+            status: 429,
+            body: String::new(),
+            url: None,
+        };
+        assert!(err.is_retryable());
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(status_error(429).is_retryable());
+        assert!(status_error(503).is_retryable());
+        assert!(!status_error(404).is_retryable());
+        assert!(!ApiError::AuthError("Unauthorized".into()).is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_is_retryable_network() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+            .mount(&server)
+            .await;
+
+        let err = Client::builder()
+            .timeout(Duration::from_millis(50))
+            .build()
+            .unwrap()
+            .get(server.uri())
+            .send()
+            .await
+            .expect_err("Expected a timeout");
+        assert!(ApiError::from(err).is_retryable());
+    }
+
+    #[test]
+    fn test_client_config_default() {
+        // A total timeout would cut off streamed downloads & uploads.
+        let config = ClientConfig::default();
+        assert!(config.timeout.is_none());
+        assert_eq!(config.connect_timeout, Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_bypass_proxy() {
+        let no_proxy = "localhost, .internal.example.com,10.0.0.1";
+        assert!(bypass_proxy(no_proxy, "localhost"));
+        assert!(bypass_proxy(no_proxy, "wiki.internal.example.com"));
+        assert!(bypass_proxy(no_proxy, "10.0.0.1"));
+        assert!(!bypass_proxy(no_proxy, "www.googleapis.com"));
+        assert!(!bypass_proxy(no_proxy, "notlocalhost"));
+        assert!(bypass_proxy("*", "graph.microsoft.com"));
+        assert!(!bypass_proxy("", "graph.microsoft.com"));
+    }
+
+    #[test]
+    fn test_expires_at() {
+        let creds = Credentials::default();
+        assert!(creds.expires_at().is_none());
+        assert!(creds.time_until_expiry().is_none());
+        assert!(!creds.is_expired());
+
+        let requested_at = Utc::now();
+        let creds = Credentials {
+            requested_at,
+            expires_in: Some(Duration::from_secs(3600)),
+            ..Default::default()
+        };
+        assert_eq!(
+            creds.expires_at(),
+            Some(requested_at + chrono::Duration::seconds(3600))
         );
+        assert!(!creds.is_expired());
+
+        let remaining = creds.time_until_expiry().expect("Expected expiry");
+        assert!(remaining <= Duration::from_secs(3600));
+        assert!(remaining > Duration::from_secs(3590));
+    }
+
+    #[test]
+    fn test_expires_at_boundary() {
+        // Expired just now
+        let creds = Credentials {
+            requested_at: Utc::now() - chrono::Duration::seconds(61),
+            expires_in: Some(Duration::from_secs(60)),
+            refresh_skew: Duration::ZERO,
+            ..Default::default()
+        };
+        assert!(creds.is_expired());
+        assert_eq!(creds.time_until_expiry(), Some(Duration::ZERO));
+
+        // About to expire
+        let creds = Credentials {
+            requested_at: Utc::now() - chrono::Duration::seconds(59),
+            expires_in: Some(Duration::from_secs(60)),
+            refresh_skew: Duration::ZERO,
+            ..Default::default()
+        };
+        assert!(!creds.is_expired());
+        assert!(creds
+            .time_until_expiry()
+            .is_some_and(|left| left > Duration::ZERO && left <= Duration::from_secs(1)));
+
+        // Within the default skew
+        let creds = Credentials {
+            expires_in: Some(Duration::from_secs(30)),
+            ..Default::default()
+        };
+        assert!(creds.is_expired());
+        assert!(creds
+            .time_until_expiry()
+            .is_some_and(|left| left > Duration::ZERO));
+    }
+
+    #[test]
+    fn test_refresh_skew_default() {
+        let creds: Credentials = serde_json::from_value(serde_json::json!({
+            "requested_at": "2024-02-05T08:00:00Z",
+            "access_token": "access",
+            "refresh_token": null,
+            "expires_in": { "secs": 3600, "nanos": 0 }
+        }))
+        .expect("Unable to parse credentials");
+        assert_eq!(creds.refresh_skew, Duration::from_secs(60));
     }
 
-    client
+    #[test]
+    fn test_oauth_client_invalid_urls() {
+        let params = OAuthParams {
+            client_id: "client-id".into(),
+            auth_url: "https://example.com/authorize".into(),
+            token_url: Some("https://example.com/token".into()),
+            redirect_url: Some("http://127.0.0.1:8080".into()),
+            ..Default::default()
+        };
+        assert!(oauth_client(&params).is_ok());
+
+        let invalid = OAuthParams {
+            redirect_url: Some("".into()),
+            ..params.clone()
+        };
+        assert!(matches!(
+            oauth_client(&invalid),
+            Err(ApiError::BadRequest(msg)) if msg.starts_with("Invalid redirect URL")
+        ));
+
+        let invalid = OAuthParams {
+            auth_url: "not a url".into(),
+            ..params
+        };
+        assert!(oauth_client(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_parse_json_keeps_body() {
+        let body = format!("<html>{}</html>", "é".repeat(400));
+        match parse_json(&body) {
+            Err(ApiError::InvalidJson { body: kept, .. }) => {
+                assert!(kept.starts_with("<html>"));
+                assert!(kept.len() <= 512);
+                assert!(body.starts_with(&kept));
+            }
+            res => panic!("Expected InvalidJson, got {res:?}"),
+        }
+
+        assert_eq!(parse_json("{\"ok\":true}").unwrap()["ok"], true);
+    }
+
+    #[test]
+    fn test_redact_url() {
+        let query = vec![
+            ("q".to_string(), "name".to_string()),
+            ("pageToken".to_string(), "abc".to_string()),
+        ];
+        assert_eq!(
+            redact_url(
+                "https://example.com/files?access_token=secret&fields=id",
+                &query
+            ),
+            "https://example.com/files?access_token=REDACTED&fields=id&q=name&pageToken=REDACTED"
+        );
+        assert_eq!(
+            redact_url("https://example.com/files?key=secret", &[]),
+            "https://example.com/files?key=REDACTED"
+        );
+        assert_eq!(redact_url("/files?key=secret", &[]), "/files");
+    }
 }
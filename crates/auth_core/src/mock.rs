@@ -0,0 +1,218 @@
+//! Fake `ApiClient` for testing code built on top of the provider clients
+//! without making any network requests. Enabled with the `test-util` feature.
+use std::sync::{Arc, Mutex as StdMutex};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use oauth2::basic::{BasicTokenResponse, BasicTokenType};
+use oauth2::{AccessToken, CsrfToken, EmptyExtraTokenFields};
+use reqwest::{Client, Method, StatusCode};
+use serde_json::Value;
+use tokio::sync::{watch, Mutex};
+use url::Url;
+
+use crate::{
+    json_response, AccountInfo, ApiClient, ApiError, AuthorizationRequest, AuthorizeOptions,
+    Credentials, TokenError,
+};
+
+/// Request made through a `MockApiClient`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MockCall {
+    pub method: Method,
+    pub endpoint: String,
+    pub query: Vec<(String, String)>,
+    /// JSON body sent with POST & PUT requests.
+    pub body: Option<Value>,
+}
+
+#[derive(Clone, Debug)]
+struct Expectation {
+    endpoint: String,
+    status: StatusCode,
+    response: Value,
+}
+
+/// `ApiClient` that answers requests with canned responses & records every
+/// request made. Each expectation answers a single request, the first one
+/// whose endpoint is a substring of the requested endpoint is used.
+#[derive(Clone)]
+pub struct MockApiClient {
+    credentials: Credentials,
+    expectations: Arc<StdMutex<Vec<Expectation>>>,
+    calls: Arc<StdMutex<Vec<MockCall>>>,
+    on_refresh_tx: watch::Sender<Credentials>,
+    on_refresh_rx: watch::Receiver<Credentials>,
+    refresh_lock: Arc<Mutex<()>>,
+}
+
+impl Default for MockApiClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockApiClient {
+    pub fn new() -> Self {
+        let credentials = Credentials::default();
+        let (tx, rx) = watch::channel(credentials.clone());
+        MockApiClient {
+            credentials,
+            expectations: Arc::new(StdMutex::new(Vec::new())),
+            calls: Arc::new(StdMutex::new(Vec::new())),
+            on_refresh_tx: tx,
+            on_refresh_rx: rx,
+            refresh_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Respond to the next request to an endpoint containing `endpoint` with
+    /// `response`.
+    pub fn expect_call(&self, endpoint: &str, response: Value) {
+        self.expect_status(endpoint, StatusCode::OK, response);
+    }
+
+    /// Same as `expect_call` but responds with an error `status`.
+    pub fn expect_status(&self, endpoint: &str, status: StatusCode, response: Value) {
+        self.expectations.lock().unwrap().push(Expectation {
+            endpoint: endpoint.to_string(),
+            status,
+            response,
+        });
+    }
+
+    /// Every request made so far, in order.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn respond(
+        &self,
+        method: Method,
+        endpoint: &str,
+        query: &[(String, String)],
+        body: Option<Value>,
+    ) -> Result<reqwest::Response, ApiError> {
+        self.calls.lock().unwrap().push(MockCall {
+            method,
+            endpoint: endpoint.to_string(),
+            query: query.to_vec(),
+            body,
+        });
+
+        let mut expectations = self.expectations.lock().unwrap();
+        let Some(idx) = expectations
+            .iter()
+            .position(|exp| endpoint.contains(&exp.endpoint))
+        else {
+            return Err(ApiError::BadRequest(format!(
+                "No mock response for {endpoint}"
+            )));
+        };
+
+        let expectation = expectations.remove(idx);
+        let resp = oauth2::http::Response::builder()
+            .status(expectation.status)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(expectation.response.to_string())
+            .map_err(|err| ApiError::Other(err.into()))?;
+        Ok(reqwest::Response::from(resp))
+    }
+}
+
+#[async_trait]
+impl ApiClient for MockApiClient {
+    fn id(&self) -> String {
+        "mock".to_string()
+    }
+
+    async fn account_id(&mut self) -> Result<String> {
+        Ok("mock-account".to_string())
+    }
+
+    async fn account_metadata(&mut self) -> Option<Value> {
+        None
+    }
+
+    async fn account_info(&mut self) -> Result<AccountInfo> {
+        Ok(AccountInfo {
+            id: "mock-account".to_string(),
+            display_name: "Mock Account".to_string(),
+            ..Default::default()
+        })
+    }
+
+    fn authorize(&self, _scopes: &[String], _options: &AuthorizeOptions) -> AuthorizationRequest {
+        AuthorizationRequest {
+            url: Url::parse("http://localhost/authorize").expect("Invalid url"),
+            csrf_token: CsrfToken::new_random(),
+            pkce_challenge: None,
+            pkce_verifier: None,
+        }
+    }
+
+    fn credentials(&self) -> Credentials {
+        self.credentials.clone()
+    }
+
+    fn http_client(&self) -> Client {
+        Client::new()
+    }
+
+    fn set_credentials(&mut self, credentials: &Credentials) -> Result<()> {
+        self.credentials = credentials.clone();
+        Ok(())
+    }
+
+    fn watch_on_refresh(&mut self) -> watch::Receiver<Credentials> {
+        self.on_refresh_rx.clone()
+    }
+
+    fn refresh_lock(&self) -> Arc<Mutex<()>> {
+        self.refresh_lock.clone()
+    }
+
+    async fn token_exchange(
+        &self,
+        _code: &str,
+        _pkce_verifier: Option<String>,
+    ) -> Result<BasicTokenResponse, TokenError> {
+        Ok(BasicTokenResponse::new(
+            AccessToken::new("mock-token".to_string()),
+            BasicTokenType::Bearer,
+            EmptyExtraTokenFields {},
+        ))
+    }
+
+    async fn refresh_credentials(&mut self) -> Result<(), TokenError> {
+        self.credentials.requested_at = chrono::Utc::now();
+        self.on_refresh_tx
+            .send(self.credentials.clone())
+            .map_err(anyhow::Error::from)?;
+        Ok(())
+    }
+
+    #[allow(clippy::ptr_arg)]
+    async fn call(
+        &mut self,
+        endpoint: &str,
+        query: &Vec<(String, String)>,
+    ) -> Result<reqwest::Response, ApiError> {
+        self.respond(Method::GET, endpoint, query, None)
+    }
+
+    async fn post_json(&mut self, endpoint: &str, body: Value) -> Result<Value, ApiError> {
+        let resp = self.respond(Method::POST, endpoint, &[], Some(body))?;
+        json_response(resp).await
+    }
+
+    async fn put_json(&mut self, endpoint: &str, body: Value) -> Result<Value, ApiError> {
+        let resp = self.respond(Method::PUT, endpoint, &[], Some(body))?;
+        json_response(resp).await
+    }
+
+    async fn delete(&mut self, endpoint: &str) -> Result<Value, ApiError> {
+        let resp = self.respond(Method::DELETE, endpoint, &[], None)?;
+        json_response(resp).await
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::VecDeque;
+use std::future::Future;
+
+use futures::stream::{self, Stream};
+
+/// Turn a function that fetches a page of items into a stream of items. `fetch`
+/// is called with `None` for the first page, then with the cursor returned by
+/// the previous page until no cursor is returned. The stream ends after the
+/// first error.
+///
+/// Works with any cursor type, e.g. a page number, an `after` token or a
+/// `@odata.nextLink` url.
+pub fn paginate_stream<T, C, E, F, Fut>(fetch: F) -> impl Stream<Item = Result<T, E>>
+where
+    F: FnMut(Option<C>) -> Fut,
+    Fut: Future<Output = Result<(Vec<T>, Option<C>), E>>,
+{
+    // `next` is None once the last page has been fetched.
+    let next: Option<Option<C>> = Some(None);
+    stream::unfold(
+        (fetch, VecDeque::new(), next),
+        |(mut fetch, mut items, mut next)| async move {
+            loop {
+                if let Some(item) = items.pop_front() {
+                    return Some((Ok(item), (fetch, items, next)));
+                }
+
+                match fetch(next?).await {
+                    Ok((page, cursor)) => {
+                        items.extend(page);
+                        next = cursor.map(Some);
+                    }
+                    // Stop after surfacing the error
+                    Err(err) => return Some((Err(err), (fetch, items, None))),
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::paginate_stream;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_paginate_stream() {
+        let mut calls = Vec::new();
+        let items = paginate_stream(|cursor: Option<usize>| {
+            calls.push(cursor);
+            async move {
+                let page = cursor.unwrap_or(0);
+                let next = if page < 2 { Some(page + 1) } else { None };
+                Ok::<_, String>((vec![page * 10, page * 10 + 1], next))
+            }
+        })
+        .collect::<Vec<_>>()
+        .await;
+
+        let items = items.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(items, vec![0, 1, 10, 11, 20, 21]);
+        assert_eq!(calls, vec![None, Some(1), Some(2)]);
+    }
+
+    #[tokio::test]
+    async fn test_paginate_stream_error() {
+        let items = paginate_stream(|cursor: Option<usize>| async move {
+            match cursor {
+                None => Ok((vec![1], Some(1))),
+                Some(_) => Err("rate limited".to_string()),
+            }
+        })
+        .collect::<Vec<_>>()
+        .await;
+
+        assert_eq!(items, vec![Ok(1), Err("rate limited".to_string())]);
+    }
+}
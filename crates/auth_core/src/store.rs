@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::Credentials;
+
+const SAVED_CREDS_DIR: &str = "credentials";
+
+/// Persists credentials between runs, keyed by an id such as `ApiClient::id`.
+/// Implement this to keep credentials in e.g. the OS keychain or a database.
+#[async_trait]
+pub trait CredentialStore: Send + Sync {
+    /// Returns `None` if no credentials have been saved for `id`.
+    async fn load(&self, id: &str) -> Result<Option<Credentials>>;
+    async fn save(&self, id: &str, creds: &Credentials) -> Result<()>;
+}
+
+/// Stores each set of credentials as `<id>.json` in a directory.
+#[derive(Clone, Debug)]
+pub struct FileStore {
+    dir: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FileStore { dir: dir.into() }
+    }
+
+    fn path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}.json"))
+    }
+}
+
+impl Default for FileStore {
+    fn default() -> Self {
+        FileStore::new(SAVED_CREDS_DIR)
+    }
+}
+
+#[async_trait]
+impl CredentialStore for FileStore {
+    async fn load(&self, id: &str) -> Result<Option<Credentials>> {
+        let path = self.path(id);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let saved = tokio::fs::read_to_string(path).await?;
+        Ok(Some(serde_json::from_str(&saved)?))
+    }
+
+    async fn save(&self, id: &str, creds: &Credentials) -> Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        tokio::fs::write(self.path(id), serde_json::to_string(creds)?).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CredentialStore, FileStore};
+    use crate::{AccessToken, Credentials, RefreshToken};
+
+    #[tokio::test]
+    async fn test_file_store() {
+        let dir = std::env::temp_dir().join(format!("libauth-store-{}", std::process::id()));
+        let store = FileStore::new(&dir);
+
+        assert!(store.load("client").await.unwrap().is_none());
+
+        let creds = Credentials {
+            access_token: AccessToken::new("access".into()),
+            refresh_token: Some(RefreshToken::new("refresh".into())),
+            ..Default::default()
+        };
+        store.save("client", &creds).await.unwrap();
+
+        let loaded = store.load("client").await.unwrap().expect("No credentials");
+        assert_eq!(loaded.access_token.secret(), "access");
+        assert_eq!(
+            loaded.refresh_token.map(|token| token.secret().to_string()),
+            Some("refresh".to_string())
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
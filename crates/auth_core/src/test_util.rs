@@ -0,0 +1,41 @@
+//! Helpers shared by the provider crates' integration tests. Enabled with the
+//! `test-util` feature.
+
+/// OAuth app used by clients built with `mock_client_factory!`.
+pub const CLIENT_ID: &str = "client-id";
+pub const CLIENT_SECRET: &str = "client-secret";
+pub const REDIRECT_URL: &str = "http://127.0.0.1:8080";
+
+/// Defines `client` & `client_with_credentials`, which build a `$client` with
+/// the test OAuth app and point it at a wiremock `MockServer`. Any extra
+/// arguments are passed to `new` before the credentials.
+///
+/// ```ignore
+/// libauth::mock_client_factory!(MicrosoftClient, "graph.microsoft.com");
+/// ```
+#[macro_export]
+macro_rules! mock_client_factory {
+    ($client:ty $(, $arg:expr)* $(,)?) => {
+        /// Builds a client pointed at the mock server with empty credentials.
+        pub fn client(server: &::wiremock::MockServer) -> $client {
+            client_with_credentials(server, ::std::default::Default::default())
+        }
+
+        /// Builds a client pointed at the mock server with the given credentials.
+        pub fn client_with_credentials(
+            server: &::wiremock::MockServer,
+            creds: $crate::Credentials,
+        ) -> $client {
+            let mut client = <$client>::new(
+                $crate::test_util::CLIENT_ID,
+                $crate::test_util::CLIENT_SECRET,
+                $crate::test_util::REDIRECT_URL,
+                $($arg,)*
+                creds,
+            )
+            .expect("Unable to create client");
+            client.set_endpoint(&server.uri());
+            client
+        }
+    };
+}
@@ -0,0 +1,51 @@
+use libauth::mock::MockApiClient;
+use libauth::{ApiClient, ApiError};
+use reqwest::{Method, StatusCode};
+
+#[tokio::test]
+async fn test_mock_api_client() {
+    let mut client = MockApiClient::new();
+    client.expect_call("/files", serde_json::json!({ "files": [{ "id": "1" }] }));
+    client.expect_status(
+        "/files/missing",
+        StatusCode::NOT_FOUND,
+        serde_json::json!({ "error": "not found" }),
+    );
+    client.expect_call("/files", serde_json::json!({ "id": "new" }));
+
+    let query = vec![("q".to_string(), "name".to_string())];
+    let files = client
+        .call_json("https://example.com/files", &query)
+        .await
+        .expect("Unable to list files");
+    assert_eq!(files["files"][0]["id"], "1");
+
+    let err = client
+        .call_json("https://example.com/files/missing", &[])
+        .await
+        .expect_err("Expected an error");
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+
+    let created = client
+        .post_json(
+            "https://example.com/files",
+            serde_json::json!({ "name": "new" }),
+        )
+        .await
+        .expect("Unable to create file");
+    assert_eq!(created["id"], "new");
+
+    // Every expectation has been used up.
+    let err = client
+        .call_json("https://example.com/files", &[])
+        .await
+        .expect_err("Expected an error");
+    assert!(matches!(err, ApiError::BadRequest(_)));
+
+    let calls = client.calls();
+    assert_eq!(calls.len(), 4);
+    assert_eq!(calls[0].method, Method::GET);
+    assert_eq!(calls[0].query, query);
+    assert_eq!(calls[2].method, Method::POST);
+    assert_eq!(calls[2].body, Some(serde_json::json!({ "name": "new" })));
+}
@@ -1,6 +1,7 @@
 use dotenv_codegen::dotenv;
 
 use libauth::helpers::load_credentials;
+use libauth::store::FileStore;
 use libgithub::types::AuthScopes;
 use libgithub::GithubClient;
 
@@ -13,8 +14,8 @@ async fn main() -> anyhow::Result<()> {
 
     let mut client = GithubClient::new(client_id, client_secret, REDIRECT_URL, Default::default())?;
 
-    let scopes = vec![AuthScopes::Repo.to_string(), AuthScopes::User.to_string()];
-    load_credentials(&mut client, &scopes, true).await;
+    let scopes = AuthScopes::default_read_scopes();
+    load_credentials(&mut client, &FileStore::default(), &scopes, true).await;
 
     let user = client.get_user().await?;
     println!("Authenticated w/ {}", user.login);
@@ -44,7 +45,7 @@ async fn main() -> anyhow::Result<()> {
     println!("\nListing users issues:");
     println!("------------------------------");
     let mut page = Some(1);
-    while let Ok(issues) = client.list_issues(page).await {
+    while let Ok(issues) = client.list_issues(page, &Default::default()).await {
         page = issues.next_page;
         println!("next_page: {:?}", issues.next_page);
         for issue in issues.result.iter().take(5) {
@@ -1,10 +1,13 @@
 use anyhow::anyhow;
 use anyhow::Result;
 use async_trait::async_trait;
+use futures::stream::{BoxStream, Stream, StreamExt};
 use libauth::ApiError;
 use libauth::AuthorizeOptions;
 use libauth::{
-    auth_http_client, oauth_client, ApiClient, AuthorizationRequest, Credentials, OAuthParams,
+    call_with_retry, json_response, oauth_client, paginate_stream, AccountInfo, ApiClient,
+    AuthorizationRequest, ClientConfig, ClientSettings, Credentials, OAuthParams, RetryPolicy,
+    TokenError,
 };
 use oauth2::basic::{BasicClient, BasicTokenResponse};
 use oauth2::http::HeaderMap;
@@ -12,25 +15,51 @@ use oauth2::reqwest::async_http_client;
 use oauth2::{
     AuthorizationCode, CsrfToken, PkceCodeChallenge, PkceCodeVerifier, Scope, TokenResponse,
 };
-use reqwest::Client;
+use reqwest::{Client, Method};
+use std::pin::Pin;
+use std::task::{Context, Poll};
 
 pub mod types;
 use serde::de::DeserializeOwned;
 use serde_json::Value;
-use tokio::sync::watch;
-use types::ApiResponse;
+use std::sync::Arc;
+use tokio::sync::{watch, Mutex};
+use types::{ApiResponse, Links, RateLimit};
 
 const AUTH_URL: &str = "https://github.com/login/oauth/authorize";
 const TOKEN_URL: &str = "https://github.com/login/oauth/access_token";
 
 const API_ENDPOINT: &str = "https://api.github.com";
 
+/// Stream of items across all pages of a GitHub listing.
+pub struct PageStream<'a, T> {
+    /// Total number of pages, if known from the `rel="last"` link.
+    pub last_page: Option<u32>,
+    inner: BoxStream<'a, Result<T, ApiError>>,
+}
+
+impl<'a, T> Stream for PageStream<'a, T> {
+    type Item = Result<T, ApiError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+#[derive(Clone)]
 pub struct GithubClient {
     pub credentials: Credentials,
+    client_id: String,
+    client_secret: String,
+    endpoint: String,
     http: Client,
     pub oauth: BasicClient,
     pub on_refresh_tx: watch::Sender<Credentials>,
     pub on_refresh_rx: watch::Receiver<Credentials>,
+    settings: ClientSettings,
+    refresh_lock: Arc<Mutex<()>>,
+    pub username: Option<String>,
+    last_rate_limit: Option<RateLimit>,
 }
 
 #[async_trait]
@@ -40,14 +69,30 @@ impl ApiClient for GithubClient {
     }
 
     async fn account_id(&mut self) -> Result<String> {
-        let user = self.get_user().await?;
-        Ok(user.login)
+        if let Some(username) = &self.username {
+            Ok(username.clone())
+        } else {
+            let login = self.get_user().await?.login;
+            self.username = Some(login.clone());
+            Ok(login)
+        }
     }
 
     async fn account_metadata(&mut self) -> Option<Value> {
         None
     }
 
+    async fn account_info(&mut self) -> Result<AccountInfo> {
+        let user = self.get_user().await?;
+        Ok(AccountInfo {
+            id: user.id.to_string(),
+            display_name: user.name.clone().unwrap_or_else(|| user.login.clone()),
+            email: user.email.clone(),
+            avatar_url: user.avatar_url.clone(),
+            metadata: serde_json::to_value(&user)?,
+        })
+    }
+
     fn credentials(&self) -> Credentials {
         self.credentials.clone()
     }
@@ -58,7 +103,9 @@ impl ApiClient for GithubClient {
 
     fn set_credentials(&mut self, credentials: &Credentials) -> Result<()> {
         self.credentials = credentials.clone();
-        self.http = auth_http_client(credentials.access_token.secret())?;
+        self.http = self
+            .settings
+            .auth_http_client(credentials.access_token.secret())?;
         Ok(())
     }
 
@@ -66,6 +113,30 @@ impl ApiClient for GithubClient {
         self.on_refresh_rx.clone()
     }
 
+    fn refresh_lock(&self) -> Arc<Mutex<()>> {
+        self.refresh_lock.clone()
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        self.settings.retry_policy.clone()
+    }
+
+    async fn call(
+        &mut self,
+        endpoint: &str,
+        query: &Vec<(String, String)>,
+    ) -> Result<reqwest::Response, ApiError> {
+        let resp = call_with_retry(self, endpoint, query).await?;
+        if let Some(limit) = RateLimit::from_headers(resp.headers()) {
+            self.last_rate_limit = Some(limit);
+        }
+        Ok(resp)
+    }
+
+    fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.settings.retry_policy = policy;
+    }
+
     fn authorize(&self, scopes: &[String], _: &AuthorizeOptions) -> AuthorizationRequest {
         let (pkce_code_challenge, pkce_code_verifier) = PkceCodeChallenge::new_random_sha256();
 
@@ -94,7 +165,7 @@ impl ApiClient for GithubClient {
         &self,
         code: &str,
         pkce_verifier: Option<String>,
-    ) -> Result<BasicTokenResponse> {
+    ) -> Result<BasicTokenResponse, TokenError> {
         let code = AuthorizationCode::new(code.to_owned());
 
         let mut exchange = self.oauth.exchange_code(code);
@@ -104,11 +175,11 @@ impl ApiClient for GithubClient {
 
         match exchange.request_async(async_http_client).await {
             Ok(val) => Ok(val),
-            Err(err) => Err(anyhow!(err.to_string())),
+            Err(err) => Err(err.into()),
         }
     }
 
-    async fn refresh_credentials(&mut self) -> Result<()> {
+    async fn refresh_credentials(&mut self) -> Result<(), TokenError> {
         if let Some(refresh_token) = &self.credentials.refresh_token {
             let new_token = self
                 .oauth
@@ -117,9 +188,13 @@ impl ApiClient for GithubClient {
                 .await?;
 
             self.credentials.refresh_token(&new_token);
-            self.http = auth_http_client(new_token.access_token().secret())?;
+            self.http = self
+                .settings
+                .auth_http_client(new_token.access_token().secret())?;
             // Let any listeners know the credentials have been updated.
-            self.on_refresh_tx.send(self.credentials.clone())?;
+            self.on_refresh_tx
+                .send(self.credentials.clone())
+                .map_err(anyhow::Error::from)?;
         }
 
         Ok(())
@@ -132,6 +207,23 @@ impl GithubClient {
         client_secret: &str,
         redirect_url: &str,
         creds: Credentials,
+    ) -> anyhow::Result<Self> {
+        Self::new_with_config(
+            client_id,
+            client_secret,
+            redirect_url,
+            creds,
+            ClientConfig::default(),
+        )
+    }
+
+    /// Same as `new`, with timeouts etc. applied to every request.
+    pub fn new_with_config(
+        client_id: &str,
+        client_secret: &str,
+        redirect_url: &str,
+        creds: Credentials,
+        config: ClientConfig,
     ) -> anyhow::Result<Self> {
         let params = OAuthParams {
             client_id: client_id.to_string(),
@@ -142,23 +234,50 @@ impl GithubClient {
             ..Default::default()
         };
 
+        let settings = ClientSettings::new(config);
         let (tx, rx) = watch::channel(creds.clone());
         Ok(GithubClient {
             credentials: creds.clone(),
-            http: auth_http_client(creds.access_token.secret())?,
-            oauth: oauth_client(&params),
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            endpoint: API_ENDPOINT.to_string(),
+            http: settings.auth_http_client(creds.access_token.secret())?,
+            oauth: oauth_client(&params)?,
             on_refresh_tx: tx,
             on_refresh_rx: rx,
+            settings,
+            refresh_lock: Arc::new(Mutex::new(())),
+            username: None,
+            last_rate_limit: None,
         })
     }
 
-    fn has_next(&self, headers: &HeaderMap) -> bool {
-        if let Some(link) = headers.get("link") {
-            let value = link.to_str().unwrap_or_default();
-            return value.contains("rel=\"next\"");
-        }
+    /// Rate limit state as of the last GET request.
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        self.last_rate_limit.clone()
+    }
 
-        false
+    /// Override the base API URL, e.g. to target a GitHub Enterprise instance
+    /// or a mock server.
+    pub fn set_endpoint(&mut self, endpoint: &str) {
+        self.endpoint = endpoint.trim_end_matches('/').to_string();
+    }
+
+    /// Override the user agent sent with API requests.
+    pub fn set_user_agent(&mut self, user_agent: &str) -> Result<()> {
+        self.settings.user_agent = user_agent.to_string();
+        self.http = self
+            .settings
+            .auth_http_client(self.credentials.access_token.secret())?;
+        Ok(())
+    }
+
+    fn links(headers: &HeaderMap) -> Links {
+        headers
+            .get("link")
+            .and_then(|link| link.to_str().ok())
+            .map(Links::parse)
+            .unwrap_or_default()
     }
 
     /// Handle pagination through Github API results
@@ -175,70 +294,441 @@ impl GithubClient {
         query.push(("page".to_string(), page.unwrap_or(1).to_string()));
 
         let resp = self.call(endpoint, &query).await?;
-        let next_page = if self.has_next(resp.headers()) {
+        let links = Self::links(resp.headers());
+        let next_page = if links.next.is_some() {
             Some(page.unwrap_or(1) + 1)
         } else {
             None
         };
 
         match resp.json().await {
-            Ok(result) => Ok(ApiResponse { next_page, result }),
+            Ok(result) => Ok(ApiResponse {
+                next_page,
+                last_page: links.last_page(),
+                result,
+            }),
             Err(err) => Err(anyhow!(err.to_string())),
         }
     }
 
-    pub async fn get_issue(&mut self, issue_or_url: &str) -> Result<types::Issue, ApiError> {
-        let endpoint = if issue_or_url.starts_with("https://api.github.com/repos") {
+    /// Lazily stream every item across all pages by following the `next` URLs
+    /// in the `Link` header. The first page is fetched up front so the total
+    /// page count is available before iterating.
+    async fn stream_pages<'a, T>(
+        &'a mut self,
+        endpoint: &str,
+        query: &Vec<(String, String)>,
+    ) -> Result<PageStream<'a, T>, ApiError>
+    where
+        T: DeserializeOwned + Send + 'a,
+    {
+        let resp = self.call(endpoint, query).await?;
+        let links = Self::links(resp.headers());
+        let items = serde_json::from_value::<Vec<T>>(json_response(resp).await?)
+            .map_err(ApiError::SerdeError)?;
+
+        let last_page = links.last_page();
+        let mut first = Some((items, links.next));
+        let client = Arc::new(Mutex::new(self));
+        let stream = paginate_stream(move |next: Option<String>| {
+            let first = first.take();
+            let client = client.clone();
+            async move {
+                // The stream starts from the page fetched above.
+                let Some(next) = next else {
+                    return Ok(first.unwrap_or_default());
+                };
+
+                let resp = client.lock().await.call(&next, &Vec::new()).await?;
+                let next = Self::links(resp.headers()).next;
+                let page = serde_json::from_value::<Vec<T>>(json_response(resp).await?)
+                    .map_err(ApiError::SerdeError)?;
+                Ok((page, next))
+            }
+        });
+
+        Ok(PageStream {
+            last_page,
+            inner: stream.boxed(),
+        })
+    }
+
+    /// Check if the access token is still valid, returning false if it has been
+    /// revoked. Unlike other requests, this authenticates as the OAuth app and
+    /// does not count against the user's rate limit.
+    pub async fn check_token(&self) -> Result<bool, ApiError> {
+        let endpoint = format!("{}/applications/{}/token", self.endpoint, self.client_id);
+        let body = serde_json::json!({ "access_token": self.credentials.access_token.secret() });
+
+        let resp = self
+            .http
+            .post(endpoint)
+            .basic_auth(&self.client_id, Some(&self.client_secret))
+            .json(&body)
+            .send()
+            .await?;
+
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+
+        json_response(resp).await.map(|_| true)
+    }
+
+    /// Fetch a single issue by "owner/name/issues/number" or API url, `None` if
+    /// it doesn't exist or isn't visible to the user.
+    pub async fn get_issue(
+        &mut self,
+        issue_or_url: &str,
+    ) -> Result<Option<types::Issue>, ApiError> {
+        let endpoint = if issue_or_url.starts_with(&format!("{}/repos", self.endpoint)) {
             issue_or_url.to_string()
         } else {
-            format!("{API_ENDPOINT}/repos/{issue_or_url}")
+            format!("{}/repos/{issue_or_url}", self.endpoint)
         };
 
-        serde_json::from_value::<types::Issue>(self.call_json(&endpoint, &Vec::new()).await?)
+        self.get_optional(&endpoint).await
+    }
+
+    pub async fn get_gist(&mut self, id: &str) -> Result<types::Gist, ApiError> {
+        let endpoint = format!("{}/gists/{id}", self.endpoint);
+        serde_json::from_value::<types::Gist>(self.call_json(&endpoint, &Vec::new()).await?)
             .map_err(ApiError::SerdeError)
     }
 
-    pub async fn get_repo(&mut self, repo_or_url: &str) -> Result<types::Repo, ApiError> {
-        let endpoint = if repo_or_url.starts_with("https://api.github.com/repos") {
+    /// Fetch a single repo by "owner/name" or API url, `None` if it doesn't exist
+    /// or isn't visible to the user. Repo topics are included by default on
+    /// github.com, older Enterprise servers require the
+    /// `application/vnd.github.mercy-preview+json` Accept header to return them.
+    pub async fn get_repo(&mut self, repo_or_url: &str) -> Result<Option<types::Repo>, ApiError> {
+        let endpoint = if repo_or_url.starts_with(&format!("{}/repos", self.endpoint)) {
             repo_or_url.to_string()
         } else {
-            format!("{API_ENDPOINT}/repos/{repo_or_url}")
+            format!("{}/repos/{repo_or_url}", self.endpoint)
         };
 
-        serde_json::from_value::<types::Repo>(self.call_json(&endpoint, &Vec::new()).await?)
-            .map_err(ApiError::SerdeError)
+        self.get_optional(&endpoint).await
+    }
+
+    /// GET `endpoint`, mapping a 404 to `None`. GitHub also uses 404s for
+    /// private resources the user can't access.
+    async fn get_optional<T>(&mut self, endpoint: &str) -> Result<Option<T>, ApiError>
+    where
+        T: DeserializeOwned,
+    {
+        match self.call_json(endpoint, &Vec::new()).await {
+            Ok(value) => serde_json::from_value(value)
+                .map(Some)
+                .map_err(ApiError::SerdeError),
+            Err(err) if err.status() == Some(reqwest::StatusCode::NOT_FOUND) => Ok(None),
+            Err(err) => Err(err),
+        }
     }
 
     pub async fn get_user(&mut self) -> Result<types::User, ApiError> {
-        let mut endpoint = API_ENDPOINT.to_string();
+        let mut endpoint = self.endpoint.to_string();
         endpoint.push_str("/user");
         serde_json::from_value::<types::User>(self.call_json(&endpoint, &Vec::new()).await?)
             .map_err(ApiError::SerdeError)
     }
 
+    /// Fetch a file or directory listing from `owner_repo` ("owner/name"). `ref_`
+    /// can be a branch, tag or commit SHA, defaulting to the default branch.
+    pub async fn get_contents(
+        &mut self,
+        owner_repo: &str,
+        path: &str,
+        ref_: Option<&str>,
+    ) -> Result<types::Content, ApiError> {
+        let endpoint = format!(
+            "{}/repos/{owner_repo}/contents/{}",
+            self.endpoint,
+            path.trim_start_matches('/')
+        );
+
+        let mut query = Vec::new();
+        if let Some(ref_) = ref_ {
+            query.push(("ref".to_string(), ref_.to_string()));
+        }
+
+        serde_json::from_value::<types::Content>(self.call_json(&endpoint, &query).await?)
+            .map_err(ApiError::SerdeError)
+    }
+
+    /// List commits on the default branch of `owner_repo` ("owner/name"), newest first.
+    pub async fn list_commits(
+        &mut self,
+        owner_repo: &str,
+        page: Option<u32>,
+    ) -> Result<ApiResponse<Vec<types::Commit>>> {
+        let endpoint = format!("{}/repos/{owner_repo}/commits", self.endpoint);
+        self.paginate(&endpoint, page, &Vec::new()).await
+    }
+
+    /// List the authenticated user's email addresses, including private ones.
+    /// Requires the `user:email` (or `user`) scope.
+    pub async fn list_emails(&mut self) -> Result<Vec<types::UserEmail>, ApiError> {
+        let mut endpoint = self.endpoint.to_string();
+        endpoint.push_str("/user/emails");
+
+        let resp = self.call(&endpoint, &Vec::new()).await?;
+        // GitHub hides endpoints the token isn't scoped for behind a 404. A 403
+        // is also used when rate limited, which is returned as an `ApiStatus`.
+        let missing_scope = match resp.status() {
+            reqwest::StatusCode::NOT_FOUND => true,
+            reqwest::StatusCode::FORBIDDEN => !is_rate_limited(resp.headers()),
+            _ => false,
+        };
+        if missing_scope {
+            return Err(ApiError::AuthError(format!(
+                "Listing emails requires the \"{}\" scope",
+                types::AuthScopes::UserEmail
+            )));
+        }
+
+        let value = json_response(resp)
+            .await
+            .map_err(|err| err.with_endpoint(Method::GET, &endpoint))?;
+        serde_json::from_value::<Vec<types::UserEmail>>(value).map_err(ApiError::SerdeError)
+    }
+
+    /// Combined status of the commit at `ref_`, which can be a SHA, branch or tag.
+    pub async fn get_combined_status(
+        &mut self,
+        owner: &str,
+        repo: &str,
+        ref_: &str,
+    ) -> Result<types::CombinedStatus, ApiError> {
+        let endpoint = format!(
+            "{}/repos/{owner}/{repo}/commits/{ref_}/status",
+            self.endpoint
+        );
+        serde_json::from_value(self.ref_json(&endpoint, owner, repo, ref_).await?)
+            .map_err(ApiError::SerdeError)
+    }
+
+    /// Check runs (e.g. GitHub Actions jobs) for the commit at `ref_`.
+    pub async fn list_check_runs(
+        &mut self,
+        owner: &str,
+        repo: &str,
+        ref_: &str,
+    ) -> Result<types::CheckRuns, ApiError> {
+        let endpoint = format!(
+            "{}/repos/{owner}/{repo}/commits/{ref_}/check-runs",
+            self.endpoint
+        );
+        serde_json::from_value(self.ref_json(&endpoint, owner, repo, ref_).await?)
+            .map_err(ApiError::SerdeError)
+    }
+
+    /// GET a per-commit resource, reporting an unknown ref as a `BadRequest`.
+    /// GitHub also 404s for a missing or inaccessible repo, in which case the
+    /// `ApiStatus` is returned as is.
+    async fn ref_json(
+        &mut self,
+        endpoint: &str,
+        owner: &str,
+        repo: &str,
+        ref_: &str,
+    ) -> Result<Value, ApiError> {
+        let query = vec![("per_page".to_string(), "100".to_string())];
+        match self.call_json(endpoint, &query).await {
+            Err(err) if err.status() == Some(reqwest::StatusCode::NOT_FOUND) => {
+                if self.get_repo(&format!("{owner}/{repo}")).await?.is_some() {
+                    Err(ApiError::BadRequest(format!(
+                        "Unknown ref \"{ref_}\" in {owner}/{repo}"
+                    )))
+                } else {
+                    Err(err)
+                }
+            }
+            result => result,
+        }
+    }
+
     pub async fn list_issues(
         &mut self,
         page: Option<u32>,
+        options: &types::ListIssuesOptions,
     ) -> Result<ApiResponse<Vec<types::Issue>>> {
-        let mut endpoint = API_ENDPOINT.to_string();
+        let mut endpoint = self.endpoint.to_string();
         endpoint.push_str("/issues");
-        let params = vec![("filter".to_string(), "all".to_string())];
+        self.paginate(&endpoint, page, &options.to_query()).await
+    }
 
-        self.paginate(&endpoint, page, &params).await
+    /// Stream issues across all pages, see `list_issues`.
+    pub async fn stream_issues(
+        &mut self,
+        options: &types::ListIssuesOptions,
+    ) -> Result<PageStream<'_, types::Issue>, ApiError> {
+        let mut endpoint = self.endpoint.to_string();
+        endpoint.push_str("/issues");
+        self.stream_pages(&endpoint, &options.to_query()).await
+    }
+
+    /// List gists for the authenticated user. Note that file contents are not
+    /// included, use `get_gist` to fetch them.
+    pub async fn list_gists(&mut self, page: Option<u32>) -> Result<ApiResponse<Vec<types::Gist>>> {
+        let mut endpoint = self.endpoint.to_string();
+        endpoint.push_str("/gists");
+        self.paginate(&endpoint, page, &Vec::new()).await
     }
 
     pub async fn list_repos(&mut self, page: Option<u32>) -> Result<ApiResponse<Vec<types::Repo>>> {
-        let mut endpoint = API_ENDPOINT.to_string();
+        let mut endpoint = self.endpoint.to_string();
         endpoint.push_str("/user/repos");
         self.paginate(&endpoint, page, &Vec::new()).await
     }
 
+    /// Stream repos across all pages, see `list_repos`.
+    pub async fn stream_repos(&mut self) -> Result<PageStream<'_, types::Repo>, ApiError> {
+        let mut endpoint = self.endpoint.to_string();
+        endpoint.push_str("/user/repos");
+        self.stream_pages(&endpoint, &Vec::new()).await
+    }
+
     pub async fn list_starred(
         &mut self,
         page: Option<u32>,
     ) -> Result<ApiResponse<Vec<types::Repo>>> {
-        let mut endpoint = API_ENDPOINT.to_string();
+        let mut endpoint = self.endpoint.to_string();
         endpoint.push_str("/user/starred");
         self.paginate(&endpoint, page, &Vec::new()).await
     }
+
+    /// Stream starred repos across all pages, see `list_starred`.
+    pub async fn stream_starred(&mut self) -> Result<PageStream<'_, types::Repo>, ApiError> {
+        let mut endpoint = self.endpoint.to_string();
+        endpoint.push_str("/user/starred");
+        self.stream_pages(&endpoint, &Vec::new()).await
+    }
+
+    /// Search repositories using GitHub's search syntax, e.g. "tokio language:rust".
+    pub async fn search_repos(
+        &mut self,
+        query: &str,
+        page: Option<u32>,
+    ) -> Result<ApiResponse<types::SearchResults<types::Repo>>, ApiError> {
+        let mut endpoint = self.endpoint.to_string();
+        endpoint.push_str("/search/repositories");
+        self.search(&endpoint, query, page).await
+    }
+
+    /// Search file contents, e.g. "ApiClient repo:spyglass-search/third-party-apis".
+    pub async fn search_code(
+        &mut self,
+        query: &str,
+        page: Option<u32>,
+    ) -> Result<ApiResponse<types::SearchResults<types::CodeSearchItem>>, ApiError> {
+        let mut endpoint = self.endpoint.to_string();
+        endpoint.push_str("/search/code");
+        self.search(&endpoint, query, page).await
+    }
+
+    async fn search<T>(
+        &mut self,
+        endpoint: &str,
+        query: &str,
+        page: Option<u32>,
+    ) -> Result<ApiResponse<types::SearchResults<T>>, ApiError>
+    where
+        T: DeserializeOwned,
+    {
+        let params = vec![
+            ("q".to_string(), query.to_string()),
+            ("page".to_string(), page.unwrap_or(1).to_string()),
+        ];
+
+        let resp = self.call(endpoint, &params).await?;
+        // Search has a separate, much lower, rate limit than the rest of the API.
+        if resp.status() == reqwest::StatusCode::FORBIDDEN {
+            let header = |name: &str| {
+                resp.headers()
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or("unknown")
+                    .to_string()
+            };
+            let remaining = header("X-RateLimit-Remaining");
+            let reset = header("X-RateLimit-Reset");
+            let body = resp.text().await.unwrap_or_default();
+            return Err(ApiError::ApiStatus {
+                status: 403,
+                body: format!("{body} (X-RateLimit-Remaining: {remaining}, resets at {reset})"),
+                url: None,
+            }
+            .with_endpoint(Method::GET, endpoint));
+        }
+
+        let links = Self::links(resp.headers());
+        let result = serde_json::from_value(
+            json_response(resp)
+                .await
+                .map_err(|err| err.with_endpoint(Method::GET, endpoint))?,
+        )
+        .map_err(ApiError::SerdeError)?;
+
+        Ok(ApiResponse {
+            next_page: links.next.is_some().then(|| page.unwrap_or(1) + 1),
+            last_page: links.last_page(),
+            result,
+        })
+    }
+
+    /// Whether the authenticated user has starred the repo.
+    pub async fn is_starred(&mut self, owner: &str, repo: &str) -> Result<bool, ApiError> {
+        let resp = self.star_request(Method::GET, owner, repo).await?;
+        // GitHub answers with an empty 204 if starred and a 404 if not.
+        match resp.status() {
+            reqwest::StatusCode::NO_CONTENT => Ok(true),
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            _ => json_response(resp).await.map(|_| true),
+        }
+    }
+
+    pub async fn star_repo(&mut self, owner: &str, repo: &str) -> Result<(), ApiError> {
+        let resp = self.star_request(Method::PUT, owner, repo).await?;
+        empty_response(resp).await
+    }
+
+    pub async fn unstar_repo(&mut self, owner: &str, repo: &str) -> Result<(), ApiError> {
+        let resp = self.star_request(Method::DELETE, owner, repo).await?;
+        empty_response(resp).await
+    }
+
+    async fn star_request(
+        &mut self,
+        method: Method,
+        owner: &str,
+        repo: &str,
+    ) -> Result<reqwest::Response, ApiError> {
+        let endpoint = format!("{}/user/starred/{owner}/{repo}", self.endpoint);
+        let client = self.get_check_client().await?;
+        client
+            .request(method.clone(), &endpoint)
+            // GitHub rejects a PUT without a body unless the length is set.
+            .header(reqwest::header::CONTENT_LENGTH, 0)
+            .send()
+            .await
+            .map_err(|err| ApiError::from(err).with_endpoint(method, &endpoint))
+    }
+}
+
+/// Check the status of a response that has no body, e.g. a 204.
+async fn empty_response(resp: reqwest::Response) -> Result<(), ApiError> {
+    if resp.status().is_success() {
+        Ok(())
+    } else {
+        json_response(resp).await.map(|_| ())
+    }
+}
+
+/// Whether a 403 is GitHub's primary (no requests left) or secondary
+/// (`retry-after`) rate limit rather than a permissions error.
+fn is_rate_limited(headers: &reqwest::header::HeaderMap) -> bool {
+    let remaining = headers
+        .get("x-ratelimit-remaining")
+        .and_then(|value| value.to_str().ok());
+    remaining == Some("0") || headers.contains_key(reqwest::header::RETRY_AFTER)
 }
@@ -1,6 +1,10 @@
-use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use base64::Engine;
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use libauth::helpers::html_to_text;
+use libauth::Document;
 use markdown::{CompileOptions, Options};
-use scraper::Html;
 use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumString};
 
@@ -24,6 +28,17 @@ pub enum AuthScopes {
     /// Grants read/write access to profile info only.
     #[strum(serialize = "user")]
     User,
+    /// Grants read access to the user's email addresses.
+    #[strum(serialize = "user:email")]
+    UserEmail,
+}
+
+impl AuthScopes {
+    /// Scopes needed to read the user's profile, repos, issues & stars.
+    /// Note: GitHub has no read-only repo scope, so this includes write access.
+    pub fn default_read_scopes() -> Vec<String> {
+        vec![AuthScopes::Repo.to_string(), AuthScopes::User.to_string()]
+    }
 }
 
 #[derive(Clone, Default, Deserialize, Serialize)]
@@ -33,6 +48,89 @@ pub struct User {
     pub id: u32,
     #[serde(rename(serialize = "user", deserialize = "user"))]
     pub user_type: String,
+    pub name: Option<String>,
+    /// Public profile email, if the user has chosen to show one.
+    pub email: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct UserEmail {
+    pub email: String,
+    pub primary: bool,
+    pub verified: bool,
+    /// Either "public" or "private", only set for the primary email.
+    pub visibility: Option<String>,
+}
+
+/// Overall status of a commit, combined from every status reported for it.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CombinedStatus {
+    /// One of "failure", "pending" or "success".
+    pub state: String,
+    pub sha: String,
+    pub total_count: u32,
+    pub statuses: Vec<CommitStatus>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CommitStatus {
+    pub context: String,
+    /// One of "error", "failure", "pending" or "success".
+    pub state: String,
+    pub description: Option<String>,
+    pub target_url: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CheckRuns {
+    pub total_count: u32,
+    pub check_runs: Vec<CheckRun>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CheckRun {
+    pub id: u64,
+    pub name: String,
+    /// One of "queued", "in_progress" or "completed".
+    pub status: String,
+    /// Only set once the run has completed, e.g. "success" or "failure".
+    pub conclusion: Option<String>,
+    pub html_url: Option<String>,
+    pub details_url: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Envelope returned by the search endpoints.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SearchResults<T> {
+    pub total_count: u64,
+    /// Set when the search timed out before finding every match, in which case
+    /// `items` may be missing results.
+    #[serde(default)]
+    pub incomplete_results: bool,
+    pub items: Vec<T>,
+}
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CodeSearchItem {
+    pub name: String,
+    pub path: String,
+    pub sha: String,
+    /// API accessible url
+    pub url: String,
+    /// URL on Github website.
+    pub html_url: String,
+    /// Note: Only a subset of the repo fields are included.
+    pub repository: Repo,
 }
 
 #[derive(Clone, Default, Deserialize, Serialize)]
@@ -45,6 +143,14 @@ pub struct Repo {
     pub watchers_count: u32,
     pub visibility: String,
     pub owner: User,
+    /// Primary language of the repo, as detected by GitHub.
+    pub language: Option<String>,
+    pub fork: bool,
+    pub archived: bool,
+    pub default_branch: String,
+    /// Note: Older GitHub Enterprise servers only return topics when the
+    /// `application/vnd.github.mercy-preview+json` media type is requested.
+    pub topics: Vec<String>,
 
     /// API accessible url
     pub url: String,
@@ -59,6 +165,8 @@ pub struct Repo {
 #[derive(Clone, Default, Deserialize, Serialize)]
 #[serde(default)]
 pub struct Issue {
+    pub id: u64,
+    pub number: u64,
     pub title: String,
     pub body: Option<String>,
     pub user: User,
@@ -74,21 +182,7 @@ pub struct Issue {
 
 impl Issue {
     pub fn to_text(&self) -> String {
-        let html = self.to_html();
-        if html.is_empty() {
-            return html;
-        }
-
-        let html = Html::parse_fragment(&html);
-        let mut buffer = String::new();
-        let root = html.tree.nodes();
-        for node in root {
-            if let scraper::Node::Text(text) = node.value() {
-                buffer.push_str(text);
-            }
-        }
-
-        buffer
+        html_to_text(&self.to_html())
     }
 
     pub fn to_html(&self) -> String {
@@ -117,14 +211,319 @@ impl Issue {
     }
 }
 
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GistFile {
+    pub filename: String,
+    pub language: Option<String>,
+    /// URL to the raw contents of the file.
+    pub raw_url: String,
+    pub size: u64,
+    /// File contents, only available when fetching a single gist.
+    pub content: Option<String>,
+}
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Gist {
+    pub id: String,
+    pub description: Option<String>,
+    pub public: bool,
+    /// URL on GitHub website
+    pub html_url: String,
+    /// Files in this gist, keyed by filename.
+    pub files: HashMap<String, GistFile>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Response from the repo contents API, which returns a single file or the
+/// entries of a directory depending on the path.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Content {
+    Directory(Vec<ContentEntry>),
+    File(ContentFile),
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ContentFile {
+    pub name: String,
+    pub path: String,
+    pub sha: String,
+    pub size: u64,
+    /// Encoding of `content`, always "base64" for files.
+    pub encoding: String,
+    /// Base64 encoded file contents, wrapped across multiple lines.
+    pub content: String,
+    /// URL on Github website.
+    pub html_url: Option<String>,
+    /// URL to the raw contents of the file.
+    pub download_url: Option<String>,
+}
+
+impl ContentFile {
+    /// Decode the base64 `content` into the raw file bytes.
+    pub fn decode_content(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        let content = self
+            .content
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>();
+        base64::engine::general_purpose::STANDARD.decode(content)
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ContentEntry {
+    /// One of "file", "dir", "symlink" or "submodule".
+    #[serde(rename = "type")]
+    pub entry_type: String,
+    pub name: String,
+    pub path: String,
+    pub sha: String,
+    pub size: u64,
+    /// URL on Github website.
+    pub html_url: Option<String>,
+    /// URL to the raw contents, only set for files.
+    pub download_url: Option<String>,
+}
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Commit {
+    pub sha: String,
+    /// URL on Github website.
+    pub html_url: String,
+    pub commit: CommitDetails,
+    /// GitHub account of the author, if the commit email is linked to one.
+    pub author: Option<User>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CommitDetails {
+    pub message: String,
+    pub author: Option<GitActor>,
+    pub committer: Option<GitActor>,
+}
+
+/// Name & email recorded in the git commit itself.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GitActor {
+    pub name: String,
+    pub email: String,
+    pub date: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug, Default, Display, EnumString)]
+pub enum IssueFilter {
+    #[strum(serialize = "assigned")]
+    Assigned,
+    #[strum(serialize = "created")]
+    Created,
+    #[strum(serialize = "mentioned")]
+    Mentioned,
+    #[strum(serialize = "subscribed")]
+    Subscribed,
+    #[strum(serialize = "repos")]
+    Repos,
+    #[default]
+    #[strum(serialize = "all")]
+    All,
+}
+
+#[derive(Clone, Debug, Display, EnumString)]
+pub enum IssueState {
+    #[strum(serialize = "open")]
+    Open,
+    #[strum(serialize = "closed")]
+    Closed,
+    #[strum(serialize = "all")]
+    All,
+}
+
+#[derive(Clone, Debug, Display, EnumString)]
+pub enum IssueSort {
+    #[strum(serialize = "created")]
+    Created,
+    #[strum(serialize = "updated")]
+    Updated,
+    #[strum(serialize = "comments")]
+    Comments,
+}
+
+#[derive(Clone, Debug, Display, EnumString)]
+pub enum SortDirection {
+    #[strum(serialize = "asc")]
+    Asc,
+    #[strum(serialize = "desc")]
+    Desc,
+}
+
+/// Filters for `GithubClient::list_issues`. Fields left as `None` use the
+/// GitHub API defaults.
+#[derive(Clone, Debug, Default)]
+pub struct ListIssuesOptions {
+    pub filter: IssueFilter,
+    pub state: Option<IssueState>,
+    /// Only return issues with all of these labels.
+    pub labels: Vec<String>,
+    /// Only return issues updated at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    pub sort: Option<IssueSort>,
+    pub direction: Option<SortDirection>,
+}
+
+impl ListIssuesOptions {
+    pub fn to_query(&self) -> Vec<(String, String)> {
+        let mut query = vec![("filter".to_string(), self.filter.to_string())];
+        if let Some(state) = &self.state {
+            query.push(("state".to_string(), state.to_string()));
+        }
+
+        if !self.labels.is_empty() {
+            query.push(("labels".to_string(), self.labels.join(",")));
+        }
+
+        if let Some(since) = &self.since {
+            query.push((
+                "since".to_string(),
+                since.to_rfc3339_opts(SecondsFormat::Secs, true),
+            ));
+        }
+
+        if let Some(sort) = &self.sort {
+            query.push(("sort".to_string(), sort.to_string()));
+        }
+
+        if let Some(direction) = &self.direction {
+            query.push(("direction".to_string(), direction.to_string()));
+        }
+
+        query
+    }
+}
+
+impl Document for Issue {
+    fn id(&self) -> String {
+        self.id.to_string()
+    }
+
+    fn title(&self) -> String {
+        self.title.clone()
+    }
+
+    fn body_text(&self) -> String {
+        self.to_text()
+    }
+
+    fn url(&self) -> Option<String> {
+        Some(self.html_url.clone())
+    }
+
+    fn updated_at(&self) -> Option<DateTime<Utc>> {
+        Some(self.updated_at)
+    }
+}
+
 pub struct ApiResponse<T> {
     pub next_page: Option<u32>,
+    /// Total number of pages, if known from the `Link` header.
+    pub last_page: Option<u32>,
     pub result: T,
 }
 
+/// Pagination URLs parsed from a `Link` response header.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Links {
+    pub first: Option<String>,
+    pub prev: Option<String>,
+    pub next: Option<String>,
+    pub last: Option<String>,
+}
+
+impl Links {
+    /// Parse a header value such as
+    /// `<https://api.github.com/user/repos?page=2>; rel="next", <...>; rel="last"`
+    pub fn parse(header: &str) -> Self {
+        let mut links = Links::default();
+        for part in header.split(',') {
+            let mut segments = part.split(';');
+            let url = match segments.next() {
+                Some(url) => url.trim().trim_start_matches('<').trim_end_matches('>'),
+                None => continue,
+            };
+
+            for param in segments {
+                let rels = match param.trim().strip_prefix("rel=") {
+                    Some(rels) => rels.trim_matches('"'),
+                    None => continue,
+                };
+
+                // A single link can have multiple space separated relations.
+                for rel in rels.split_whitespace() {
+                    let target = match rel {
+                        "first" => &mut links.first,
+                        "prev" => &mut links.prev,
+                        "next" => &mut links.next,
+                        "last" => &mut links.last,
+                        _ => continue,
+                    };
+                    *target = Some(url.to_string());
+                }
+            }
+        }
+
+        links
+    }
+
+    /// Total number of pages, taken from the `page` param of the `last` link.
+    pub fn last_page(&self) -> Option<u32> {
+        self.last.as_deref().and_then(page_param)
+    }
+}
+
+/// Rate limit state reported by GitHub in the `X-RateLimit-*` response headers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Maximum number of requests allowed per window.
+    pub limit: u32,
+    /// Number of requests left in the current window.
+    pub remaining: u32,
+    /// When the current window ends & the limit resets.
+    pub reset: DateTime<Utc>,
+}
+
+impl RateLimit {
+    pub fn from_headers(headers: &reqwest::header::HeaderMap) -> Option<Self> {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+        let limit = header("x-ratelimit-limit")?.parse::<u32>().ok()?;
+        let remaining = header("x-ratelimit-remaining")?.parse::<u32>().ok()?;
+        let reset = header("x-ratelimit-reset")?.parse::<i64>().ok()?;
+
+        Some(RateLimit {
+            limit,
+            remaining,
+            reset: Utc.timestamp_opt(reset, 0).single()?,
+        })
+    }
+}
+
+fn page_param(url: &str) -> Option<u32> {
+    let url = reqwest::Url::parse(url).ok()?;
+    url.query_pairs()
+        .find(|(key, _)| key == "page")
+        .and_then(|(_, value)| value.parse().ok())
+}
+
 #[cfg(test)]
 mod test {
-    use super::Issue;
+    use super::{Issue, Links};
 
     #[test]
     pub fn test_to_text() {
@@ -139,4 +538,30 @@ mod test {
         let expected = include_str!("../fixtures/issue_body.txt");
         assert_eq!(issue.to_text(), expected);
     }
+
+    #[test]
+    pub fn test_parse_links() {
+        let header = concat!(
+            "<https://api.github.com/user/repos?page=3&per_page=100>; rel=\"next\", ",
+            "<https://api.github.com/user/repos?page=57&per_page=100>; rel=\"last\", ",
+            "<https://api.github.com/user/repos?page=1&per_page=100>; rel=\"first\", ",
+            "<https://api.github.com/user/repos?page=1&per_page=100>; rel=\"prev\""
+        );
+
+        let links = Links::parse(header);
+        assert_eq!(
+            links.next.as_deref(),
+            Some("https://api.github.com/user/repos?page=3&per_page=100")
+        );
+        assert_eq!(
+            links.first.as_deref(),
+            Some("https://api.github.com/user/repos?page=1&per_page=100")
+        );
+        assert_eq!(links.prev, links.first);
+        assert_eq!(links.last_page(), Some(57));
+
+        let links = Links::parse("");
+        assert_eq!(links, Links::default());
+        assert_eq!(links.last_page(), None);
+    }
 }
@@ -0,0 +1,36 @@
+use libauth::ApiClient;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_account_info() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/user"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "login": "octocat",
+            "id": 583231,
+            "name": "The Octocat",
+            "email": null,
+            "avatar_url": "https://avatars.githubusercontent.com/u/583231?v=4"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let info = common::client(&server)
+        .account_info()
+        .await
+        .expect("Unable to get account info");
+    assert_eq!(info.id, "583231");
+    assert_eq!(info.display_name, "The Octocat");
+    assert_eq!(info.email, None);
+    assert_eq!(
+        info.avatar_url.as_deref(),
+        Some("https://avatars.githubusercontent.com/u/583231?v=4")
+    );
+    assert_eq!(info.metadata["login"], "octocat");
+}
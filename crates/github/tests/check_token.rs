@@ -0,0 +1,53 @@
+use libauth::{AccessToken, Credentials};
+use libgithub::GithubClient;
+use wiremock::matchers::{body_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+async fn client(server: &MockServer, token: &str) -> GithubClient {
+    let creds = Credentials {
+        access_token: AccessToken::new(token.to_string()),
+        ..Default::default()
+    };
+
+    common::client_with_credentials(server, creds)
+}
+
+#[tokio::test]
+async fn test_check_token() {
+    let server = MockServer::start().await;
+
+    // base64("client-id:client-secret")
+    let basic_auth = "Basic Y2xpZW50LWlkOmNsaWVudC1zZWNyZXQ=";
+    Mock::given(method("POST"))
+        .and(path("/applications/client-id/token"))
+        .and(header("authorization", basic_auth))
+        .and(body_json(
+            serde_json::json!({ "access_token": "valid-token" }),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": 1,
+            "token": "valid-token",
+            "created_at": "2023-01-01T00:00:00Z"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/applications/client-id/token"))
+        .and(body_json(
+            serde_json::json!({ "access_token": "revoked-token" }),
+        ))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let valid = client(&server, "valid-token").await;
+    assert!(valid.check_token().await.expect("Unable to check token"));
+
+    let revoked = client(&server, "revoked-token").await;
+    assert!(!revoked.check_token().await.expect("Unable to check token"));
+}
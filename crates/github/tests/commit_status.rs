@@ -0,0 +1,124 @@
+use libauth::ApiError;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_get_combined_status() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/repos/octocat/hello-world/commits/main/status"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "state": "failure",
+            "sha": "6dcb09b5b57875f334f61aebed695e2e4193db5e",
+            "total_count": 2,
+            "statuses": [
+                {
+                    "context": "ci/lint",
+                    "state": "success",
+                    "target_url": "https://ci.example.com/1",
+                    "updated_at": "2023-04-01T10:00:00Z"
+                },
+                { "context": "ci/test", "state": "failure", "target_url": null }
+            ]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let status = common::client(&server)
+        .get_combined_status("octocat", "hello-world", "main")
+        .await
+        .expect("Unable to get status");
+
+    assert_eq!(status.state, "failure");
+    assert_eq!(status.statuses.len(), 2);
+    assert_eq!(
+        status.statuses[0].target_url.as_deref(),
+        Some("https://ci.example.com/1")
+    );
+    assert_eq!(status.statuses[1].context, "ci/test");
+}
+
+#[tokio::test]
+async fn test_list_check_runs() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/repos/octocat/hello-world/commits/main/check-runs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "total_count": 1,
+            "check_runs": [{
+                "id": 4,
+                "name": "build",
+                "status": "completed",
+                "conclusion": "success",
+                "html_url": "https://github.com/octocat/hello-world/runs/4"
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let runs = common::client(&server)
+        .list_check_runs("octocat", "hello-world", "main")
+        .await
+        .expect("Unable to list check runs");
+
+    assert_eq!(runs.total_count, 1);
+    assert_eq!(runs.check_runs[0].name, "build");
+    assert_eq!(runs.check_runs[0].conclusion.as_deref(), Some("success"));
+}
+
+#[tokio::test]
+async fn test_unknown_ref() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/repos/octocat/hello-world/commits/nope/status"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/repos/octocat/hello-world"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "name": "hello-world",
+            "full_name": "octocat/hello-world"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = common::client(&server)
+        .get_combined_status("octocat", "hello-world", "nope")
+        .await
+        .expect_err("Expected an error");
+    assert!(matches!(err, ApiError::BadRequest(msg) if msg.contains("nope")));
+}
+
+#[tokio::test]
+async fn test_unknown_repo() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/repos/octocat/missing/commits/main/check-runs"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/repos/octocat/missing"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = common::client(&server)
+        .list_check_runs("octocat", "missing", "main")
+        .await
+        .expect_err("Expected an error");
+    assert!(matches!(err, ApiError::ApiStatus { status: 404, .. }));
+}
@@ -0,0 +1,5 @@
+#![allow(dead_code)]
+
+use libgithub::GithubClient;
+
+libauth::mock_client_factory!(GithubClient);
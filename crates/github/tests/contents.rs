@@ -0,0 +1,110 @@
+use libgithub::types::Content;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_get_contents_file() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/repos/octocat/hello-world/contents/src/README.md"))
+        .and(query_param("ref", "main"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "type": "file",
+            "encoding": "base64",
+            "size": 13,
+            "name": "README.md",
+            "path": "src/README.md",
+            "sha": "3d21ec53a331a6f037a91c368710b99387d012c1",
+            "content": "SGVsbG8s\nIHdvcmxk\nIQ==\n"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let content = common::client(&server)
+        .get_contents("octocat/hello-world", "/src/README.md", Some("main"))
+        .await
+        .expect("Unable to get contents");
+    let Content::File(file) = content else {
+        panic!("Expected a file");
+    };
+    assert_eq!(file.path, "src/README.md");
+    assert_eq!(file.decode_content().unwrap(), b"Hello, world!");
+}
+
+#[tokio::test]
+async fn test_get_contents_directory() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/repos/octocat/hello-world/contents/src"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            { "type": "file", "size": 13, "name": "README.md", "path": "src/README.md", "sha": "abc" },
+            { "type": "dir", "size": 0, "name": "lib", "path": "src/lib", "sha": "def", "download_url": null }
+        ])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let content = common::client(&server)
+        .get_contents("octocat/hello-world", "src", None)
+        .await
+        .expect("Unable to get contents");
+    let Content::Directory(entries) = content else {
+        panic!("Expected a directory");
+    };
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].entry_type, "dir");
+    assert_eq!(entries[1].path, "src/lib");
+}
+
+#[tokio::test]
+async fn test_list_commits() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/repos/octocat/hello-world/commits"))
+        .and(query_param("page", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([{
+            "sha": "7fd1a60b01f91b314f59955a4e4d4e80d8edf11d",
+            "html_url": "https://github.com/octocat/hello-world/commit/7fd1a60b01f91b314f59955a4e4d4e80d8edf11d",
+            "commit": {
+                "message": "Merge pull request #6",
+                "author": {
+                    "name": "The Octocat",
+                    "email": "octocat@nowhere.com",
+                    "date": "2012-03-06T23:06:50Z"
+                }
+            },
+            "author": { "login": "octocat", "id": 583231 }
+        }])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let commits = common::client(&server)
+        .list_commits("octocat/hello-world", None)
+        .await
+        .expect("Unable to list commits");
+    assert_eq!(commits.next_page, None);
+    assert_eq!(commits.result.len(), 1);
+
+    let commit = &commits.result[0];
+    assert_eq!(commit.sha, "7fd1a60b01f91b314f59955a4e4d4e80d8edf11d");
+    assert_eq!(commit.commit.message, "Merge pull request #6");
+    assert_eq!(
+        commit
+            .commit
+            .author
+            .as_ref()
+            .map(|author| author.name.as_str()),
+        Some("The Octocat")
+    );
+    assert_eq!(
+        commit.author.as_ref().map(|user| user.login.as_str()),
+        Some("octocat")
+    );
+}
@@ -0,0 +1,81 @@
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+fn gist(id: &str, content: Option<&str>) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "description": "Hello world examples",
+        "public": true,
+        "html_url": format!("https://gist.github.com/{id}"),
+        "files": {
+            "hello_world.rb": {
+                "filename": "hello_world.rb",
+                "language": "Ruby",
+                "raw_url": format!("https://gist.githubusercontent.com/octocat/{id}/raw/hello_world.rb"),
+                "size": 167,
+                "content": content
+            }
+        },
+        "created_at": "2010-04-14T02:15:15Z",
+        "updated_at": "2011-06-20T11:34:15Z"
+    })
+}
+
+#[tokio::test]
+async fn test_list_gists() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/gists"))
+        .and(query_param("page", "1"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header(
+                    "link",
+                    format!("<{}/gists?page=2>; rel=\"next\"", server.uri()).as_str(),
+                )
+                .set_body_json(serde_json::json!([gist("aa5a315d61ae9438b18d", None)])),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let gists = common::client(&server)
+        .list_gists(None)
+        .await
+        .expect("Unable to list gists");
+    assert_eq!(gists.next_page, Some(2));
+    assert_eq!(gists.result.len(), 1);
+
+    let file = &gists.result[0].files["hello_world.rb"];
+    assert_eq!(file.language.as_deref(), Some("Ruby"));
+    assert_eq!(file.content, None);
+}
+
+#[tokio::test]
+async fn test_get_gist() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/gists/aa5a315d61ae9438b18d"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(gist("aa5a315d61ae9438b18d", Some("puts \"Hello, world!\""))),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let gist = common::client(&server)
+        .get_gist("aa5a315d61ae9438b18d")
+        .await
+        .expect("Unable to get gist");
+    assert_eq!(gist.description.as_deref(), Some("Hello world examples"));
+    assert!(gist.public);
+    assert_eq!(
+        gist.files["hello_world.rb"].content.as_deref(),
+        Some("puts \"Hello, world!\"")
+    );
+}
@@ -0,0 +1,77 @@
+use libauth::ApiError;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_list_emails() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/user/emails"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            { "email": "octocat@github.com", "primary": true, "verified": true, "visibility": "public" },
+            { "email": "octocat@example.com", "primary": false, "verified": false, "visibility": null }
+        ])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let emails = common::client(&server)
+        .list_emails()
+        .await
+        .expect("Unable to list emails");
+    assert_eq!(emails.len(), 2);
+
+    let primary = emails
+        .iter()
+        .find(|email| email.primary && email.verified)
+        .expect("No primary email");
+    assert_eq!(primary.email, "octocat@github.com");
+    assert_eq!(emails[1].visibility, None);
+}
+
+#[tokio::test]
+async fn test_list_emails_missing_scope() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/user/emails"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "message": "Not Found"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = common::client(&server)
+        .list_emails()
+        .await
+        .expect_err("Expected missing scope error");
+    assert!(matches!(err, ApiError::AuthError(msg) if msg.contains("user:email")));
+}
+
+#[tokio::test]
+async fn test_list_emails_rate_limited() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/user/emails"))
+        .respond_with(
+            ResponseTemplate::new(403)
+                .insert_header("x-ratelimit-remaining", "0")
+                .set_body_json(serde_json::json!({
+                    "message": "API rate limit exceeded"
+                })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = common::client(&server)
+        .list_emails()
+        .await
+        .expect_err("Expected rate limit error");
+    assert!(matches!(err, ApiError::ApiStatus { status: 403, .. }));
+}
@@ -0,0 +1,153 @@
+use futures::StreamExt;
+use libgithub::types::{IssueSort, IssueState, ListIssuesOptions, SortDirection};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+fn fixture(body: &str) -> serde_json::Value {
+    serde_json::from_str(body).expect("Invalid fixture")
+}
+
+#[tokio::test]
+async fn test_list_issues_paginates() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/issues"))
+        .and(query_param("filter", "all"))
+        .and(query_param("page", "1"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header(
+                    "link",
+                    format!("<{}/issues?page=2>; rel=\"next\"", server.uri()).as_str(),
+                )
+                .set_body_json(fixture(include_str!("../fixtures/issues_page_1.json"))),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/issues"))
+        .and(query_param("filter", "all"))
+        .and(query_param("page", "2"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(fixture(include_str!("../fixtures/issues_page_2.json"))),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let first = client
+        .list_issues(None, &Default::default())
+        .await
+        .expect("Unable to list issues");
+    assert_eq!(first.next_page, Some(2));
+    assert_eq!(first.result.len(), 1);
+    assert_eq!(first.result[0].title, "Crash when indexing large repos");
+
+    let second = client
+        .list_issues(first.next_page, &Default::default())
+        .await
+        .expect("Unable to list issues");
+    assert_eq!(second.next_page, None);
+    assert_eq!(second.result.len(), 1);
+    assert_eq!(second.result[0].state, "closed");
+}
+
+#[tokio::test]
+async fn test_list_issues_options() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/issues"))
+        .and(query_param("filter", "all"))
+        .and(query_param("state", "open"))
+        .and(query_param("labels", "bug,ui"))
+        .and(query_param("since", "2023-01-01T00:00:00Z"))
+        .and(query_param("sort", "updated"))
+        .and(query_param("direction", "asc"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(fixture(include_str!("../fixtures/issues_page_1.json"))),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let options = ListIssuesOptions {
+        state: Some(IssueState::Open),
+        labels: vec!["bug".into(), "ui".into()],
+        since: Some("2023-01-01T00:00:00Z".parse().expect("Invalid date")),
+        sort: Some(IssueSort::Updated),
+        direction: Some(SortDirection::Asc),
+        ..Default::default()
+    };
+
+    let issues = client
+        .list_issues(None, &options)
+        .await
+        .expect("Unable to list issues");
+    assert_eq!(issues.result.len(), 1);
+}
+
+#[tokio::test]
+async fn test_stream_issues() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/issues"))
+        .and(query_param("filter", "all"))
+        .and(query_param("page", "2"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(fixture(include_str!("../fixtures/issues_page_2.json"))),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/issues"))
+        .and(query_param("filter", "all"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header(
+                    "link",
+                    format!(
+                        "<{0}/issues?filter=all&page=2>; rel=\"next\", <{0}/issues?filter=all&page=2>; rel=\"last\"",
+                        server.uri()
+                    )
+                    .as_str(),
+                )
+                .set_body_json(fixture(include_str!("../fixtures/issues_page_1.json"))),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let stream = client
+        .stream_issues(&Default::default())
+        .await
+        .expect("Unable to stream issues");
+    assert_eq!(stream.last_page, Some(2));
+
+    let issues = stream
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .expect("Unable to fetch issues");
+    assert_eq!(issues.len(), 2);
+    assert_eq!(issues[0].title, "Crash when indexing large repos");
+    assert_eq!(issues[1].state, "closed");
+}
@@ -0,0 +1,32 @@
+use chrono::{TimeZone, Utc};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_rate_limit_headers() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/user"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("X-RateLimit-Limit", "5000")
+                .insert_header("X-RateLimit-Remaining", "4999")
+                .insert_header("X-RateLimit-Reset", "1700000000")
+                .set_body_json(serde_json::json!({ "login": "octocat", "id": 1 })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+    assert!(client.rate_limit().is_none());
+
+    client.get_user().await.expect("Unable to get user");
+    let limit = client.rate_limit().expect("No rate limit");
+    assert_eq!(limit.limit, 5000);
+    assert_eq!(limit.remaining, 4999);
+    assert_eq!(limit.reset, Utc.timestamp_opt(1_700_000_000, 0).unwrap());
+}
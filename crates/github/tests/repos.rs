@@ -0,0 +1,25 @@
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_missing_repo() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/repos/octocat/missing"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "message": "Not Found",
+            "documentation_url": "https://docs.github.com/rest/repos/repos#get-a-repository"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let repo = common::client(&server)
+        .get_repo("octocat/missing")
+        .await
+        .expect("Unable to get repo");
+    assert!(repo.is_none());
+}
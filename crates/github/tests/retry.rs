@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+mod common;
+
+use libauth::{ApiClient, RetryPolicy};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mock_rate_limited(server: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/user"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+        .up_to_n_times(1)
+        .mount(server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/user"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/user"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "login": "octocat",
+            "id": 1
+        })))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_retries_rate_limited_requests() {
+    let server = MockServer::start().await;
+    mock_rate_limited(&server).await;
+
+    let mut client = common::client(&server);
+    client.set_retry_policy(RetryPolicy {
+        max_retries: 2,
+        base_delay: Duration::from_millis(1),
+        ..Default::default()
+    });
+
+    let user = client.get_user().await.expect("Unable to get user");
+    assert_eq!(user.login, "octocat");
+    assert_eq!(server.received_requests().await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_no_retries_by_default() {
+    let server = MockServer::start().await;
+    mock_rate_limited(&server).await;
+
+    let Err(err) = common::client(&server).get_user().await else {
+        panic!("Expected an error");
+    };
+    assert!(err.is_retryable());
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_returns_last_error_when_exhausted() {
+    let server = MockServer::start().await;
+    mock_rate_limited(&server).await;
+
+    let mut client = common::client(&server);
+    client.set_retry_policy(RetryPolicy {
+        max_retries: 1,
+        base_delay: Duration::from_millis(1),
+        ..Default::default()
+    });
+
+    let Err(err) = client.get_user().await else {
+        panic!("Expected an error");
+    };
+    assert!(err.to_string().contains("503"));
+}
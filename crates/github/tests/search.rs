@@ -0,0 +1,113 @@
+use libauth::ApiError;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_search_repos() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/search/repositories"))
+        .and(query_param("q", "tokio language:rust"))
+        .and(query_param("page", "1"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header(
+                    "Link",
+                    format!(
+                        "<{0}/search/repositories?q=tokio&page=2>; rel=\"next\", <{0}/search/repositories?q=tokio&page=5>; rel=\"last\"",
+                        server.uri()
+                    )
+                    .as_str(),
+                )
+                .set_body_json(serde_json::json!({
+                    "total_count": 2,
+                    "incomplete_results": true,
+                    "items": [
+                        { "name": "tokio", "full_name": "tokio-rs/tokio", "owner": { "login": "tokio-rs", "id": 1 } },
+                        { "name": "mini-redis", "full_name": "tokio-rs/mini-redis", "owner": { "login": "tokio-rs", "id": 1 } }
+                    ]
+                })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let resp = common::client(&server)
+        .search_repos("tokio language:rust", None)
+        .await
+        .expect("Unable to search repos");
+
+    assert_eq!(resp.next_page, Some(2));
+    assert_eq!(resp.last_page, Some(5));
+    assert!(resp.result.incomplete_results);
+    assert_eq!(resp.result.items[0].full_name, "tokio-rs/tokio");
+}
+
+#[tokio::test]
+async fn test_search_code() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/search/code"))
+        .and(query_param("q", "ApiClient"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "total_count": 1,
+            "incomplete_results": false,
+            "items": [{
+                "name": "lib.rs",
+                "path": "crates/auth_core/src/lib.rs",
+                "sha": "abc123",
+                "html_url": "https://github.com/spyglass-search/third-party-apis/blob/main/crates/auth_core/src/lib.rs",
+                "repository": { "name": "third-party-apis", "full_name": "spyglass-search/third-party-apis" }
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let resp = common::client(&server)
+        .search_code("ApiClient", None)
+        .await
+        .expect("Unable to search code");
+
+    assert!(resp.next_page.is_none());
+    assert_eq!(resp.result.items[0].path, "crates/auth_core/src/lib.rs");
+    assert_eq!(
+        resp.result.items[0].repository.full_name,
+        "spyglass-search/third-party-apis"
+    );
+}
+
+#[tokio::test]
+async fn test_search_rate_limited() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/search/code"))
+        .respond_with(
+            ResponseTemplate::new(403)
+                .insert_header("X-RateLimit-Remaining", "0")
+                .insert_header("X-RateLimit-Reset", "1700000000")
+                .set_body_json(serde_json::json!({ "message": "API rate limit exceeded" })),
+        )
+        .mount(&server)
+        .await;
+
+    let err = common::client(&server)
+        .search_code("ApiClient", None)
+        .await
+        .err()
+        .expect("Expected an error");
+
+    match err {
+        ApiError::ApiStatus { status, body, .. } => {
+            assert_eq!(status, 403);
+            assert!(body.contains("X-RateLimit-Remaining: 0"));
+            assert!(body.contains("1700000000"));
+        }
+        err => panic!("Unexpected error: {err}"),
+    }
+}
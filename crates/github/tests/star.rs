@@ -0,0 +1,71 @@
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_is_starred() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/user/starred/octocat/hello-world"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/user/starred/octocat/spoon-knife"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+    assert!(client
+        .is_starred("octocat", "hello-world")
+        .await
+        .expect("Unable to check star"));
+    assert!(!client
+        .is_starred("octocat", "spoon-knife")
+        .await
+        .expect("Unable to check star"));
+}
+
+#[tokio::test]
+async fn test_star_and_unstar_repo() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("PUT"))
+        .and(path("/user/starred/octocat/hello-world"))
+        .and(header("Content-Length", "0"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("DELETE"))
+        .and(path("/user/starred/octocat/hello-world"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("PUT"))
+        .and(path("/user/starred/octocat/missing"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+    client
+        .star_repo("octocat", "hello-world")
+        .await
+        .expect("Unable to star repo");
+    client
+        .unstar_repo("octocat", "hello-world")
+        .await
+        .expect("Unable to unstar repo");
+    assert!(client.star_repo("octocat", "missing").await.is_err());
+}
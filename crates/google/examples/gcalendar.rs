@@ -1,5 +1,6 @@
 use dotenv_codegen::dotenv;
 use libauth::helpers::load_credentials;
+use libauth::store::FileStore;
 use libgoog::services::calendar::Calendar;
 use libgoog::{types::AuthScope, ClientType, GoogClient};
 
@@ -22,7 +23,7 @@ async fn main() -> anyhow::Result<()> {
         Default::default(),
     )?;
 
-    load_credentials(&mut client, &scopes, true).await;
+    load_credentials(&mut client, &FileStore::default(), &scopes, true).await;
 
     let user = client.get_user().await;
     let mut calendar = Calendar::new(client);
@@ -49,7 +50,13 @@ async fn main() -> anyhow::Result<()> {
     println!("\n------------------------------");
     println!("PRIMARY CALENDAR");
     let primary_events = calendar
-        .list_calendar_events("primary", Some(last_month), Some(future_month), None)
+        .list_calendar_events(
+            "primary",
+            Some(last_month),
+            Some(future_month),
+            None,
+            &Default::default(),
+        )
         .await?;
     for event in primary_events.items.iter().take(10) {
         // Skip recurring dates that don't have a next recurrence within our time
@@ -57,12 +64,7 @@ async fn main() -> anyhow::Result<()> {
         let date = if event.is_recurring() {
             event.next_recurrence().map(|x| x.to_rfc3339())
         } else {
-            Some(
-                event
-                    .start
-                    .date_time
-                    .map_or(event.start.date.clone(), |d| d.to_rfc3339()),
-            )
+            event.start.as_datetime().map(|d| d.to_rfc3339())
         };
 
         if let Some(date) = date {
@@ -82,7 +84,13 @@ async fn main() -> anyhow::Result<()> {
     for cal in cals.items.iter().take(5) {
         println!("\nCALENDAR: {} ({})", cal.summary, cal.id);
         if let Ok(events) = calendar
-            .list_calendar_events(&cal.id, Some(last_month), Some(future_month), None)
+            .list_calendar_events(
+                &cal.id,
+                Some(last_month),
+                Some(future_month),
+                None,
+                &Default::default(),
+            )
             .await
         {
             for event in events.items.iter().take(5) {
@@ -90,8 +98,9 @@ async fn main() -> anyhow::Result<()> {
                     println!(
                         "EVENT: {} {} ({} attendees)",
                         data.start
-                            .date_time
-                            .map_or(event.start.date.clone(), |d| d.to_rfc3339()),
+                            .as_datetime()
+                            .map(|d| d.to_rfc3339())
+                            .unwrap_or_default(),
                         data.summary,
                         data.attendees.len()
                     );
@@ -1,7 +1,9 @@
 use dotenv_codegen::dotenv;
 
 use libauth::helpers::load_credentials;
-use libgoog::{types::AuthScope, ClientType, GoogClient};
+use libauth::store::FileStore;
+use libgoog::types::{AuthScope, DriveScope};
+use libgoog::{ClientType, GoogClient};
 
 const REDIRECT_URL: &str = "http://127.0.0.1:8080";
 
@@ -23,9 +25,11 @@ async fn main() -> anyhow::Result<()> {
         Default::default(),
     )?;
 
-    load_credentials(&mut client, &scopes, true).await;
+    load_credentials(&mut client, &FileStore::default(), &scopes, true).await;
 
-    let files = client.list_files(None, None).await?;
+    let files = client
+        .list_files(None, None, &[], &DriveScope::default())
+        .await?;
 
     println!("------------------------------");
     println!("next_page: {:?}", files.next_page_token);
@@ -35,10 +39,13 @@ async fn main() -> anyhow::Result<()> {
     println!("------------------------------");
     for file in files.files.iter().take(5) {
         println!("{file:?}");
-        match client.get_file_metadata(&file.id).await {
+        match client
+            .get_file_metadata(&file.id, &DriveScope::default())
+            .await
+        {
             Ok(content) => {
                 println!("details: {content:?}");
-                // if let Ok(content) = client.download_file(&file.id).await {
+                // if let Ok(content) = client.download_file(&file.id, &DriveScope::default()).await {
                 //     println!("read {} bytes", content.len());
                 // }
                 println!("----------")
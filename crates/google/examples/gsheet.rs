@@ -2,6 +2,7 @@ use dotenv_codegen::dotenv;
 use std::env;
 
 use libauth::helpers::load_credentials;
+use libauth::store::FileStore;
 use libgoog::{services::spreadsheets::Sheets, types::AuthScope, ClientType, GoogClient};
 
 const REDIRECT_URL: &str = "http://127.0.0.1:8080";
@@ -30,7 +31,7 @@ async fn main() -> anyhow::Result<()> {
         Default::default(),
     )?;
 
-    load_credentials(&mut client, &scopes, true).await;
+    load_credentials(&mut client, &FileStore::default(), &scopes, true).await;
     let mut spreadsheet = Sheets::new(client);
 
     let sheet_data = spreadsheet.get(&sheet_id).await?;
@@ -54,7 +55,7 @@ async fn main() -> anyhow::Result<()> {
         for y in x.iter() {
             print!(" {y},");
         }
-        println!("");
+        println!();
     }
 
     let updated_values: Vec<String> = vec![
@@ -1,46 +1,113 @@
-use bytes::Bytes;
-use libauth::{AuthorizeOptions, OAuthParams};
+use bytes::{Bytes, BytesMut};
+use futures::{stream, Stream, StreamExt};
+use libauth::{AuthorizeOptions, OAuthParams, RetryPolicy};
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
-use tokio::sync::watch;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::sync::{watch, Mutex};
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use oauth2::basic::BasicClient;
 use oauth2::TokenResponse;
-use reqwest::Client;
+use reqwest::{Client, Method};
 // Alternatively, this can be oauth2::curl::http_client or a custom.
 use oauth2::basic::BasicTokenResponse;
 use oauth2::reqwest::async_http_client;
-use oauth2::{AuthorizationCode, CsrfToken, PkceCodeChallenge, PkceCodeVerifier, Scope};
+use oauth2::{
+    AuthorizationCode, CsrfToken, PkceCodeChallenge, PkceCodeVerifier, Scope,
+    StandardRevocableToken,
+};
 
 use libauth::{
-    auth_http_client, oauth_client, ApiClient, ApiError, AuthorizationRequest, Credentials,
+    check_status, get_json, json_response, oauth_client, send_json, AccountInfo, ApiClient,
+    ApiError, AuthorizationRequest, ClientConfig, ClientSettings, Credentials, TokenError,
 };
 
 pub mod services;
 pub mod types;
 
-use types::{File, FileType, Files, GoogUser};
+use types::{
+    ChangeList, DriveComments, DriveReplies, DriveReply, DriveScope, File, FileType, Files,
+    GoogUser, NewFile, Space, WatchChannel, WatchResponse,
+};
 
+#[derive(Clone)]
 pub enum ClientType {
     Calendar,
     Drive,
     Sheets,
+    YouTube,
 }
 
 const AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
 const TOKEN_URL: &str = "https://www.googleapis.com/oauth2/v3/token";
 const REVOKE_URL: &str = "https://oauth2.googleapis.com/revoke";
+const USERINFO_URL: &str = "https://www.googleapis.com/oauth2/v3/userinfo";
+
+/// Fields requested by `list_comments`. Drive does not return any comment fields
+/// unless they are explicitly requested.
+const COMMENT_FIELDS: &str =
+    "nextPageToken,comments(id,content,author,createdTime,resolved,replies)";
+/// Fields requested by `list_replies`.
+const REPLY_FIELDS: &str = "nextPageToken,replies(id,content,author,createdTime,action)";
+
+/// Fields requested by `get_file_metadata`.
+pub const DEFAULT_FILE_FIELDS: &[&str] = &[
+    "kind",
+    "id",
+    "name",
+    "mimeType",
+    "description",
+    "starred",
+    "parents",
+    "version",
+    "trashed",
+    "owners",
+    "sharingUser",
+    "lastModifyingUser",
+    "webViewLink",
+    "webContentLink",
+    "createdTime",
+    "modifiedTime",
+    "sharedWithMeTime",
+    "spaces",
+    "properties",
+    "appProperties",
+    "exportLinks",
+];
+
+/// Drive uploads use a separate host from the rest of the API.
+const DRIVE_UPLOAD_ENDPOINT: &str = "https://www.googleapis.com/upload/drive/v3";
+
+/// Size of each chunk sent by `upload_file_resumable`. Must be a multiple of 256 KiB.
+const UPLOAD_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+/// Number of consecutive failed chunks before giving up on a resumable upload.
+const MAX_UPLOAD_RETRIES: u32 = 3;
 
+/// Fields requested for each file by `list_files`.
+const LIST_FILE_FIELDS: &str =
+    "nextPageToken,files(kind,id,name,mimeType,properties,appProperties)";
+
+#[derive(Clone)]
 pub struct GoogClient {
     client_type: ClientType,
     endpoint: String,
+    upload_endpoint: String,
+    userinfo_endpoint: String,
     http: Client,
     pub oauth: BasicClient,
     pub credentials: Credentials,
     pub on_refresh_tx: watch::Sender<Credentials>,
     pub on_refresh_rx: watch::Receiver<Credentials>,
+    settings: ClientSettings,
+    refresh_lock: Arc<Mutex<()>>,
+    pub email: Option<String>,
+    /// Folder name & parent, keyed by id. Used to resolve file paths.
+    folder_cache: HashMap<String, (String, Option<String>)>,
+    root_folder_id: Option<String>,
 }
 
 #[async_trait]
@@ -50,18 +117,35 @@ impl ApiClient for GoogClient {
             ClientType::Calendar => "calendar.google.com".to_string(),
             ClientType::Drive => "drive.google.com".to_string(),
             ClientType::Sheets => "sheets.google.com".to_string(),
+            ClientType::YouTube => "youtube.com".to_string(),
         }
     }
 
     async fn account_id(&mut self) -> Result<String> {
-        let user = self.get_user().await?;
-        Ok(user.email)
+        if let Some(email) = &self.email {
+            Ok(email.clone())
+        } else {
+            let email = self.get_user().await?.email;
+            self.email = Some(email.clone());
+            Ok(email)
+        }
     }
 
     async fn account_metadata(&mut self) -> Option<Value> {
         None
     }
 
+    async fn account_info(&mut self) -> Result<AccountInfo> {
+        let user = self.get_user().await?;
+        Ok(AccountInfo {
+            id: user.sub.clone(),
+            display_name: user.name.clone().unwrap_or_else(|| user.email.clone()),
+            email: Some(user.email.clone()),
+            avatar_url: user.picture.clone(),
+            metadata: serde_json::to_value(&user)?,
+        })
+    }
+
     fn credentials(&self) -> Credentials {
         self.credentials.clone()
     }
@@ -72,7 +156,9 @@ impl ApiClient for GoogClient {
 
     fn set_credentials(&mut self, credentials: &Credentials) -> Result<()> {
         self.credentials = credentials.clone();
-        self.http = auth_http_client(credentials.access_token.secret())?;
+        self.http = self
+            .settings
+            .auth_http_client(credentials.access_token.secret())?;
         Ok(())
     }
 
@@ -80,6 +166,18 @@ impl ApiClient for GoogClient {
         self.on_refresh_rx.clone()
     }
 
+    fn refresh_lock(&self) -> Arc<Mutex<()>> {
+        self.refresh_lock.clone()
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        self.settings.retry_policy.clone()
+    }
+
+    fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.settings.retry_policy = policy;
+    }
+
     fn authorize(&self, scopes: &[String], options: &AuthorizeOptions) -> AuthorizationRequest {
         let scopes = scopes
             .iter()
@@ -123,7 +221,7 @@ impl ApiClient for GoogClient {
         &self,
         code: &str,
         pkce_verifier: Option<String>,
-    ) -> Result<BasicTokenResponse> {
+    ) -> Result<BasicTokenResponse, TokenError> {
         let code = AuthorizationCode::new(code.to_owned());
         let mut exchange = self.oauth.exchange_code(code);
         if let Some(pkce) = pkce_verifier {
@@ -132,11 +230,45 @@ impl ApiClient for GoogClient {
 
         match exchange.request_async(async_http_client).await {
             Ok(val) => Ok(val),
-            Err(err) => Err(anyhow!(err.to_string())),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Also retries error bodies with a retryable code, which `call` can't see
+    /// since they come with a 200.
+    async fn call_json(
+        &mut self,
+        endpoint: &str,
+        query: &[(String, String)],
+    ) -> Result<Value, ApiError> {
+        let policy = self.retry_policy();
+        let mut attempt = 0;
+        loop {
+            match check_error(get_json(self, endpoint, query).await?) {
+                Err(err) if err.is_retryable() && attempt < policy.max_retries => {
+                    let delay = policy.delay(attempt, None);
+                    attempt += 1;
+                    log::debug!("Retrying {endpoint} in {delay:?}: {err}");
+                    tokio::time::sleep(delay).await;
+                }
+                res => return res,
+            }
         }
     }
 
-    async fn refresh_credentials(&mut self) -> Result<()> {
+    async fn post_json(&mut self, endpoint: &str, body: Value) -> Result<Value, ApiError> {
+        check_error(send_json(self, Method::POST, endpoint, Some(body)).await?)
+    }
+
+    async fn put_json(&mut self, endpoint: &str, body: Value) -> Result<Value, ApiError> {
+        check_error(send_json(self, Method::PUT, endpoint, Some(body)).await?)
+    }
+
+    async fn delete(&mut self, endpoint: &str) -> Result<Value, ApiError> {
+        check_error(send_json(self, Method::DELETE, endpoint, None).await?)
+    }
+
+    async fn refresh_credentials(&mut self) -> Result<(), TokenError> {
         if let Some(refresh_token) = &self.credentials.refresh_token {
             let new_token = self
                 .oauth
@@ -145,13 +277,32 @@ impl ApiClient for GoogClient {
                 .await?;
 
             self.credentials.refresh_token(&new_token);
-            self.http = auth_http_client(new_token.access_token().secret())?;
+            self.http = self
+                .settings
+                .auth_http_client(new_token.access_token().secret())?;
             // Let any listeners know the credentials have been updated.
-            self.on_refresh_tx.send(self.credentials.clone())?;
+            self.on_refresh_tx
+                .send(self.credentials.clone())
+                .map_err(anyhow::Error::from)?;
         }
 
         Ok(())
     }
+
+    /// Revokes the refresh token if available, which also invalidates any
+    /// access tokens issued from it.
+    async fn revoke(&self) -> Result<()> {
+        let token = match &self.credentials.refresh_token {
+            Some(refresh_token) => StandardRevocableToken::RefreshToken(refresh_token.clone()),
+            None => StandardRevocableToken::AccessToken(self.credentials.access_token.clone()),
+        };
+
+        self.oauth
+            .revoke_token(token)?
+            .request_async(async_http_client)
+            .await?;
+        Ok(())
+    }
 }
 
 impl GoogClient {
@@ -161,11 +312,31 @@ impl GoogClient {
         client_secret: &str,
         redirect_url: &str,
         creds: Credentials,
+    ) -> anyhow::Result<Self> {
+        Self::new_with_config(
+            client_type,
+            client_id,
+            client_secret,
+            redirect_url,
+            creds,
+            ClientConfig::default(),
+        )
+    }
+
+    /// Same as `new`, with timeouts etc. applied to every request.
+    pub fn new_with_config(
+        client_type: ClientType,
+        client_id: &str,
+        client_secret: &str,
+        redirect_url: &str,
+        creds: Credentials,
+        config: ClientConfig,
     ) -> anyhow::Result<Self> {
         let endpoint = match client_type {
             ClientType::Calendar => "https://www.googleapis.com/calendar/v3".to_string(),
             ClientType::Drive => "https://www.googleapis.com/drive/v3".to_string(),
             ClientType::Sheets => "https://sheets.googleapis.com/v4".to_string(),
+            ClientType::YouTube => "https://www.googleapis.com/youtube/v3".to_string(),
         };
 
         let params = OAuthParams {
@@ -177,25 +348,265 @@ impl GoogClient {
             revoke_url: Some(REVOKE_URL.to_string()),
         };
 
+        let settings = ClientSettings::new(config);
         let (tx, rx) = watch::channel(creds.clone());
         Ok(GoogClient {
             client_type,
             endpoint,
-            http: auth_http_client(creds.access_token.secret())?,
-            oauth: oauth_client(&params),
+            upload_endpoint: DRIVE_UPLOAD_ENDPOINT.to_string(),
+            userinfo_endpoint: USERINFO_URL.to_string(),
+            http: settings.auth_http_client(creds.access_token.secret())?,
+            oauth: oauth_client(&params)?,
             credentials: creds,
             on_refresh_tx: tx,
             on_refresh_rx: rx,
+            settings,
+            refresh_lock: Arc::new(Mutex::new(())),
+            email: None,
+            folder_cache: HashMap::new(),
+            root_folder_id: None,
         })
     }
 
-    pub async fn download_file(&mut self, file_id: &str) -> Result<Bytes> {
+    /// Override the base API URL, e.g. to target a mock server.
+    pub fn set_endpoint(&mut self, endpoint: &str) {
+        self.endpoint = endpoint.trim_end_matches('/').to_string();
+    }
+
+    /// Override the user agent sent with API requests.
+    pub fn set_user_agent(&mut self, user_agent: &str) -> Result<()> {
+        self.settings.user_agent = user_agent.to_string();
+        self.http = self
+            .settings
+            .auth_http_client(self.credentials.access_token.secret())?;
+        Ok(())
+    }
+
+    /// Override the base URL used for Drive uploads, e.g. to target a mock server.
+    pub fn set_upload_endpoint(&mut self, endpoint: &str) {
+        self.upload_endpoint = endpoint.trim_end_matches('/').to_string();
+    }
+
+    /// Override the URL `get_user` fetches the user's profile from.
+    pub fn set_userinfo_endpoint(&mut self, endpoint: &str) {
+        self.userinfo_endpoint = endpoint.to_string();
+    }
+
+    /// Create a new file with `data` as its content using a multipart upload.
+    pub async fn upload_file(
+        &mut self,
+        name: &str,
+        mime_type: &str,
+        parents: &[String],
+        data: Bytes,
+    ) -> Result<File, ApiError> {
+        let mut endpoint = self.upload_endpoint.to_string();
+        endpoint.push_str("/files");
+
+        let metadata = serde_json::json!({
+            "name": name,
+            "mimeType": mime_type,
+            "parents": parents,
+        });
+
+        // Multipart upload requires a `multipart/related` body with the metadata
+        // first, followed by the media.
+        let boundary = format!("spyglass-{}", CsrfToken::new_random().secret());
+        let mut body = Vec::with_capacity(data.len() + 512);
+        body.extend_from_slice(
+            format!(
+                "--{boundary}\r\nContent-Type: application/json; charset=UTF-8\r\n\r\n{metadata}\r\n"
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(
+            format!("--{boundary}\r\nContent-Type: {mime_type}\r\n\r\n").as_bytes(),
+        );
+        body.extend_from_slice(&data);
+        body.extend_from_slice(format!("\r\n--{boundary}--").as_bytes());
+
+        let params = vec![
+            ("uploadType".to_string(), "multipart".to_string()),
+            ("fields".to_string(), DEFAULT_FILE_FIELDS.join(",")),
+        ];
+
+        let client = self.get_check_client().await?;
+        let resp = client
+            .post(endpoint)
+            .query(&params)
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                format!("multipart/related; boundary={boundary}"),
+            )
+            .body(body)
+            .send()
+            .await?;
+
+        serde_json::from_value::<File>(check_error(json_response(resp).await?)?)
+            .map_err(ApiError::SerdeError)
+    }
+
+    /// Upload `size` bytes from `reader` in chunks using a resumable upload session.
+    /// If a chunk fails to send, the session is queried for the last byte the
+    /// server received & the upload resumes from there.
+    pub async fn upload_file_resumable<R>(
+        &mut self,
+        metadata: &NewFile,
+        mut reader: R,
+        size: u64,
+    ) -> Result<File, ApiError>
+    where
+        R: AsyncRead + Unpin + Send,
+    {
+        let mut endpoint = self.upload_endpoint.to_string();
+        endpoint.push_str("/files");
+
+        let params = vec![
+            ("uploadType".to_string(), "resumable".to_string()),
+            ("fields".to_string(), DEFAULT_FILE_FIELDS.join(",")),
+        ];
+
+        let client = self.get_check_client().await?;
+        let resp = client
+            .post(&endpoint)
+            .query(&params)
+            .header("X-Upload-Content-Type", metadata.mime_type.as_str())
+            .header("X-Upload-Content-Length", size)
+            .json(metadata)
+            .send()
+            .await
+            .map_err(|err| ApiError::from(err).with_endpoint(Method::POST, &endpoint))?;
+        let resp = check_status(resp)
+            .await
+            .map_err(|err| err.with_endpoint(Method::POST, &endpoint))?;
+
+        let session = resp
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|loc| loc.to_str().ok())
+            .ok_or_else(|| ApiError::BadRequest("Missing upload session URL".to_string()))?
+            .to_string();
+
+        // Bytes confirmed by the server & the unconfirmed bytes following them.
+        let mut offset = 0;
+        let mut pending = Bytes::new();
+        let mut failures = 0;
+        loop {
+            if pending.is_empty() && offset < size {
+                let len = (size - offset).min(UPLOAD_CHUNK_SIZE as u64) as usize;
+                let mut buf = vec![0; len];
+                reader
+                    .read_exact(&mut buf)
+                    .await
+                    .map_err(anyhow::Error::from)?;
+                pending = Bytes::from(buf);
+            }
+
+            let range = if pending.is_empty() {
+                format!("bytes */{size}")
+            } else {
+                let end = offset + pending.len() as u64 - 1;
+                format!("bytes {offset}-{end}/{size}")
+            };
+
+            let status = match self
+                .put_upload_chunk(&session, range, pending.clone())
+                .await
+            {
+                Ok(status) => {
+                    failures = 0;
+                    status
+                }
+                Err(err) if err.is_retryable() && failures < MAX_UPLOAD_RETRIES => {
+                    let delay = self.retry_policy().delay(failures, None);
+                    failures += 1;
+                    log::warn!("Upload chunk failed, checking upload status in {delay:?}: {err}");
+                    tokio::time::sleep(delay).await;
+                    let range = format!("bytes */{size}");
+                    match self.put_upload_chunk(&session, range, Bytes::new()).await {
+                        Ok(status) => status,
+                        Err(err) if err.is_retryable() => continue,
+                        Err(err) => return Err(err),
+                    }
+                }
+                Err(err) => return Err(err),
+            };
+
+            match status {
+                UploadStatus::Complete(file) => return Ok(*file),
+                UploadStatus::Incomplete(received) => {
+                    if received < offset || received > offset + pending.len() as u64 {
+                        return Err(ApiError::BadRequest(format!(
+                            "Unexpected upload progress: {received} of {size} bytes"
+                        )));
+                    }
+
+                    pending = pending.slice((received - offset) as usize..);
+                    offset = received;
+                }
+            }
+        }
+    }
+
+    /// Send a chunk of a resumable upload. An empty chunk w/ a `bytes */size`
+    /// range queries the status of the upload.
+    async fn put_upload_chunk(
+        &mut self,
+        session: &str,
+        range: String,
+        chunk: Bytes,
+    ) -> Result<UploadStatus, ApiError> {
+        let client = self.get_check_client().await?;
+        let resp = client
+            .put(session)
+            .header(reqwest::header::CONTENT_RANGE, range)
+            .body(chunk)
+            .send()
+            .await?;
+
+        // 308 "Resume Incomplete", w/ the range of bytes received so far, if any.
+        if resp.status() == reqwest::StatusCode::PERMANENT_REDIRECT {
+            let received = resp
+                .headers()
+                .get(reqwest::header::RANGE)
+                .and_then(|range| range.to_str().ok())
+                .and_then(|range| range.strip_prefix("bytes=0-"))
+                .and_then(|end| end.parse::<u64>().ok())
+                .map(|end| end + 1)
+                .unwrap_or_default();
+            return Ok(UploadStatus::Incomplete(received));
+        }
+
+        let file = serde_json::from_value::<File>(check_error(json_response(resp).await?)?)
+            .map_err(ApiError::SerdeError)?;
+        Ok(UploadStatus::Complete(Box::new(file)))
+    }
+
+    /// Download the file contents, exporting Google Docs, Sheets & Slides to a
+    /// plain text or Excel format. Use `download_file_stream` for large files.
+    pub async fn download_file(&mut self, file_id: &str, scope: &DriveScope) -> Result<Bytes> {
+        let mut stream = Box::pin(self.download_file_stream(file_id, scope).await?);
+        let mut data = BytesMut::new();
+        while let Some(chunk) = stream.next().await {
+            data.extend_from_slice(&chunk?);
+        }
+
+        Ok(data.freeze())
+    }
+
+    /// Same as `download_file` but yields the contents in chunks as they're
+    /// received instead of buffering the entire file in memory.
+    pub async fn download_file_stream(
+        &mut self,
+        file_id: &str,
+        scope: &DriveScope,
+    ) -> Result<impl Stream<Item = Result<Bytes>>> {
         let mut endpoint = self.endpoint.to_string();
         endpoint.push_str("/files/");
         endpoint.push_str(file_id);
 
-        let file_info = self.get_file_metadata(file_id).await?;
-        let mut params = Vec::new();
+        let file_info = self.get_file_metadata(file_id, scope).await?;
+        let mut params = scope.to_file_query();
         // If Google specific file, we need to export
         if file_info
             .mime_type
@@ -219,14 +630,24 @@ impl GoogClient {
             params.push(("alt".to_string(), "media".to_string()));
         }
 
+        // `call` refreshes the credentials if needed before the request is sent.
         let resp = self.call(&endpoint, &params).await?;
-        Ok(resp.bytes().await?)
+        let resp = check_status(resp)
+            .await
+            .map_err(|err| err.with_endpoint(Method::GET, &endpoint))?;
+        Ok(resp
+            .bytes_stream()
+            .map(|chunk| chunk.map_err(anyhow::Error::from)))
     }
 
+    /// List files in the given `spaces`, defaulting to the user's drive if empty.
+    /// `query` is a raw Drive query string, see `DriveQuery` to build one.
     pub async fn list_files(
         &mut self,
         next_page: Option<String>,
         query: Option<String>,
+        spaces: &[Space],
+        scope: &DriveScope,
     ) -> Result<Files, ApiError> {
         let mut endpoint = self.endpoint.to_string();
         endpoint.push_str("/files");
@@ -241,45 +662,374 @@ impl GoogClient {
             params.push(("q".to_string(), query));
         }
 
+        if !spaces.is_empty() {
+            let spaces = spaces
+                .iter()
+                .map(|space| space.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            params.push(("spaces".to_string(), spaces));
+        }
+
+        params.extend(scope.to_query());
+        params.push(("fields".to_string(), LIST_FILE_FIELDS.to_string()));
+
         params.push(("orderBy".to_string(), "viewedByMeTime desc".to_string()));
         serde_json::from_value::<Files>(self.call_json(&endpoint, &params).await?)
             .map_err(ApiError::SerdeError)
     }
 
-    pub async fn get_file_metadata(&mut self, id: &str) -> Result<File, ApiError> {
+    /// List comments on a file, including any replies to each comment.
+    pub async fn list_comments(
+        &mut self,
+        file_id: &str,
+        next_page: Option<String>,
+    ) -> Result<DriveComments, ApiError> {
+        let endpoint = format!("{}/files/{file_id}/comments", self.endpoint);
+
+        let mut params = vec![("fields".to_string(), COMMENT_FIELDS.to_string())];
+        if let Some(next_page) = next_page {
+            params.push(("pageToken".to_string(), next_page));
+        }
+
+        serde_json::from_value::<DriveComments>(self.call_json(&endpoint, &params).await?)
+            .map_err(ApiError::SerdeError)
+    }
+
+    /// List all replies to a comment, following pagination until exhausted.
+    pub async fn list_replies(
+        &mut self,
+        file_id: &str,
+        comment_id: &str,
+    ) -> Result<Vec<DriveReply>, ApiError> {
+        let endpoint = format!(
+            "{}/files/{file_id}/comments/{comment_id}/replies",
+            self.endpoint
+        );
+
+        let mut replies = Vec::new();
+        let mut next_page: Option<String> = None;
+        loop {
+            let mut params = vec![("fields".to_string(), REPLY_FIELDS.to_string())];
+            if let Some(next_page) = next_page {
+                params.push(("pageToken".to_string(), next_page));
+            }
+
+            let page =
+                serde_json::from_value::<DriveReplies>(self.call_json(&endpoint, &params).await?)
+                    .map_err(ApiError::SerdeError)?;
+            replies.extend(page.replies);
+
+            next_page = page.next_page_token;
+            if next_page.is_none() {
+                break;
+            }
+        }
+
+        Ok(replies)
+    }
+
+    /// Token marking the current state of the user's drive. Pass it to
+    /// `list_changes` to fetch everything that changes from now on.
+    pub async fn get_start_page_token(&mut self) -> Result<String, ApiError> {
+        let mut endpoint = self.endpoint.to_string();
+        endpoint.push_str("/changes/startPageToken");
+
+        let resp = self.call_json(&endpoint, &Vec::new()).await?;
+        resp.get("startPageToken")
+            .and_then(|token| token.as_str())
+            .map(|token| token.to_string())
+            .ok_or_else(|| ApiError::BadRequest("Missing startPageToken".to_string()))
+    }
+
+    /// List changes made since `page_token`. Keep following `next_page_token`
+    /// until the last page, which instead has `new_start_page_token` to poll
+    /// for future changes.
+    pub async fn list_changes(
+        &mut self,
+        page_token: &str,
+        scope: &DriveScope,
+    ) -> Result<ChangeList, ApiError> {
+        let mut endpoint = self.endpoint.to_string();
+        endpoint.push_str("/changes");
+
+        let fields = format!(
+            "nextPageToken,newStartPageToken,changes(fileId,removed,time,file({}))",
+            DEFAULT_FILE_FIELDS.join(",")
+        );
+        let mut params = vec![
+            ("pageToken".to_string(), page_token.to_string()),
+            ("fields".to_string(), fields),
+        ];
+        params.extend(scope.to_changes_query());
+
+        serde_json::from_value::<ChangeList>(self.call_json(&endpoint, &params).await?)
+            .map_err(ApiError::SerdeError)
+    }
+
+    /// Subscribe a webhook to changes in the user's drive, starting from
+    /// `page_token`. Notifications only signal that something changed, the
+    /// changes themselves still need to be fetched.
+    pub async fn watch_changes(
+        &mut self,
+        page_token: &str,
+        channel: WatchChannel,
+    ) -> Result<WatchResponse, ApiError> {
+        let mut endpoint = self.endpoint.to_string();
+        endpoint.push_str("/changes/watch");
+        let url = reqwest::Url::parse_with_params(&endpoint, &[("pageToken", page_token)])
+            .map_err(|err| ApiError::Other(err.into()))?;
+
+        let mut body = serde_json::json!({
+            "id": channel.id,
+            "type": "web_hook",
+            "address": channel.address,
+        });
+        if let Some(token) = channel.token {
+            body["token"] = Value::String(token);
+        }
+        if let Some(expiration) = channel.expiration {
+            body["expiration"] = Value::String(expiration.timestamp_millis().to_string());
+        }
+
+        serde_json::from_value::<WatchResponse>(self.post_json(url.as_str(), body).await?)
+            .map_err(ApiError::SerdeError)
+    }
+
+    /// Stop receiving notifications for a channel created by `watch_changes`.
+    pub async fn stop_channel(&mut self, id: &str, resource_id: &str) -> Result<(), ApiError> {
+        let mut endpoint = self.endpoint.to_string();
+        endpoint.push_str("/channels/stop");
+
+        let body = serde_json::json!({ "id": id, "resourceId": resource_id });
+
+        // Google replies with an empty 204, so there's no JSON to parse.
+        let client = self.get_check_client().await?;
+        let resp = client
+            .post(&endpoint)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|err| ApiError::from(err).with_endpoint(Method::POST, &endpoint))?;
+        check_status(resp)
+            .await
+            .map_err(|err| err.with_endpoint(Method::POST, &endpoint))?;
+        Ok(())
+    }
+
+    /// Pass a `DriveScope` with shared drives enabled to fetch files that live
+    /// in a shared drive.
+    pub async fn get_file_metadata(
+        &mut self,
+        id: &str,
+        scope: &DriveScope,
+    ) -> Result<File, ApiError> {
+        self.get_file_metadata_fields(id, DEFAULT_FILE_FIELDS, scope)
+            .await
+    }
+
+    /// Fetch the metadata for many files, running at most `concurrency` requests
+    /// at a time. Results are returned in the same order as `ids`, the outer
+    /// error is returned if the credentials couldn't be refreshed.
+    pub async fn get_files_metadata(
+        &mut self,
+        ids: &[String],
+        scope: &DriveScope,
+        concurrency: usize,
+    ) -> Result<Vec<Result<File, ApiError>>, ApiError> {
+        // Refresh once up front, otherwise every request that sees the expired
+        // token would try to refresh it.
+        self.get_check_client().await?;
+
+        let mut results = stream::iter(ids.iter().enumerate())
+            .map(|(idx, id)| {
+                let mut client = self.clone();
+                async move {
+                    let result = client.get_file_metadata(id, scope).await;
+                    (idx, result)
+                }
+            })
+            .buffer_unordered(concurrency.max(1))
+            .collect::<Vec<_>>()
+            .await;
+
+        results.sort_by_key(|(idx, _)| *idx);
+        Ok(results.into_iter().map(|(_, result)| result).collect())
+    }
+
+    /// Same as `get_file_metadata` but only requests the specified `fields`.
+    /// Nested fields can be requested using the Drive partial response syntax,
+    /// e.g. `owners(displayName,emailAddress)`.
+    pub async fn get_file_metadata_fields(
+        &mut self,
+        id: &str,
+        fields: &[&str],
+        scope: &DriveScope,
+    ) -> Result<File, ApiError> {
+        serde_json::from_value::<File>(self.file_metadata_json(id, fields, scope).await?)
+            .map_err(ApiError::SerdeError)
+    }
+
+    /// Metadata for a file as returned by Drive, with the default fields of
+    /// `get_file_metadata`. A value Drive changes the type of won't fail the call.
+    pub async fn get_file_metadata_raw(
+        &mut self,
+        id: &str,
+        scope: &DriveScope,
+    ) -> Result<Value, ApiError> {
+        self.file_metadata_json(id, DEFAULT_FILE_FIELDS, scope)
+            .await
+    }
+
+    async fn file_metadata_json(
+        &mut self,
+        id: &str,
+        fields: &[&str],
+        scope: &DriveScope,
+    ) -> Result<Value, ApiError> {
+        let mut params = scope.to_file_query();
+        params.push(("fields".to_string(), fields.join(",")));
+        self.call_json(&self.file_endpoint(id), &params).await
+    }
+
+    fn file_endpoint(&self, id: &str) -> String {
         let mut endpoint = self.endpoint.to_string();
         endpoint.push_str("/files/");
         endpoint.push_str(id);
+        endpoint
+    }
 
-        let params = vec![(
-            "fields".to_string(),
-            vec![
-                "kind",
-                "id",
-                "name",
-                "mimeType",
-                "description",
-                "starred",
-                "parents",
-                "version",
-                "sharingUser",
-                "lastModifyingUser",
-                "webViewLink",
-                "createdTime",
-                "modifiedTime",
-                "sharedWithMeTime",
-            ]
-            .join(","),
-        )];
-
-        serde_json::from_value::<File>(self.call_json(&endpoint, &params).await?)
-            .map_err(ApiError::SerdeError)
+    /// Resolve the human-readable folder path of a file, e.g. "/My Drive/Projects/report".
+    /// Files that are not in the user's drive (e.g. shared with the user) and whose
+    /// parents aren't accessible are placed under "/Shared with me".
+    pub async fn get_file_path(
+        &mut self,
+        file_id: &str,
+        scope: &DriveScope,
+    ) -> Result<String, ApiError> {
+        let root_id = self.root_folder_id().await?;
+        let file = self
+            .get_file_metadata_fields(file_id, &["id", "name", "parents"], scope)
+            .await?;
+
+        let mut names = vec![file.name];
+        let mut parent = file.parents.first().cloned();
+        let mut reached_root = file.id == root_id;
+        let mut visited = HashSet::from([file.id]);
+
+        while let Some(folder_id) = parent.take() {
+            // Protect against cycles in the folder hierarchy.
+            if !visited.insert(folder_id.clone()) {
+                break;
+            }
+
+            let Some((name, next)) = self.get_folder(&folder_id, scope).await? else {
+                // Parent isn't accessible to this user.
+                break;
+            };
+
+            names.push(name);
+            if folder_id == root_id {
+                reached_root = true;
+                break;
+            }
+
+            parent = next;
+        }
+
+        if !reached_root {
+            names.push("Shared with me".to_string());
+        }
+
+        names.reverse();
+        Ok(format!("/{}", names.join("/")))
+    }
+
+    async fn root_folder_id(&mut self) -> Result<String, ApiError> {
+        if let Some(root_id) = &self.root_folder_id {
+            return Ok(root_id.clone());
+        }
+
+        let root = self
+            .get_file_metadata_fields("root", &["id", "name"], &DriveScope::default())
+            .await?;
+        self.folder_cache.insert(root.id.clone(), (root.name, None));
+        self.root_folder_id = Some(root.id.clone());
+        Ok(root.id)
+    }
+
+    /// Name & first parent of a folder, using the cache when possible. `None`
+    /// if the folder isn't accessible to this user.
+    async fn get_folder(
+        &mut self,
+        folder_id: &str,
+        scope: &DriveScope,
+    ) -> Result<Option<(String, Option<String>)>, ApiError> {
+        if let Some(folder) = self.folder_cache.get(folder_id) {
+            return Ok(Some(folder.clone()));
+        }
+
+        let folder = match self
+            .get_file_metadata_fields(folder_id, &["id", "name", "parents"], scope)
+            .await
+        {
+            Ok(folder) => folder,
+            Err(err)
+                if matches!(
+                    err.status(),
+                    Some(reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::NOT_FOUND)
+                ) =>
+            {
+                return Ok(None)
+            }
+            Err(err) => return Err(err),
+        };
+        let entry = (folder.name, folder.parents.first().cloned());
+        self.folder_cache
+            .insert(folder_id.to_string(), entry.clone());
+        Ok(Some(entry))
     }
 
     /// User associated with this credential
     pub async fn get_user(&mut self) -> Result<GoogUser, ApiError> {
-        let endpoint = "https://www.googleapis.com/oauth2/v3/userinfo";
-        serde_json::from_value::<GoogUser>(self.call_json(endpoint, &Vec::new()).await?)
+        let endpoint = self.userinfo_endpoint.clone();
+        serde_json::from_value::<GoogUser>(self.call_json(&endpoint, &Vec::new()).await?)
             .map_err(ApiError::SerdeError)
     }
 }
+
+/// Result of sending a chunk of a resumable upload.
+enum UploadStatus {
+    Complete(Box<File>),
+    /// Number of bytes received by the server so far.
+    Incomplete(u64),
+}
+
+/// Some Google endpoints respond with a 200 and an `{ "error": { .. } }` body
+/// instead of an error status code. Convert those into an `ApiError`, keeping
+/// the error code as the status.
+fn check_error(value: Value) -> Result<Value, ApiError> {
+    let Some(error) = value.get("error").filter(|err| err.is_object()) else {
+        return Ok(value);
+    };
+
+    let message = error
+        .get("message")
+        .and_then(|msg| msg.as_str())
+        .unwrap_or("Unknown error")
+        .to_string();
+
+    match error
+        .get("code")
+        .and_then(|code| code.as_u64())
+        .and_then(|code| u16::try_from(code).ok())
+    {
+        Some(401) => Err(ApiError::AuthError(message)),
+        Some(status) => Err(ApiError::ApiStatus {
+            status,
+            body: value.to_string(),
+            url: None,
+        }),
+        None => Err(ApiError::BadRequest(message)),
+    }
+}
@@ -1,8 +1,10 @@
 use crate::types;
 use crate::GoogClient;
 use chrono::{DateTime, Utc};
-use libauth::{ApiClient, ApiError};
-use serde_json::from_value;
+use libauth::{json_response, ApiClient, ApiError};
+use reqwest::{Method, StatusCode};
+use serde_json::{from_value, json, Value};
+use std::collections::HashSet;
 
 pub struct Calendar {
     client: GoogClient,
@@ -30,15 +32,47 @@ impl Calendar {
         from_value(self.client.call_json(&endpoint, &params).await?).map_err(ApiError::SerdeError)
     }
 
+    /// Retrieve every calendar in the user's calendar list, following
+    /// `next_page_token` until all pages have been fetched.
+    pub async fn list_all_calendars(&mut self) -> Result<Vec<types::CalendarList>, ApiError> {
+        let mut calendars = Vec::new();
+        let mut seen_tokens = HashSet::new();
+        let mut next_page = None;
+        loop {
+            let mut page = self.list_calendars(next_page).await?;
+            calendars.append(&mut page.items);
+
+            next_page = match page.next_page_token {
+                Some(token) if seen_tokens.insert(token.clone()) => Some(token),
+                Some(token) => {
+                    log::warn!("calendarList returned page token {token} twice, stopping");
+                    break;
+                }
+                None => break,
+            };
+        }
+
+        Ok(calendars)
+    }
+
     /// Retrieve all events for a calendar.
-    /// Use the id "primary" for the user's primary calendar.
+    /// Use the id "primary" for the user's primary calendar. By default recurring
+    /// events are returned as a single master event, see `ListEventsOptions` to
+    /// expand them instead.
     pub async fn list_calendar_events(
         &mut self,
         calendar_id: &str,
         after: Option<DateTime<Utc>>,
         before: Option<DateTime<Utc>>,
         next_page: Option<String>,
+        options: &types::ListEventsOptions,
     ) -> Result<types::ListCalendarEventsResponse, ApiError> {
+        if options.order_by == types::EventOrder::StartTime && !options.single_events {
+            return Err(ApiError::BadRequest(
+                "Ordering by start time requires single_events".to_string(),
+            ));
+        }
+
         let mut endpoint = self.client.endpoint.to_string();
         endpoint.push_str(&format!("/calendars/{calendar_id}/events"));
 
@@ -48,7 +82,19 @@ impl Calendar {
             Vec::new()
         };
 
-        params.push(("orderBy".to_string(), "updated".to_string()));
+        params.push(("orderBy".to_string(), options.order_by.to_string()));
+        params.push((
+            "singleEvents".to_string(),
+            options.single_events.to_string(),
+        ));
+        if options.show_deleted || options.updated_min.is_some() {
+            params.push(("showDeleted".to_string(), "true".to_string()));
+        }
+
+        if let Some(updated_min) = options.updated_min {
+            params.push(("updatedMin".into(), updated_min.to_rfc3339()));
+        }
+
         if let Some(after) = after {
             params.push(("timeMin".into(), after.to_rfc3339()));
         }
@@ -60,6 +106,36 @@ impl Calendar {
         from_value(self.client.call_json(&endpoint, &params).await?).map_err(ApiError::SerdeError)
     }
 
+    /// Retrieve the events that changed since the `sync_token` was issued, i.e.
+    /// the `next_sync_token` of the last page of a previous listing. Deleted
+    /// events are included and `single_events` must match the original listing.
+    /// Returns `ApiError::SyncTokenExpired` if Google requires a full re-sync.
+    pub async fn list_calendar_events_delta(
+        &mut self,
+        calendar_id: &str,
+        sync_token: &str,
+        next_page: Option<String>,
+        single_events: bool,
+    ) -> Result<types::ListCalendarEventsResponse, ApiError> {
+        let mut endpoint = self.client.endpoint.to_string();
+        endpoint.push_str(&format!("/calendars/{calendar_id}/events"));
+
+        // Filters such as timeMin & orderBy can't be combined with a sync token.
+        let mut params = vec![
+            ("syncToken".to_string(), sync_token.to_string()),
+            ("singleEvents".to_string(), single_events.to_string()),
+        ];
+        if let Some(next_page) = next_page {
+            params.push(("pageToken".to_string(), next_page));
+        }
+
+        match self.client.call_json(&endpoint, &params).await {
+            Ok(value) => from_value(value).map_err(ApiError::SerdeError),
+            Err(err) if err.status() == Some(StatusCode::GONE) => Err(ApiError::SyncTokenExpired),
+            Err(err) => Err(err),
+        }
+    }
+
     /// Retrieve a single event from a calendar.
     /// Use the id "primary" for the user's primary calendar.
     pub async fn get_calendar_event(
@@ -67,9 +143,78 @@ impl Calendar {
         calendar_id: &str,
         event_id: &str,
     ) -> Result<types::CalendarEvent, ApiError> {
+        from_value(self.get_calendar_event_raw(calendar_id, event_id).await?)
+            .map_err(ApiError::SerdeError)
+    }
+
+    /// Retrieve an event as untyped JSON, e.g. to read extended properties
+    /// that `CalendarEvent` doesn't model.
+    pub async fn get_calendar_event_raw(
+        &mut self,
+        calendar_id: &str,
+        event_id: &str,
+    ) -> Result<Value, ApiError> {
         let mut endpoint = self.client.endpoint.to_string();
         endpoint.push_str(&format!("/calendars/{calendar_id}/events/{event_id}"));
-        from_value(self.client.call_json(&endpoint, &Vec::new()).await?)
-            .map_err(ApiError::SerdeError)
+        self.client.call_json(&endpoint, &Vec::new()).await
+    }
+
+    /// Create a new event in a calendar.
+    /// Use the id "primary" for the user's primary calendar. If `add_meet_link` is
+    /// set, the generated link is available through `CalendarEvent::meet_link`.
+    pub async fn create_event(
+        &mut self,
+        calendar_id: &str,
+        event: &types::NewCalendarEvent,
+    ) -> Result<types::CalendarEvent, ApiError> {
+        let mut endpoint = self.client.endpoint.to_string();
+        endpoint.push_str(&format!("/calendars/{calendar_id}/events"));
+
+        let mut body = serde_json::to_value(event)?;
+        if event.add_meet_link {
+            // Conference data is only read when this is set.
+            endpoint.push_str("?conferenceDataVersion=1");
+            body["conferenceData"] = json!({
+                "createRequest": {
+                    // Unique per request, used by Google to dedupe retries.
+                    "requestId": uuid::Uuid::new_v4().to_string(),
+                    "conferenceSolutionKey": { "type": "hangoutsMeet" }
+                }
+            });
+        }
+
+        from_value(self.client.post_json(&endpoint, body).await?).map_err(ApiError::SerdeError)
+    }
+
+    /// Create an event from a natural-language phrase, e.g. "Lunch with Sam
+    /// tomorrow at noon", letting Google work out the details.
+    /// Use the id "primary" for the user's primary calendar.
+    pub async fn quick_add(
+        &mut self,
+        calendar_id: &str,
+        text: &str,
+    ) -> Result<types::CalendarEvent, ApiError> {
+        let mut endpoint = self.client.endpoint.to_string();
+        endpoint.push_str(&format!("/calendars/{calendar_id}/events/quickAdd"));
+
+        let client = self.client.get_check_client().await?;
+        let resp = client
+            .post(&endpoint)
+            .query(&[("text", text)])
+            .send()
+            .await
+            .map_err(|err| ApiError::from(err).with_endpoint(Method::POST, &endpoint))?;
+
+        // Google responds with a 400 when it can't make sense of the text.
+        if resp.status() == StatusCode::BAD_REQUEST {
+            return Err(ApiError::BadRequest(format!(
+                "Unable to create an event from \"{text}\""
+            )));
+        }
+
+        let value = json_response(resp)
+            .await
+            .map_err(|err| err.with_endpoint(Method::POST, &endpoint))?;
+        from_value(value).map_err(ApiError::SerdeError)
     }
 }
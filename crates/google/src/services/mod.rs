@@ -1,2 +1,3 @@
 pub mod calendar;
 pub mod spreadsheets;
+pub mod youtube;
@@ -2,7 +2,9 @@ use std::collections::HashMap;
 
 use a1_notation::{Address, RangeOrCell, A1};
 use libauth::{ApiClient, ApiError};
-use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Number, Value};
 
 pub mod types;
 
@@ -34,20 +36,64 @@ impl Sheets {
         sheet_id: &str,
         cell_range: &str,
     ) -> Result<types::ValueRange, ApiError> {
-        let mut endpoint = self.client.endpoint.clone();
+        self.read_range_with_options(spreadsheet_id, sheet_id, cell_range, &Default::default())
+            .await
+    }
+
+    /// Same as `read_range`, e.g. to read unformatted values with
+    /// `ValueRenderOption::UnformattedValue`.
+    pub async fn read_range_with_options(
+        &mut self,
+        spreadsheet_id: &str,
+        sheet_id: &str,
+        cell_range: &str,
+        options: &types::ReadOptions,
+    ) -> Result<types::ValueRange, ApiError> {
         // Parse and validate cell range
         let mut notation = a1_notation::new(cell_range)
             .map_err(|_| ApiError::BadRequest("Invalid cell range".to_string()))?;
         notation = notation.with_sheet_name(sheet_id);
 
-        endpoint.push_str(&format!(
-            "/spreadsheets/{spreadsheet_id}/values/{}",
-            notation
-        ));
+        let endpoint = self.values_endpoint(spreadsheet_id, &notation.to_string())?;
+        let endpoint = with_options(&endpoint, options)?;
         serde_json::from_value::<types::ValueRange>(self.client.call_json(&endpoint, &[]).await?)
             .map_err(ApiError::SerdeError)
     }
 
+    /// Read multiple ranges in a single request. Each range is in A1 notation,
+    /// including the sheet name (e.g. "Sheet1!A1:B2"). Results are in the same
+    /// order as `ranges`.
+    pub async fn batch_get(
+        &mut self,
+        spreadsheet_id: &str,
+        ranges: &[String],
+    ) -> Result<Vec<types::ValueRange>, ApiError> {
+        self.batch_get_with_options(spreadsheet_id, ranges, &Default::default())
+            .await
+    }
+
+    /// Same as `batch_get` with options applied to every range.
+    pub async fn batch_get_with_options(
+        &mut self,
+        spreadsheet_id: &str,
+        ranges: &[String],
+        options: &types::ReadOptions,
+    ) -> Result<Vec<types::ValueRange>, ApiError> {
+        let mut endpoint = self.client.endpoint.clone();
+        endpoint.push_str(&format!("/spreadsheets/{spreadsheet_id}/values:batchGet"));
+
+        let query = ranges
+            .iter()
+            .map(|range| ("ranges".to_string(), range.to_string()))
+            .collect::<Vec<_>>();
+        let endpoint = with_options(&endpoint, options)?;
+        let resp = serde_json::from_value::<types::BatchGetValuesResponse>(
+            self.client.call_json(&endpoint, &query).await?,
+        )
+        .map_err(ApiError::SerdeError)?;
+        Ok(resp.value_ranges)
+    }
+
     pub async fn read_rows_as_map(
         &mut self,
         spreadsheet_id: &str,
@@ -59,21 +105,25 @@ impl Sheets {
         let start = if start <= 1 { 2 } else { start };
 
         let notation = a1_notation::new(&format!("{start}:{end}"))
-            .map_err(|_| ApiError::BadRequest("Invalid cell range".to_string()))?;
+            .map_err(|_| ApiError::BadRequest("Invalid cell range".to_string()))?
+            .with_sheet_name(sheet_id);
+        let header_notation = a1_notation::new("1:1")
+            .map_err(|_| ApiError::BadRequest("Invalid cell range".to_string()))?
+            .with_sheet_name(sheet_id);
 
-        // Read header
-        let headers = self
-            .read_range(spreadsheet_id, sheet_id, "1:1")
+        // Read the header & rows in one request
+        let mut ranges = self
+            .batch_get(
+                spreadsheet_id,
+                &[header_notation.to_string(), notation.to_string()],
+            )
             .await?
-            .values
-            .first()
-            .cloned()
+            .into_iter();
+        let headers = ranges
+            .next()
+            .and_then(|range| range.values.into_iter().next())
             .ok_or(ApiError::BadRequest("No headers found".to_string()))?;
-
-        // Read rows
-        let rows: ValueRange = self
-            .read_range(spreadsheet_id, sheet_id, &notation.to_string())
-            .await?;
+        let rows = ranges.next().unwrap_or_default();
 
         // Map rows to headers
         let mut results: Vec<HashMap<String, String>> = Vec::new();
@@ -92,6 +142,60 @@ impl Sheets {
         Ok(results)
     }
 
+    /// Read every row of a sheet into `T`, using the first row as the field names.
+    /// Columns where every value is a number or boolean are converted to that type,
+    /// otherwise values are left as strings. Empty cells are omitted so `Option`
+    /// or `#[serde(default)]` fields can be used for sparse columns.
+    pub async fn read_records<T>(
+        &mut self,
+        spreadsheet_id: &str,
+        sheet_id: &str,
+    ) -> Result<Vec<T>, ApiError>
+    where
+        T: DeserializeOwned,
+    {
+        // A quoted sheet name on its own refers to the entire sheet.
+        let range = format!("'{}'", sheet_id.replace('\'', "''"));
+        let endpoint = self.values_endpoint(spreadsheet_id, &range)?;
+
+        let mut rows = serde_json::from_value::<types::ValueRange>(
+            self.client.call_json(&endpoint, &[]).await?,
+        )
+        .map_err(ApiError::SerdeError)?
+        .values
+        .into_iter();
+
+        let headers = rows
+            .next()
+            .ok_or(ApiError::BadRequest("No headers found".to_string()))?;
+        let rows = rows.collect::<Vec<_>>();
+
+        let column_types = headers
+            .iter()
+            .enumerate()
+            .map(|(idx, _)| {
+                CellType::infer(
+                    rows.iter()
+                        .filter_map(|row| row.get(idx))
+                        .filter(|cell| !cell.is_empty()),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        rows.into_iter()
+            .map(|row| {
+                let mut record = Map::new();
+                for ((header, cell), cell_type) in headers.iter().zip(row).zip(&column_types) {
+                    if !cell.is_empty() {
+                        record.insert(header.to_owned(), cell_type.to_value(cell));
+                    }
+                }
+
+                serde_json::from_value::<T>(Value::Object(record)).map_err(ApiError::SerdeError)
+            })
+            .collect()
+    }
+
     pub async fn append(
         &mut self,
         spreadsheet_id: &str,
@@ -99,46 +203,28 @@ impl Sheets {
         values: &[Vec<String>],
         update_options: &types::UpdateRangeOptions,
     ) -> Result<types::AppendValuesResponse, ApiError> {
-        // Determine the cell range based on the number of values
+        // Span the width & height of the values, Google appends after the last
+        // row of the table found in this range.
+        let columns = values.iter().map(|row| row.len()).max().unwrap_or_default();
         let notation = A1 {
             sheet_name: Some(sheet_id.to_string()),
             reference: RangeOrCell::Range {
                 from: Address::new(0, 0),
-                to: Address::new(0, values.len()),
+                to: Address::new(columns.saturating_sub(1), values.len().saturating_sub(1)),
             },
         };
 
-        let notation = notation.to_string();
-        let mut endpoint = self.client.endpoint.clone();
-        endpoint.push_str(&format!(
-            "/spreadsheets/{spreadsheet_id}/values/{notation}:append"
-        ));
+        let endpoint = self.values_endpoint(spreadsheet_id, &format!("{notation}:append"))?;
 
         let updates: Vec<Vec<String>> = values.to_owned();
         let body = ValueRange::with_values(updates);
 
-        let client = self.client.get_check_client().await?;
-        let resp = client
-            .post(&endpoint)
-            .query(update_options)
-            .json(&body)
-            .send()
+        let endpoint = with_options(&endpoint, update_options)?;
+        let resp = self
+            .client
+            .post_json(&endpoint, serde_json::to_value(&body)?)
             .await?;
-
-        match resp.error_for_status() {
-            Ok(resp) => match resp.json::<types::AppendValuesResponse>().await {
-                Ok(res) => Ok(res),
-                Err(err) => Err(err.into()),
-            },
-            // Any status code from 400..599
-            Err(err) => {
-                if let Some(StatusCode::UNAUTHORIZED) = err.status() {
-                    Err(ApiError::AuthError("Unauthorized".to_owned()))
-                } else {
-                    Err(err.into())
-                }
-            }
-        }
+        serde_json::from_value(resp).map_err(ApiError::SerdeError)
     }
 
     pub async fn update_range(
@@ -153,32 +239,131 @@ impl Sheets {
             .map_err(|_| ApiError::BadRequest("Invalid cell range".to_string()))?;
         notation = notation.with_sheet_name(sheet_id);
 
+        let endpoint = self.values_endpoint(spreadsheet_id, &notation.to_string())?;
+        let body = ValueRange::with_values(updates.to_owned());
+
+        let endpoint = with_options(&endpoint, update_options)?;
+        let resp = self
+            .client
+            .put_json(&endpoint, serde_json::to_value(&body)?)
+            .await?;
+        serde_json::from_value(resp).map_err(ApiError::SerdeError)
+    }
+
+    /// Update multiple ranges at once. Each entry in `data` is a range in A1 notation
+    /// (including the sheet name, e.g. "Sheet1!A1:B2") and the values to write to it.
+    pub async fn batch_update_values(
+        &mut self,
+        spreadsheet_id: &str,
+        data: Vec<(String, Vec<Vec<String>>)>,
+        update_options: &types::UpdateRangeOptions,
+    ) -> Result<types::BatchUpdateValuesResponse, ApiError> {
         let mut endpoint = self.client.endpoint.clone();
-        endpoint.push_str(&format!("/spreadsheets/{spreadsheet_id}/values/{notation}"));
+        endpoint.push_str(&format!(
+            "/spreadsheets/{spreadsheet_id}/values:batchUpdate"
+        ));
 
-        let body = ValueRange::with_values(updates.to_owned());
+        let body = types::BatchUpdateValuesRequest {
+            options: update_options,
+            data: data
+                .into_iter()
+                .map(|(range, values)| ValueRange {
+                    range: Some(range),
+                    major_dimension: None,
+                    values,
+                })
+                .collect(),
+        };
 
-        let client = self.client.get_check_client().await?;
-        let resp = client
-            .put(&endpoint)
-            .query(update_options)
-            .json(&body)
-            .send()
+        let resp = self
+            .client
+            .post_json(&endpoint, serde_json::to_value(&body)?)
             .await?;
+        serde_json::from_value(resp).map_err(ApiError::SerdeError)
+    }
 
-        match resp.error_for_status() {
-            Ok(resp) => match resp.json::<types::UpdateValuesResponse>().await {
-                Ok(res) => Ok(res),
-                Err(err) => Err(err.into()),
-            },
-            // Any status code from 400..599
-            Err(err) => {
-                if let Some(StatusCode::UNAUTHORIZED) = err.status() {
-                    Err(ApiError::AuthError("Unauthorized".to_owned()))
-                } else {
-                    Err(err.into())
-                }
-            }
+    /// `/spreadsheets/{id}/values/{range}`, with the range percent-encoded
+    /// since sheet names may contain spaces, `/`, `#` etc.
+    fn values_endpoint(&self, spreadsheet_id: &str, range: &str) -> Result<String, ApiError> {
+        let mut url = reqwest::Url::parse(&self.client.endpoint)
+            .map_err(|err| ApiError::Other(err.into()))?;
+        url.path_segments_mut()
+            .map_err(|_| ApiError::BadRequest("Invalid Sheets endpoint".to_string()))?
+            .pop_if_empty()
+            .extend(["spreadsheets", spreadsheet_id, "values", range]);
+        Ok(url.to_string())
+    }
+}
+
+/// Append the read/update options to the endpoint as query params.
+fn with_options<T: Serialize>(endpoint: &str, options: &T) -> Result<String, ApiError> {
+    let params = match serde_json::to_value(options)? {
+        Value::Object(map) => map
+            .into_iter()
+            .map(|(key, value)| match value {
+                Value::String(value) => (key, value),
+                value => (key, value.to_string()),
+            })
+            .collect::<Vec<_>>(),
+        _ => Vec::new(),
+    };
+    if params.is_empty() {
+        return Ok(endpoint.to_string());
+    }
+
+    reqwest::Url::parse_with_params(endpoint, &params)
+        .map(|url| url.to_string())
+        .map_err(|err| ApiError::Other(err.into()))
+}
+
+/// Type of the values in a column, used by `read_records`.
+enum CellType {
+    Bool,
+    Number,
+    String,
+}
+
+impl CellType {
+    fn infer<'a>(mut cells: impl Iterator<Item = &'a String> + Clone) -> Self {
+        if cells.clone().all(|cell| parse_bool(cell).is_some()) {
+            CellType::Bool
+        } else if cells.all(|cell| parse_number(cell).is_some()) {
+            CellType::Number
+        } else {
+            CellType::String
         }
     }
+
+    fn to_value(&self, cell: String) -> Value {
+        match self {
+            CellType::Bool => parse_bool(&cell).map(Value::Bool),
+            CellType::Number => parse_number(&cell).map(Value::Number),
+            CellType::String => None,
+        }
+        .unwrap_or(Value::String(cell))
+    }
+}
+
+fn parse_bool(cell: &str) -> Option<bool> {
+    if cell.eq_ignore_ascii_case("true") {
+        Some(true)
+    } else if cell.eq_ignore_ascii_case("false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn parse_number(cell: &str) -> Option<Number> {
+    // Keep values like zip codes or ids with leading zeros as strings.
+    let digits = cell.trim_start_matches('-');
+    if digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0.") {
+        return None;
+    }
+
+    if let Ok(num) = cell.parse::<i64>() {
+        Some(num.into())
+    } else {
+        cell.parse::<f64>().ok().and_then(Number::from_f64)
+    }
 }
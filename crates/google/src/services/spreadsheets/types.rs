@@ -1,4 +1,5 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -7,9 +8,31 @@ pub struct ValueRange {
     pub range: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub major_dimension: Option<String>,
+    /// Omitted by Google when the range is empty. Unformatted numbers & booleans
+    /// are converted to strings.
+    #[serde(default, deserialize_with = "cells_as_strings")]
     pub values: Vec<Vec<String>>,
 }
 
+fn cells_as_strings<'de, D>(deserializer: D) -> Result<Vec<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let rows: Vec<Vec<Value>> = Deserialize::deserialize(deserializer)?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|cell| match cell {
+                    Value::String(cell) => cell,
+                    Value::Null => String::new(),
+                    cell => cell.to_string(),
+                })
+                .collect()
+        })
+        .collect())
+}
+
 impl ValueRange {
     pub fn with_values(values: Vec<Vec<String>>) -> Self {
         ValueRange {
@@ -20,6 +43,14 @@ impl ValueRange {
     }
 }
 
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BatchGetValuesResponse {
+    pub spreadsheet_id: String,
+    /// One per requested range, in the order requested.
+    pub value_ranges: Vec<ValueRange>,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Spreadsheet {
@@ -53,6 +84,27 @@ pub struct UpdateRangeOptions {
     response_date_time_render_option: DateTimeRenderOption,
 }
 
+/// Options for reading values, unset options use Google's defaults.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_render_option: Option<ValueRenderOption>,
+    /// Ignored when values are formatted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date_time_render_option: Option<DateTimeRenderOption>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub major_dimension: Option<Dimension>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Dimension {
+    #[default]
+    Rows,
+    Columns,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AppendValuesResponse {
@@ -71,6 +123,25 @@ pub struct UpdateValuesResponse {
     updated_cells: usize,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchUpdateValuesRequest<'a> {
+    #[serde(flatten)]
+    pub options: &'a UpdateRangeOptions,
+    pub data: Vec<ValueRange>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BatchUpdateValuesResponse {
+    pub spreadsheet_id: String,
+    pub total_updated_rows: usize,
+    pub total_updated_columns: usize,
+    pub total_updated_cells: usize,
+    pub total_updated_sheets: usize,
+    pub responses: Vec<UpdateValuesResponse>,
+}
+
 #[derive(Default, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum DateTimeRenderOption {
@@ -90,7 +161,6 @@ pub enum ValueInputOption {
 
 #[derive(Default, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
-
 pub enum ValueRenderOption {
     #[default]
     FormattedValue,
@@ -0,0 +1,61 @@
+use libauth::{ApiClient, ApiError};
+use serde_json::from_value;
+
+pub mod types;
+
+use crate::GoogClient;
+
+/// Max page size allowed by the YouTube Data API.
+const MAX_RESULTS: &str = "50";
+
+pub struct YouTube {
+    client: GoogClient,
+}
+
+impl YouTube {
+    pub fn new(client: GoogClient) -> Self {
+        YouTube { client }
+    }
+
+    /// Retrieve the channels the authenticated user is subscribed to.
+    pub async fn list_subscriptions(
+        &mut self,
+        page_token: Option<String>,
+    ) -> Result<types::ListResponse<types::Subscription>, ApiError> {
+        let mut endpoint = self.client.endpoint.to_string();
+        endpoint.push_str("/subscriptions");
+
+        let mut params = vec![
+            ("part".to_string(), "snippet".to_string()),
+            ("mine".to_string(), "true".to_string()),
+            ("maxResults".to_string(), MAX_RESULTS.to_string()),
+        ];
+        if let Some(page_token) = page_token {
+            params.push(("pageToken".to_string(), page_token));
+        }
+
+        from_value(self.client.call_json(&endpoint, &params).await?).map_err(ApiError::SerdeError)
+    }
+
+    /// Retrieve the videos in a playlist, e.g. a channel's uploads or the user's
+    /// "liked videos" playlist.
+    pub async fn list_playlist_items(
+        &mut self,
+        playlist_id: &str,
+        page_token: Option<String>,
+    ) -> Result<types::ListResponse<types::PlaylistItem>, ApiError> {
+        let mut endpoint = self.client.endpoint.to_string();
+        endpoint.push_str("/playlistItems");
+
+        let mut params = vec![
+            ("part".to_string(), "snippet".to_string()),
+            ("playlistId".to_string(), playlist_id.to_string()),
+            ("maxResults".to_string(), MAX_RESULTS.to_string()),
+        ];
+        if let Some(page_token) = page_token {
+            params.push(("pageToken".to_string(), page_token));
+        }
+
+        from_value(self.client.call_json(&endpoint, &params).await?).map_err(ApiError::SerdeError)
+    }
+}
@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A page of results from one of the YouTube list endpoints.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListResponse<T> {
+    pub next_page_token: Option<String>,
+    pub prev_page_token: Option<String>,
+    #[serde(default)]
+    pub page_info: PageInfo,
+    #[serde(default = "Vec::new")]
+    pub items: Vec<T>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PageInfo {
+    pub total_results: u32,
+    pub results_per_page: u32,
+}
+
+/// The resource a subscription or playlist item points to.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ResourceId {
+    pub kind: String,
+    /// Set for subscriptions.
+    pub channel_id: Option<String>,
+    /// Set for playlist items.
+    pub video_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Subscription {
+    pub id: String,
+    pub snippet: SubscriptionSnippet,
+}
+
+impl Subscription {
+    /// Id of the channel subscribed to.
+    pub fn channel_id(&self) -> Option<&str> {
+        self.snippet.resource_id.channel_id.as_deref()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionSnippet {
+    /// Title of the channel subscribed to.
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// When the user subscribed.
+    pub published_at: DateTime<Utc>,
+    pub resource_id: ResourceId,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistItem {
+    pub id: String,
+    pub snippet: PlaylistItemSnippet,
+}
+
+impl PlaylistItem {
+    pub fn video_id(&self) -> Option<&str> {
+        self.snippet.resource_id.video_id.as_deref()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaylistItemSnippet {
+    /// Title of the video.
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// When the video was added to the playlist.
+    pub published_at: DateTime<Utc>,
+    /// Channel that owns the playlist.
+    pub channel_id: String,
+    pub playlist_id: String,
+    #[serde(default)]
+    pub position: u32,
+    pub resource_id: ResourceId,
+    /// Channel that uploaded the video, missing for private & deleted videos.
+    pub video_owner_channel_id: Option<String>,
+    pub video_owner_channel_title: Option<String>,
+}
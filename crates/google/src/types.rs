@@ -1,7 +1,9 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 
 use anyhow::anyhow;
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, NaiveDate, SecondsFormat, TimeZone, Utc};
+use libauth::Document;
 pub use rrule::Tz;
 use rrule::{RRule, RRuleSet};
 use serde::{Deserialize, Serialize};
@@ -25,11 +27,85 @@ pub struct CalendarAttendee {
 #[derive(Debug, Default, Deserialize, Serialize)]
 #[serde(default, rename_all = "camelCase")]
 pub struct CalendarTime {
+    #[serde(skip_serializing_if = "String::is_empty")]
     pub date: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub date_time: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "String::is_empty")]
     pub time_zone: String,
 }
 
+impl CalendarTime {
+    /// Returns true if this only has a date, i.e. the event lasts all day.
+    pub fn is_all_day(&self) -> bool {
+        self.date_time.is_none() && !self.date.is_empty()
+    }
+
+    /// Resolves this into a single datetime. If `date_time` is not set, `date` is
+    /// used at midnight in `time_zone` (falling back to UTC if the time zone is
+    /// unknown).
+    pub fn as_datetime(&self) -> Option<DateTime<Tz>> {
+        let tz = chrono_tz::Tz::from_str(&self.time_zone)
+            .map(Tz::from)
+            .unwrap_or(Tz::UTC);
+
+        if let Some(date_time) = self.date_time {
+            return Some(date_time.with_timezone(&tz));
+        }
+
+        let date = NaiveDate::parse_from_str(&self.date, "%Y-%m-%d").ok()?;
+        tz.from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+            .earliest()
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EventAttachment {
+    /// URL link to the attachment.
+    pub file_url: String,
+    pub title: String,
+    pub mime_type: String,
+    /// ID of the attached file. Only available for Google Drive files.
+    pub file_id: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ConferenceEntryPoint {
+    /// One of "video", "phone", "sip" or "more".
+    pub entry_point_type: String,
+    pub uri: String,
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ConferenceData {
+    pub conference_id: Option<String>,
+    pub entry_points: Vec<ConferenceEntryPoint>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Reminder {
+    /// The method used by this reminder, either "email" or "popup".
+    pub method: String,
+    /// Number of minutes before the start of the event when the reminder should trigger.
+    pub minutes: i32,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EventReminders {
+    /// Whether the default reminders of the calendar apply to the event.
+    pub use_default: bool,
+    /// If the event doesn't use the default reminders, this lists the reminders
+    /// specific to the event.
+    pub overrides: Vec<Reminder>,
+}
+
 #[derive(Debug, Default, Deserialize, Serialize)]
 #[serde(default, rename_all = "camelCase")]
 pub struct CalendarEvent {
@@ -54,6 +130,15 @@ pub struct CalendarEvent {
     pub end: CalendarTime,
     pub recurrence: Vec<String>,
     pub recurring_event_id: String,
+    /// File attachments for the event (e.g. linked Google Drive files).
+    pub attachments: Vec<EventAttachment>,
+    /// Conference-related information, such as details of a Google Meet conference.
+    pub conference_data: Option<ConferenceData>,
+    /// The color of the event. This is an ID referring to an entry in the event
+    /// section of the colors definition. Optional.
+    pub color_id: Option<String>,
+    /// Information about the event's reminders for the authenticated user.
+    pub reminders: EventReminders,
 }
 
 impl CalendarEvent {
@@ -61,6 +146,28 @@ impl CalendarEvent {
         !self.recurrence.is_empty()
     }
 
+    /// Minutes before the start of the event at which reminders are triggered.
+    /// Note that if the event uses the calendar's default reminders these are
+    /// not included in the event.
+    pub fn reminder_minutes(&self) -> Vec<i32> {
+        self.reminders
+            .overrides
+            .iter()
+            .map(|reminder| reminder.minutes)
+            .collect()
+    }
+
+    /// URL of the video entry point for the event's conference (e.g. the Google Meet link),
+    /// if one exists.
+    pub fn meet_link(&self) -> Option<String> {
+        self.conference_data.as_ref().and_then(|data| {
+            data.entry_points
+                .iter()
+                .find(|entry| entry.entry_point_type == "video")
+                .map(|entry| entry.uri.clone())
+        })
+    }
+
     pub fn next_recurrence(&self) -> Option<DateTime<Utc>> {
         self.list_recurrences(1, None, None)
             .map(|x| x.first().map(|x| x.to_owned()))
@@ -113,6 +220,116 @@ impl CalendarEvent {
     }
 }
 
+/// Metadata for a file to be created with `GoogClient::upload_file_resumable`.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewFile {
+    pub name: String,
+    pub mime_type: String,
+    /// Ids of the folders to place the file in, defaults to the root folder.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub parents: Vec<String>,
+}
+
+/// Webhook to notify with `GoogClient::watch_changes`. The `address` must be an
+/// HTTPS URL on a domain that has been verified with Google.
+#[derive(Debug, Default, Clone)]
+pub struct WatchChannel {
+    /// Unique id for the channel, e.g. a UUID.
+    pub id: String,
+    pub address: String,
+    /// Sent back in the `X-Goog-Channel-Token` header of each notification.
+    pub token: Option<String>,
+    /// When the channel should stop, Google caps this at one week.
+    pub expiration: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WatchResponse {
+    pub id: String,
+    /// Needed along with the channel id to stop the channel.
+    pub resource_id: String,
+    pub resource_uri: String,
+    /// Milliseconds since the epoch.
+    pub expiration: Option<String>,
+}
+
+impl WatchResponse {
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expiration
+            .as_ref()
+            .and_then(|ms| ms.parse::<i64>().ok())
+            .and_then(|ms| Utc.timestamp_millis_opt(ms).single())
+    }
+}
+
+/// Event to be created with `Calendar::create_event`.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewCalendarEvent {
+    /// Title of the event.
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    pub start: CalendarTime,
+    pub end: CalendarTime,
+    /// Email addresses of the attendees to invite.
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_attendees"
+    )]
+    pub attendees: Vec<String>,
+    /// List of RRULE, EXRULE, RDATE and EXDATE lines for a recurring event.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recurrence: Vec<String>,
+    /// Request a Google Meet conference to be created & attached to the event.
+    #[serde(skip)]
+    pub add_meet_link: bool,
+}
+
+fn serialize_attendees<S>(emails: &[String], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    use serde::ser::SerializeSeq;
+
+    let mut seq = serializer.serialize_seq(Some(emails.len()))?;
+    for email in emails {
+        seq.serialize_element(&serde_json::json!({ "email": email }))?;
+    }
+    seq.end()
+}
+
+/// Order of the events returned when listing calendar events.
+#[derive(Clone, Debug, Default, Display, PartialEq, Eq)]
+pub enum EventOrder {
+    /// Order by start date/time (ascending). Only available when expanding
+    /// recurring events into single events.
+    #[strum(serialize = "startTime")]
+    StartTime,
+    /// Order by last modification time (ascending).
+    #[default]
+    #[strum(serialize = "updated")]
+    Updated,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ListEventsOptions {
+    /// Whether to expand recurring events into instances and only return single
+    /// one-off events and instances of recurring events, but not the underlying
+    /// recurring events themselves.
+    pub single_events: bool,
+    pub order_by: EventOrder,
+    /// Whether to include deleted events (with status equals "cancelled") in the result.
+    pub show_deleted: bool,
+    /// Only return events modified after this time. Deleted events are always
+    /// included when set so cancellations aren't missed.
+    pub updated_min: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Default, Deserialize, Serialize)]
 #[serde(default, rename_all = "camelCase")]
 pub struct ListCalendarEventsResponse {
@@ -148,6 +365,7 @@ pub struct CalendarListResponse {
 #[derive(Debug, Default, Deserialize, Serialize)]
 #[serde(default, rename_all = "camelCase")]
 pub struct FileUser {
+    /// Empty if Drive doesn't return a name for the user.
     pub display_name: String,
     // The email address of the user. This may not be present in certain contexts if
     // the user has not made their email address visible to the user.
@@ -163,17 +381,66 @@ pub struct File {
     pub id: String,
     pub name: String,
     pub mime_type: String,
-    pub description: String,
+    /// Omitted by Drive when the file has no description.
+    pub description: Option<String>,
     pub starred: bool,
+    pub trashed: bool,
     pub parents: Vec<String>,
     pub version: String,
+    /// Empty for files in shared drives, which are owned by the drive.
     pub owners: Vec<FileUser>,
-    pub sharing_user: FileUser,
-    pub last_modifying_user: FileUser,
-    pub web_view_link: String,
-    pub created_time: DateTime<Utc>,
+    /// Omitted for files that weren't shared with the user.
+    pub sharing_user: Option<FileUser>,
+    /// Omitted when Drive can't attribute the latest change, e.g. for some
+    /// files owned by the user.
+    pub last_modifying_user: Option<FileUser>,
+    pub web_view_link: Option<String>,
+    /// Direct download link, only set for binary files.
+    pub web_content_link: Option<String>,
+    pub created_time: Option<DateTime<Utc>>,
     pub modified_time: Option<DateTime<Utc>>,
     pub shared_with_me_time: Option<DateTime<Utc>>,
+    /// Spaces containing the file, e.g. "drive", "appDataFolder" or "photos".
+    pub spaces: Vec<String>,
+    /// Custom properties visible to all apps.
+    pub properties: HashMap<String, String>,
+    /// Custom properties private to the requesting app.
+    pub app_properties: HashMap<String, String>,
+    /// Links for exporting Google Workspace documents to specific formats,
+    /// keyed by MIME type.
+    pub export_links: HashMap<String, String>,
+}
+
+impl Document for File {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn title(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Drive only returns the file description, use `GoogClient::download_file`
+    /// for the file contents.
+    fn body_text(&self) -> String {
+        self.description.clone().unwrap_or_default()
+    }
+
+    fn url(&self) -> Option<String> {
+        self.web_view_link.clone()
+    }
+
+    fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.modified_time
+    }
+}
+
+impl File {
+    /// Direct export URL for this file in the requested MIME type (e.g. "application/pdf").
+    /// Only available for Google Workspace documents.
+    pub fn export_link(&self, mime: &str) -> Option<String> {
+        self.export_links.get(mime).cloned()
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -183,6 +450,197 @@ pub struct FileInfo {
     pub name: String,
     #[serde(rename = "mimeType")]
     pub mime_type: String,
+    #[serde(default)]
+    pub properties: HashMap<String, String>,
+    #[serde(default, rename = "appProperties")]
+    pub app_properties: HashMap<String, String>,
+}
+
+/// Storage spaces that can be searched when listing files.
+#[derive(Clone, Debug, Default, Display, PartialEq, Eq)]
+pub enum Space {
+    #[default]
+    #[strum(serialize = "drive")]
+    Drive,
+    /// Hidden app specific folder, requires the `drive.appdata` scope.
+    #[strum(serialize = "appDataFolder")]
+    AppDataFolder,
+    #[strum(serialize = "photos")]
+    Photos,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DriveReply {
+    pub id: String,
+    /// Plain text content of the reply.
+    pub content: String,
+    pub author: FileUser,
+    pub created_time: Option<DateTime<Utc>>,
+    /// Action taken on the parent comment, either "resolve" or "reopen".
+    pub action: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DriveReplies {
+    pub next_page_token: Option<String>,
+    pub replies: Vec<DriveReply>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DriveComment {
+    pub id: String,
+    /// Plain text content of the comment.
+    pub content: String,
+    pub author: FileUser,
+    pub created_time: Option<DateTime<Utc>>,
+    pub resolved: bool,
+    pub replies: Vec<DriveReply>,
+}
+
+/// Builds the `q` param for `GoogClient::list_files`, quoting & escaping values.
+/// Every condition must match.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DriveQuery {
+    conditions: Vec<String>,
+}
+
+impl DriveQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn created_after(self, time: DateTime<Utc>) -> Self {
+        self.with(format!("createdTime > {}", quote_time(time)))
+    }
+
+    pub fn modified_after(self, time: DateTime<Utc>) -> Self {
+        self.with(format!("modifiedTime > {}", quote_time(time)))
+    }
+
+    pub fn name_contains(self, name: &str) -> Self {
+        self.with(format!("name contains {}", quote(name)))
+    }
+
+    pub fn mime_type(self, mime_type: &str) -> Self {
+        self.with(format!("mimeType = {}", quote(mime_type)))
+    }
+
+    pub fn trashed(self, trashed: bool) -> Self {
+        self.with(format!("trashed = {trashed}"))
+    }
+
+    /// Only files directly inside the folder.
+    pub fn in_folder(self, folder_id: &str) -> Self {
+        self.with(format!("{} in parents", quote(folder_id)))
+    }
+
+    fn with(mut self, condition: String) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    /// Drive query string, `None` if there are no conditions.
+    pub fn build(&self) -> Option<String> {
+        if self.conditions.is_empty() {
+            None
+        } else {
+            Some(self.conditions.join(" and "))
+        }
+    }
+}
+
+/// Quote a string value, escaping backslashes & single quotes.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+fn quote_time(time: DateTime<Utc>) -> String {
+    format!("'{}'", time.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+/// Which drives to include when listing or fetching files. Defaults to only
+/// the user's own drive.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DriveScope {
+    /// Include files from every shared drive the user is a member of.
+    pub include_shared_drives: bool,
+    /// Restrict results to a single shared drive.
+    pub drive_id: Option<String>,
+}
+
+impl DriveScope {
+    fn supports_all_drives(&self) -> bool {
+        self.include_shared_drives || self.drive_id.is_some()
+    }
+
+    /// Query params for listing files.
+    pub fn to_query(&self) -> Vec<(String, String)> {
+        let mut query = self.to_file_query();
+        if let Some(drive_id) = &self.drive_id {
+            query.push(("includeItemsFromAllDrives".into(), "true".into()));
+            query.push(("corpora".into(), "drive".into()));
+            query.push(("driveId".into(), drive_id.clone()));
+        } else if self.include_shared_drives {
+            query.push(("includeItemsFromAllDrives".into(), "true".into()));
+            query.push(("corpora".into(), "allDrives".into()));
+        }
+
+        query
+    }
+
+    /// Query params for listing changes, which takes a `driveId` but no
+    /// `corpora`.
+    pub fn to_changes_query(&self) -> Vec<(String, String)> {
+        let mut query = self.to_file_query();
+        if self.supports_all_drives() {
+            query.push(("includeItemsFromAllDrives".into(), "true".into()));
+        }
+        if let Some(drive_id) = &self.drive_id {
+            query.push(("driveId".into(), drive_id.clone()));
+        }
+
+        query
+    }
+
+    /// Query params for fetching a single file.
+    pub fn to_file_query(&self) -> Vec<(String, String)> {
+        if self.supports_all_drives() {
+            vec![("supportsAllDrives".into(), "true".into())]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DriveComments {
+    pub next_page_token: Option<String>,
+    pub comments: Vec<DriveComment>,
+}
+
+/// Page of results from the Drive changes feed.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ChangeList {
+    pub changes: Vec<Change>,
+    /// Only set on the last page, used to poll for future changes.
+    pub new_start_page_token: Option<String>,
+    pub next_page_token: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Change {
+    pub file_id: String,
+    /// Whether the file was removed, e.g. deleted or access was revoked.
+    pub removed: bool,
+    /// Latest file metadata, not set for removed files.
+    pub file: Option<File>,
+    pub time: Option<DateTime<Utc>>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -202,6 +660,8 @@ pub enum AuthScope {
     CalendarEvents,
     #[strum(serialize = "https://www.googleapis.com/auth/drive.readonly")]
     Drive,
+    #[strum(serialize = "https://www.googleapis.com/auth/drive.appdata")]
+    DriveAppData,
     #[strum(serialize = "https://www.googleapis.com/auth/drive.activity.readonly")]
     DriveActivity,
     #[strum(serialize = "https://www.googleapis.com/auth/drive.metadata.readonly")]
@@ -221,6 +681,18 @@ pub enum AuthScope {
     YouTube,
 }
 
+impl AuthScope {
+    /// Read-only scopes for the user's email, calendars and Drive files.
+    pub fn default_read_scopes() -> Vec<String> {
+        vec![
+            AuthScope::Email.to_string(),
+            AuthScope::Calendar.to_string(),
+            AuthScope::Drive.to_string(),
+            AuthScope::DriveMetadata.to_string(),
+        ]
+    }
+}
+
 #[derive(AsRefStr, Debug, EnumString, PartialEq, Eq)]
 pub enum FileType {
     #[strum(serialize = "application/vnd.google-apps.document")]
@@ -234,13 +706,61 @@ pub enum FileType {
 #[derive(Debug, Default, Deserialize, Serialize)]
 #[serde(default, rename_all = "camelCase")]
 pub struct GoogUser {
+    /// Stable Google account identifier.
+    pub sub: String,
     pub email: String,
+    pub name: Option<String>,
+    pub picture: Option<String>,
 }
 
 #[cfg(test)]
 mod test {
-    use crate::types::{CalendarEvent, CalendarTime};
-    use chrono::TimeZone;
+    use crate::types::{CalendarEvent, CalendarTime, DriveQuery, File};
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_file_without_sharing_user() {
+        let file: File = serde_json::from_value(serde_json::json!({
+            "id": "file-id",
+            "name": "Notes",
+            "mimeType": "text/plain",
+            "lastModifyingUser": { "me": true }
+        }))
+        .expect("Unable to parse file");
+
+        assert!(file.sharing_user.is_none());
+        let user = file
+            .last_modifying_user
+            .as_ref()
+            .expect("Missing last modifying user");
+        assert!(user.is_me);
+        assert_eq!(user.display_name, "");
+        assert_eq!(user.email_address, None);
+
+        // A missing user must stay missing rather than become a default FileUser
+        let value = serde_json::to_value(&file).expect("Unable to serialize file");
+        assert!(value["sharingUser"].is_null());
+        let file: File = serde_json::from_value(value).expect("Unable to parse file");
+        assert!(file.sharing_user.is_none());
+        assert!(file.last_modifying_user.is_some_and(|user| user.is_me));
+    }
+
+    #[test]
+    fn test_drive_query() {
+        assert_eq!(DriveQuery::new().build(), None);
+
+        let query = DriveQuery::new()
+            .modified_after(Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap())
+            .name_contains("Bob's \\ notes")
+            .mime_type("application/pdf")
+            .trashed(false)
+            .in_folder("folder-id");
+        assert_eq!(
+            query.build().unwrap(),
+            "modifiedTime > '2024-01-02T03:04:05Z' and name contains 'Bob\\'s \\\\ notes' \
+             and mimeType = 'application/pdf' and trashed = false and 'folder-id' in parents"
+        );
+    }
 
     #[test]
     fn test_next_recurrence_yearly() {
@@ -260,9 +780,12 @@ mod test {
             .expect("Unable to get next recurrences");
         assert_eq!(recurrences.len(), 1);
 
-        let next = event.next_recurrence();
-        assert!(next.is_some());
-        assert_eq!(next.unwrap().to_rfc3339(), "2024-11-12T00:00:00+00:00");
+        let after = chrono::Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap();
+        let next = event
+            .list_recurrences(1, Some(after), None)
+            .expect("Unable to get next recurrences");
+        assert_eq!(next.len(), 1);
+        assert_eq!(next[0].to_rfc3339(), "2024-11-12T00:00:00+00:00");
     }
 
     #[test]
@@ -283,6 +806,82 @@ mod test {
         assert_eq!(recurrences.len(), 0);
     }
 
+    #[test]
+    fn test_as_datetime() {
+        let all_day = CalendarTime {
+            date: "2023-11-12".into(),
+            date_time: None,
+            time_zone: "America/Los_Angeles".into(),
+        };
+        assert!(all_day.is_all_day());
+        assert_eq!(
+            all_day.as_datetime().map(|x| x.to_rfc3339()),
+            Some("2023-11-12T00:00:00-08:00".to_string())
+        );
+
+        let timed = CalendarTime {
+            date: "".into(),
+            date_time: Some("2023-11-12T17:30:00Z".parse().expect("Invalid date")),
+            time_zone: "America/Los_Angeles".into(),
+        };
+        assert!(!timed.is_all_day());
+        assert_eq!(
+            timed.as_datetime().map(|x| x.to_rfc3339()),
+            Some("2023-11-12T09:30:00-08:00".to_string())
+        );
+
+        assert!(CalendarTime::default().as_datetime().is_none());
+    }
+
+    #[test]
+    fn test_meet_link() {
+        let event: CalendarEvent = serde_json::from_value(serde_json::json!({
+            "id": "event",
+            "created": "2023-01-01T00:00:00Z",
+            "attachments": [{
+                "fileUrl": "https://drive.google.com/open?id=abc",
+                "title": "Notes",
+                "mimeType": "application/vnd.google-apps.document",
+                "fileId": "abc"
+            }],
+            "conferenceData": {
+                "conferenceId": "abc-defg-hij",
+                "entryPoints": [
+                    { "entryPointType": "phone", "uri": "tel:+1-555-555-5555" },
+                    { "entryPointType": "video", "uri": "https://meet.google.com/abc-defg-hij" }
+                ]
+            }
+        }))
+        .expect("Unable to deserialize event");
+
+        assert_eq!(event.attachments.len(), 1);
+        assert_eq!(
+            event.meet_link(),
+            Some("https://meet.google.com/abc-defg-hij".to_string())
+        );
+        assert_eq!(CalendarEvent::default().meet_link(), None);
+    }
+
+    #[test]
+    fn test_reminders() {
+        let event: CalendarEvent = serde_json::from_value(serde_json::json!({
+            "id": "event",
+            "colorId": "5",
+            "reminders": {
+                "useDefault": false,
+                "overrides": [
+                    { "method": "email", "minutes": 1440 },
+                    { "method": "popup", "minutes": 10 }
+                ]
+            }
+        }))
+        .expect("Unable to deserialize event");
+
+        assert_eq!(event.color_id, Some("5".to_string()));
+        assert!(!event.reminders.use_default);
+        assert_eq!(event.reminder_minutes(), vec![1440, 10]);
+    }
+
     #[ignore]
     #[test]
     fn test_next_recurrence_until() {
@@ -0,0 +1,64 @@
+use libauth::ApiClient;
+use libgoog::ClientType;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_account_info() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/oauth2/v3/userinfo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "sub": "110169484474386276334",
+            "email": "jane@example.com",
+            "name": "Jane Doe",
+            "picture": "https://lh3.googleusercontent.com/a/photo.jpg"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server, ClientType::Drive);
+    client.set_userinfo_endpoint(&format!("{}/oauth2/v3/userinfo", server.uri()));
+
+    let info = client
+        .account_info()
+        .await
+        .expect("Unable to get account info");
+    assert_eq!(info.id, "110169484474386276334");
+    assert_eq!(info.display_name, "Jane Doe");
+    assert_eq!(info.email.as_deref(), Some("jane@example.com"));
+    assert_eq!(
+        info.avatar_url.as_deref(),
+        Some("https://lh3.googleusercontent.com/a/photo.jpg")
+    );
+    assert_eq!(info.metadata["sub"], "110169484474386276334");
+}
+
+#[tokio::test]
+async fn test_account_info_without_name() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/oauth2/v3/userinfo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "sub": "110169484474386276334",
+            "email": "jane@example.com"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server, ClientType::Drive);
+    client.set_userinfo_endpoint(&format!("{}/oauth2/v3/userinfo", server.uri()));
+
+    let info = client
+        .account_info()
+        .await
+        .expect("Unable to get account info");
+    assert_eq!(info.display_name, "jane@example.com");
+    assert_eq!(info.avatar_url, None);
+}
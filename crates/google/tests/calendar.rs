@@ -0,0 +1,282 @@
+use libauth::ApiError;
+use libgoog::services::calendar::Calendar;
+use libgoog::types::{CalendarTime, EventOrder, ListEventsOptions, NewCalendarEvent};
+use libgoog::ClientType;
+use wiremock::matchers::{body_partial_json, method, path, query_param, query_param_is_missing};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_create_event_with_meet_link() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/calendars/primary/events"))
+        .and(query_param("conferenceDataVersion", "1"))
+        .and(body_partial_json(serde_json::json!({
+            "summary": "Sync",
+            "start": { "dateTime": "2023-11-12T17:00:00Z" },
+            "attendees": [{ "email": "jane@example.com" }],
+            "conferenceData": {
+                "createRequest": { "conferenceSolutionKey": { "type": "hangoutsMeet" } }
+            }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "event-id",
+            "summary": "Sync",
+            "conferenceData": {
+                "entryPoints": [
+                    { "entryPointType": "video", "uri": "https://meet.google.com/abc-defg-hij" }
+                ]
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut calendar = Calendar::new(common::client(&server, ClientType::Calendar));
+    let event = NewCalendarEvent {
+        summary: "Sync".into(),
+        start: CalendarTime {
+            date_time: Some("2023-11-12T17:00:00Z".parse().expect("Invalid date")),
+            ..Default::default()
+        },
+        end: CalendarTime {
+            date_time: Some("2023-11-12T17:30:00Z".parse().expect("Invalid date")),
+            ..Default::default()
+        },
+        attendees: vec!["jane@example.com".into()],
+        add_meet_link: true,
+        ..Default::default()
+    };
+
+    let created = calendar
+        .create_event("primary", &event)
+        .await
+        .expect("Unable to create event");
+    assert_eq!(
+        created.meet_link(),
+        Some("https://meet.google.com/abc-defg-hij".to_string())
+    );
+
+    let requests = server
+        .received_requests()
+        .await
+        .expect("Requests not recorded");
+    let body: serde_json::Value = requests[0].body_json().expect("Invalid body");
+    let request_id = body["conferenceData"]["createRequest"]["requestId"]
+        .as_str()
+        .expect("Missing requestId");
+    let groups: Vec<usize> = request_id.split('-').map(|part| part.len()).collect();
+    assert_eq!(groups, vec![8, 4, 4, 4, 12]);
+    assert_eq!(request_id.chars().nth(14), Some('4'));
+}
+
+#[tokio::test]
+async fn test_list_events_options() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/calendars/primary/events"))
+        .and(query_param("singleEvents", "true"))
+        .and(query_param("orderBy", "startTime"))
+        .and(query_param_is_missing("showDeleted"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "items": [{ "id": "event-id_20231112", "recurringEventId": "event-id" }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/calendars/primary/events"))
+        .and(query_param("singleEvents", "false"))
+        .and(query_param("orderBy", "updated"))
+        .and(query_param("showDeleted", "true"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "items": [{ "id": "event-id", "recurrence": ["RRULE:FREQ=WEEKLY"] }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut calendar = Calendar::new(common::client(&server, ClientType::Calendar));
+
+    let expanded = ListEventsOptions {
+        single_events: true,
+        order_by: EventOrder::StartTime,
+        ..Default::default()
+    };
+    let events = calendar
+        .list_calendar_events("primary", None, None, None, &expanded)
+        .await
+        .expect("Unable to list events");
+    assert_eq!(events.items[0].recurring_event_id, "event-id");
+
+    let recurring = ListEventsOptions {
+        show_deleted: true,
+        ..Default::default()
+    };
+    let events = calendar
+        .list_calendar_events("primary", None, None, None, &recurring)
+        .await
+        .expect("Unable to list events");
+    assert!(events.items[0].is_recurring());
+
+    // Google rejects ordering by start time without expanding recurrences.
+    let invalid = ListEventsOptions {
+        order_by: EventOrder::StartTime,
+        ..Default::default()
+    };
+    let err = calendar
+        .list_calendar_events("primary", None, None, None, &invalid)
+        .await
+        .expect_err("Expected an error");
+    assert!(matches!(err, ApiError::BadRequest(_)));
+}
+
+#[tokio::test]
+async fn test_list_events_updated_min() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/calendars/primary/events"))
+        .and(query_param("updatedMin", "2023-11-01T00:00:00+00:00"))
+        .and(query_param("showDeleted", "true"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "items": [{ "id": "cancelled-id", "status": "cancelled" }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut calendar = Calendar::new(common::client(&server, ClientType::Calendar));
+    let options = ListEventsOptions {
+        updated_min: Some("2023-11-01T00:00:00Z".parse().expect("Invalid date")),
+        ..Default::default()
+    };
+
+    let events = calendar
+        .list_calendar_events("primary", None, None, None, &options)
+        .await
+        .expect("Unable to list events");
+    assert_eq!(events.items.len(), 1);
+    assert_eq!(events.items[0].status, "cancelled");
+}
+
+#[tokio::test]
+async fn test_quick_add() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/calendars/primary/events/quickAdd"))
+        .and(query_param("text", "Lunch with Sam & Jo tomorrow at noon"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "event-id",
+            "summary": "Lunch with Sam & Jo"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/calendars/primary/events/quickAdd"))
+        .and(query_param("text", "???"))
+        .respond_with(ResponseTemplate::new(400))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut calendar = Calendar::new(common::client(&server, ClientType::Calendar));
+    let event = calendar
+        .quick_add("primary", "Lunch with Sam & Jo tomorrow at noon")
+        .await
+        .expect("Unable to quick add event");
+    assert_eq!(event.id, "event-id");
+    assert_eq!(event.summary, "Lunch with Sam & Jo");
+
+    let err = calendar
+        .quick_add("primary", "???")
+        .await
+        .expect_err("Expected an error");
+    assert!(matches!(err, ApiError::BadRequest(_)));
+}
+
+#[tokio::test]
+async fn test_list_calendar_events_delta() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/calendars/primary/events"))
+        .and(query_param("syncToken", "sync-1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "items": [{ "id": "event-id", "status": "cancelled" }],
+            "nextSyncToken": "sync-2"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/calendars/primary/events"))
+        .and(query_param("syncToken", "expired"))
+        .respond_with(ResponseTemplate::new(410).set_body_json(serde_json::json!({
+            "error": { "code": 410, "message": "Sync token is no longer valid, a full sync is required." }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut calendar = Calendar::new(common::client(&server, ClientType::Calendar));
+    let events = calendar
+        .list_calendar_events_delta("primary", "sync-1", None, false)
+        .await
+        .expect("Unable to list events");
+    assert_eq!(events.items.len(), 1);
+    assert_eq!(events.next_sync_token.as_deref(), Some("sync-2"));
+
+    let err = calendar
+        .list_calendar_events_delta("primary", "expired", None, false)
+        .await
+        .expect_err("Expected an error");
+    assert!(matches!(err, ApiError::SyncTokenExpired));
+}
+
+#[tokio::test]
+async fn test_list_all_calendars() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/users/me/calendarList"))
+        .and(query_param("pageToken", "page-2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "items": [{ "id": "work", "summary": "Work" }],
+            // Repeated token shouldn't loop forever.
+            "nextPageToken": "page-2"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/users/me/calendarList"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "items": [{ "id": "primary", "summary": "Me" }],
+            "nextPageToken": "page-2"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut calendar = Calendar::new(common::client(&server, ClientType::Calendar));
+    let calendars = calendar
+        .list_all_calendars()
+        .await
+        .expect("Unable to list calendars");
+    let ids = calendars
+        .iter()
+        .map(|cal| cal.id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["primary", "work"]);
+}
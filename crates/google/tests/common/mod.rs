@@ -0,0 +1,41 @@
+#![allow(dead_code)]
+
+use libauth::{oauth_client, Credentials, OAuthParams};
+use libgoog::{ClientType, GoogClient};
+use wiremock::MockServer;
+
+pub use libauth::test_util::{CLIENT_ID, CLIENT_SECRET, REDIRECT_URL};
+
+/// Builds a client of the given type pointed at the mock server with empty
+/// credentials.
+pub fn client(server: &MockServer, client_type: ClientType) -> GoogClient {
+    client_with_credentials(server, client_type, Default::default())
+}
+
+/// Builds a client of the given type pointed at the mock server with the
+/// given credentials.
+pub fn client_with_credentials(
+    server: &MockServer,
+    client_type: ClientType,
+    creds: Credentials,
+) -> GoogClient {
+    let mut client = GoogClient::new(client_type, CLIENT_ID, CLIENT_SECRET, REDIRECT_URL, creds)
+        .expect("Unable to create client");
+    client.set_endpoint(&server.uri());
+    client
+}
+
+/// Builds a Drive client whose token refreshes go to `/token` on the mock
+/// server.
+pub fn refreshing_client(server: &MockServer, creds: Credentials) -> GoogClient {
+    let mut client = client_with_credentials(server, ClientType::Drive, creds);
+    client.oauth = oauth_client(&OAuthParams {
+        client_id: CLIENT_ID.into(),
+        client_secret: Some(CLIENT_SECRET.into()),
+        auth_url: "https://accounts.google.com/o/oauth2/v2/auth".into(),
+        token_url: Some(format!("{}/token", server.uri())),
+        ..Default::default()
+    })
+    .expect("Unable to create oauth client");
+    client
+}
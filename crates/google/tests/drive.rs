@@ -0,0 +1,1051 @@
+use std::time::{Duration, Instant};
+
+use chrono::{TimeZone, Utc};
+use futures::StreamExt;
+use libauth::{ApiClient, ApiError, Credentials, RefreshToken, RetryPolicy};
+use libgoog::types::{DriveQuery, DriveScope, NewFile, Space, WatchChannel};
+use libgoog::{ClientType, DEFAULT_FILE_FIELDS};
+use wiremock::matchers::{
+    body_json, body_string, body_string_contains, header, header_regex, method, path, query_param,
+    query_param_is_missing,
+};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_get_file_metadata_fields() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/files/file-id"))
+        .and(query_param(
+            "fields",
+            "id,name,owners(displayName,emailAddress)",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "file-id",
+            "name": "Quarterly report",
+            "owners": [{ "displayName": "Jane Doe", "emailAddress": "jane@example.com" }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server, ClientType::Drive);
+    let file = client
+        .get_file_metadata_fields(
+            "file-id",
+            &["id", "name", "owners(displayName,emailAddress)"],
+            &DriveScope::default(),
+        )
+        .await
+        .expect("Unable to get file metadata");
+
+    assert_eq!(file.name, "Quarterly report");
+    assert_eq!(file.owners.len(), 1);
+    assert_eq!(file.owners[0].display_name, "Jane Doe");
+}
+
+#[tokio::test]
+async fn test_get_file_metadata_raw() {
+    let server = MockServer::start().await;
+
+    // `version` should be a string, which fails the typed call but not the raw one.
+    Mock::given(method("GET"))
+        .and(path("/files/file-id"))
+        .and(query_param("fields", DEFAULT_FILE_FIELDS.join(",")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "file-id",
+            "name": "Quarterly report",
+            "version": { "major": 3 }
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server, ClientType::Drive);
+    let scope = DriveScope::default();
+    let err = client
+        .get_file_metadata("file-id", &scope)
+        .await
+        .expect_err("Expected a deserialization error");
+    assert!(matches!(err, ApiError::SerdeError(_)), "{err:?}");
+
+    let file = client
+        .get_file_metadata_raw("file-id", &scope)
+        .await
+        .expect("Unable to get file metadata");
+    assert_eq!(file["name"], "Quarterly report");
+    assert_eq!(file["version"]["major"], 3);
+}
+
+#[tokio::test]
+async fn test_get_file_metadata_fixture() {
+    let server = MockServer::start().await;
+
+    let body: serde_json::Value =
+        serde_json::from_str(include_str!("../fixtures/drive_file.json")).expect("Invalid fixture");
+    Mock::given(method("GET"))
+        .and(path("/files/1aBcD2eFgH3iJkL4mNoP5qRsT6uVwX7yZ"))
+        .and(query_param("fields", DEFAULT_FILE_FIELDS.join(",")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(body))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server, ClientType::Drive);
+    let file = client
+        .get_file_metadata("1aBcD2eFgH3iJkL4mNoP5qRsT6uVwX7yZ", &DriveScope::default())
+        .await
+        .expect("Unable to get file metadata");
+
+    assert_eq!(file.name, "Budget 2023.xlsx");
+    assert_eq!(file.description, None);
+    assert!(!file.trashed);
+    assert_eq!(
+        file.owners[0].email_address.as_deref(),
+        Some("jane@example.com")
+    );
+    assert!(file.last_modifying_user.is_some_and(|user| user.is_me));
+    assert!(file.sharing_user.is_none());
+    assert!(file
+        .web_content_link
+        .is_some_and(|link| link.ends_with("export=download")));
+    assert_eq!(
+        file.created_time,
+        Some(
+            Utc.with_ymd_and_hms(2023, 1, 9, 18, 22, 41).unwrap()
+                + chrono::Duration::milliseconds(137)
+        )
+    );
+    assert!(file.shared_with_me_time.is_none());
+}
+
+#[tokio::test]
+async fn test_get_file_metadata_export_links() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/files/doc-id"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "doc-id",
+            "mimeType": "application/vnd.google-apps.document",
+            "exportLinks": {
+                "application/pdf": "https://docs.google.com/feeds/download/documents/export/Export?id=doc-id&exportFormat=pdf"
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server, ClientType::Drive);
+    let file = client
+        .get_file_metadata("doc-id", &DriveScope::default())
+        .await
+        .expect("Unable to get file metadata");
+
+    assert!(file
+        .export_link("application/pdf")
+        .is_some_and(|link| link.ends_with("exportFormat=pdf")));
+    assert_eq!(file.export_link("text/plain"), None);
+}
+
+#[tokio::test]
+async fn test_error_body_with_success_status() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/files/file-id"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": {
+                "code": 403,
+                "message": "Export quota exceeded",
+                "status": "PERMISSION_DENIED"
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server, ClientType::Drive);
+    let res = client
+        .get_file_metadata("file-id", &DriveScope::default())
+        .await;
+    match res {
+        Err(ApiError::ApiStatus { status, body, .. }) => {
+            assert_eq!(status, 403);
+            assert!(body.contains("Export quota exceeded"));
+        }
+        res => panic!("Unexpected result: {res:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_error_body_with_success_status_retried() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/files/file-id"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": { "code": 503, "message": "The service is currently unavailable." }
+        })))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/files/file-id"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "id": "file-id" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server, ClientType::Drive);
+    client.set_retry_policy(RetryPolicy {
+        max_retries: 1,
+        base_delay: Duration::from_millis(1),
+        ..Default::default()
+    });
+    let file = client
+        .get_file_metadata("file-id", &DriveScope::default())
+        .await
+        .expect("Unable to get file");
+    assert_eq!(file.id, "file-id");
+}
+
+async fn mount_file(server: &MockServer, id: &str, name: &str, parents: &[&str]) {
+    Mock::given(method("GET"))
+        .and(path(format!("/files/{id}")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": id,
+            "name": name,
+            "parents": parents
+        })))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_get_file_path() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/files/root"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "root-id",
+            "name": "My Drive"
+        })))
+        .mount(&server)
+        .await;
+    mount_file(&server, "projects", "Projects", &["root-id"]).await;
+    mount_file(&server, "report", "report", &["projects"]).await;
+    // Parent that isn't accessible
+    mount_file(&server, "shared", "shared doc", &["someone-elses-folder"]).await;
+    Mock::given(method("GET"))
+        .and(path("/files/someone-elses-folder"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server, ClientType::Drive);
+    assert_eq!(
+        client
+            .get_file_path("report", &DriveScope::default())
+            .await
+            .expect("Unable to get path"),
+        "/My Drive/Projects/report"
+    );
+    assert_eq!(
+        client
+            .get_file_path("shared", &DriveScope::default())
+            .await
+            .expect("Unable to get path"),
+        "/Shared with me/shared doc"
+    );
+}
+
+#[tokio::test]
+async fn test_get_file_path_parent_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/files/root"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "root-id",
+            "name": "My Drive"
+        })))
+        .mount(&server)
+        .await;
+    mount_file(&server, "report", "report", &["projects"]).await;
+    Mock::given(method("GET"))
+        .and(path("/files/projects"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    // A failed lookup isn't the same as an inaccessible parent, so it must
+    // not produce a "/Shared with me" path.
+    let err = common::client(&server, ClientType::Drive)
+        .get_file_path("report", &DriveScope::default())
+        .await
+        .expect_err("Expected an error");
+    assert_eq!(
+        err.status(),
+        Some(reqwest::StatusCode::INTERNAL_SERVER_ERROR)
+    );
+}
+
+#[tokio::test]
+async fn test_list_comments_and_replies() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/files/doc-id/comments"))
+        .and(query_param(
+            "fields",
+            "nextPageToken,comments(id,content,author,createdTime,resolved,replies)",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "nextPageToken": "page-2",
+            "comments": [{
+                "id": "comment-1",
+                "content": "Can we double check these numbers?",
+                "author": { "displayName": "Jane Doe", "me": true },
+                "createdTime": "2023-05-01T12:00:00.000Z",
+                "resolved": true,
+                "replies": [{ "id": "reply-1", "content": "Fixed", "action": "resolve" }]
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/files/doc-id/comments/comment-1/replies"))
+        .and(query_param("pageToken", "page-2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "replies": [{ "id": "reply-2", "content": "Thanks!" }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/files/doc-id/comments/comment-1/replies"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "nextPageToken": "page-2",
+            "replies": [{ "id": "reply-1", "content": "Fixed", "action": "resolve" }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server, ClientType::Drive);
+    let comments = client
+        .list_comments("doc-id", None)
+        .await
+        .expect("Unable to list comments");
+    assert_eq!(comments.next_page_token.as_deref(), Some("page-2"));
+    assert_eq!(comments.comments.len(), 1);
+
+    let comment = &comments.comments[0];
+    assert_eq!(comment.content, "Can we double check these numbers?");
+    assert_eq!(comment.author.display_name, "Jane Doe");
+    assert!(comment.resolved);
+    assert_eq!(comment.replies[0].action.as_deref(), Some("resolve"));
+
+    let replies = client
+        .list_replies("doc-id", "comment-1")
+        .await
+        .expect("Unable to list replies");
+    let ids = replies.iter().map(|r| r.id.as_str()).collect::<Vec<_>>();
+    assert_eq!(ids, vec!["reply-1", "reply-2"]);
+}
+
+#[tokio::test]
+async fn test_list_files_app_data() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/files"))
+        .and(query_param("spaces", "appDataFolder"))
+        .and(query_param(
+            "fields",
+            "nextPageToken,files(kind,id,name,mimeType,properties,appProperties)",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "files": [{
+                "kind": "drive#file",
+                "id": "config-id",
+                "name": "config.json",
+                "mimeType": "application/json",
+                "appProperties": { "version": "2" }
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server, ClientType::Drive);
+    let files = client
+        .list_files(None, None, &[Space::AppDataFolder], &DriveScope::default())
+        .await
+        .expect("Unable to list files");
+
+    assert_eq!(files.next_page_token, None);
+    assert_eq!(files.files.len(), 1);
+    assert_eq!(
+        files.files[0]
+            .app_properties
+            .get("version")
+            .map(String::as_str),
+        Some("2")
+    );
+    assert!(files.files[0].properties.is_empty());
+}
+
+#[tokio::test]
+async fn test_upload_file() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/files"))
+        .and(query_param("uploadType", "multipart"))
+        .and(header_regex(
+            "content-type",
+            "^multipart/related; boundary=spyglass-.+$",
+        ))
+        .and(body_string_contains(r#""name":"notes.txt""#))
+        .and(body_string_contains(r#""parents":["folder-id"]"#))
+        .and(body_string_contains(
+            "Content-Type: text/plain\r\n\r\nHello from spyglass\r\n",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "new-file-id",
+            "name": "notes.txt",
+            "mimeType": "text/plain",
+            "parents": ["folder-id"]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server, ClientType::Drive);
+    client.set_upload_endpoint(&server.uri());
+
+    let file = client
+        .upload_file(
+            "notes.txt",
+            "text/plain",
+            &["folder-id".to_string()],
+            "Hello from spyglass".into(),
+        )
+        .await
+        .expect("Unable to upload file");
+    assert_eq!(file.id, "new-file-id");
+    assert_eq!(file.parents, vec!["folder-id"]);
+}
+
+#[tokio::test]
+async fn test_upload_file_resumable() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/files"))
+        .and(query_param("uploadType", "resumable"))
+        .and(header("x-upload-content-length", "11"))
+        .and(body_string_contains(r#""name":"hello.txt""#))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("location", format!("{}/session", server.uri()).as_str()),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // The first attempt fails part way through...
+    Mock::given(method("PUT"))
+        .and(path("/session"))
+        .and(header("content-range", "bytes 0-10/11"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // ...after only receiving "hello"
+    Mock::given(method("PUT"))
+        .and(path("/session"))
+        .and(header("content-range", "bytes */11"))
+        .respond_with(ResponseTemplate::new(308).insert_header("range", "bytes=0-4"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("PUT"))
+        .and(path("/session"))
+        .and(header("content-range", "bytes 5-10/11"))
+        .and(body_string(" world"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+            "id": "uploaded-id",
+            "name": "hello.txt"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server, ClientType::Drive);
+    client.set_upload_endpoint(&server.uri());
+    client.set_retry_policy(RetryPolicy {
+        base_delay: Duration::from_millis(100),
+        jitter: false,
+        ..Default::default()
+    });
+
+    let metadata = NewFile {
+        name: "hello.txt".into(),
+        mime_type: "text/plain".into(),
+        ..Default::default()
+    };
+    let start = Instant::now();
+    let file = client
+        .upload_file_resumable(&metadata, "hello world".as_bytes(), 11)
+        .await
+        .expect("Unable to upload file");
+    assert_eq!(file.id, "uploaded-id");
+    // Waits before checking the upload status after the failed chunk.
+    assert!(start.elapsed() >= Duration::from_millis(100));
+}
+
+#[tokio::test]
+async fn test_upload_file_resumable_init_error() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/files"))
+        .and(query_param("uploadType", "resumable"))
+        .respond_with(ResponseTemplate::new(403).set_body_json(serde_json::json!({
+            "error": { "code": 403, "message": "The user's Drive storage quota has been exceeded." }
+        })))
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server, ClientType::Drive);
+    client.set_upload_endpoint(&server.uri());
+
+    let metadata = NewFile {
+        name: "hello.txt".into(),
+        mime_type: "text/plain".into(),
+        ..Default::default()
+    };
+    let err = client
+        .upload_file_resumable(&metadata, "hello world".as_bytes(), 11)
+        .await
+        .expect_err("Expected an error");
+    match err {
+        ApiError::ApiStatus { status, body, .. } => {
+            assert_eq!(status, 403);
+            assert!(body.contains("storage quota"));
+        }
+        err => panic!("Unexpected error: {err:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_watch_changes() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/changes/watch"))
+        .and(query_param("pageToken", "12345"))
+        .and(body_json(serde_json::json!({
+            "id": "channel-id",
+            "type": "web_hook",
+            "address": "https://example.com/notify",
+            "token": "secret",
+            "expiration": "1700000000000"
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "kind": "api#channel",
+            "id": "channel-id",
+            "resourceId": "resource-id",
+            "resourceUri": "https://www.googleapis.com/drive/v3/changes",
+            "expiration": "1700000000000"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/channels/stop"))
+        .and(body_json(serde_json::json!({
+            "id": "channel-id",
+            "resourceId": "resource-id"
+        })))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server, ClientType::Drive);
+    let channel = WatchChannel {
+        id: "channel-id".into(),
+        address: "https://example.com/notify".into(),
+        token: Some("secret".into()),
+        expiration: Utc.timestamp_millis_opt(1_700_000_000_000).single(),
+    };
+
+    let watch = client
+        .watch_changes("12345", channel)
+        .await
+        .expect("Unable to watch changes");
+    assert_eq!(watch.resource_id, "resource-id");
+    assert_eq!(
+        watch.expires_at(),
+        Utc.timestamp_millis_opt(1_700_000_000_000).single()
+    );
+
+    client
+        .stop_channel(&watch.id, &watch.resource_id)
+        .await
+        .expect("Unable to stop channel");
+}
+
+#[tokio::test]
+async fn test_stop_unknown_channel() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/channels/stop"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "error": { "code": 404, "message": "Channel 'channel-id' not found for project" }
+        })))
+        .mount(&server)
+        .await;
+
+    let err = common::client(&server, ClientType::Drive)
+        .stop_channel("channel-id", "resource-id")
+        .await
+        .expect_err("Expected an error");
+    match err {
+        ApiError::ApiStatus { status, body, url } => {
+            assert_eq!(status, 404);
+            assert!(body.contains("not found for project"));
+            assert!(url.unwrap_or_default().ends_with("/channels/stop"));
+        }
+        err => panic!("Unexpected error: {err:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_download_file_stream() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/files/file-id"))
+        .and(query_param("alt", "media"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("hello world"))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/files/file-id"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "file-id",
+            "name": "notes.txt",
+            "mimeType": "text/plain"
+        })))
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server, ClientType::Drive);
+
+    let mut stream = Box::pin(
+        client
+            .download_file_stream("file-id", &DriveScope::default())
+            .await
+            .expect("Unable to download file"),
+    );
+    let mut data = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        data.extend_from_slice(&chunk.expect("Unable to read chunk"));
+    }
+    assert_eq!(data, b"hello world");
+
+    let data = client
+        .download_file("file-id", &DriveScope::default())
+        .await
+        .expect("Unable to download file");
+    assert_eq!(&data[..], b"hello world");
+}
+
+#[tokio::test]
+async fn test_download_file_unauthorized() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/files/file-id"))
+        .and(query_param("alt", "media"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/files/file-id"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "file-id",
+            "name": "notes.txt",
+            "mimeType": "text/plain"
+        })))
+        .mount(&server)
+        .await;
+
+    let err = common::client(&server, ClientType::Drive)
+        .download_file("file-id", &DriveScope::default())
+        .await
+        .expect_err("Expected an error");
+    let err = err.downcast::<ApiError>().expect("Expected an ApiError");
+    assert!(matches!(err, ApiError::AuthError(_)));
+}
+
+#[tokio::test]
+async fn test_get_files_metadata() {
+    let server = MockServer::start().await;
+
+    for id in ["a", "b", "c"] {
+        Mock::given(method("GET"))
+            .and(path(format!("/files/{id}")))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "id": id, "name": format!("{id}.txt") }))
+                    // Finish out of order
+                    .set_delay(std::time::Duration::from_millis(if id == "a" {
+                        50
+                    } else {
+                        0
+                    })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    Mock::given(method("GET"))
+        .and(path("/files/missing"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let ids = ["a", "missing", "b", "c"].map(String::from);
+    let results = common::client(&server, ClientType::Drive)
+        .get_files_metadata(&ids, &DriveScope::default(), 2)
+        .await
+        .expect("Unable to refresh credentials");
+
+    assert_eq!(results.len(), 4);
+    assert_eq!(
+        results[0].as_ref().map(|f| f.name.as_str()).ok(),
+        Some("a.txt")
+    );
+    assert!(results[1]
+        .as_ref()
+        .is_err_and(|err| err.status() == Some(reqwest::StatusCode::NOT_FOUND)));
+    assert_eq!(results[3].as_ref().map(|f| f.id.as_str()).ok(), Some("c"));
+}
+
+#[tokio::test]
+async fn test_get_files_metadata_shared_request_path() {
+    let server = MockServer::start().await;
+
+    // Retried according to the client's retry policy...
+    Mock::given(method("GET"))
+        .and(path("/files/flaky"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/files/flaky"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "id": "flaky" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+    // ...and error bodies are checked like any other Drive request.
+    Mock::given(method("GET"))
+        .and(path("/files/broken"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": { "code": 400, "message": "Invalid field selection" }
+        })))
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server, ClientType::Drive);
+    client.set_retry_policy(RetryPolicy {
+        max_retries: 1,
+        base_delay: Duration::ZERO,
+        ..Default::default()
+    });
+
+    let ids = ["flaky", "broken"].map(String::from);
+    let results = client
+        .get_files_metadata(&ids, &DriveScope::default(), 2)
+        .await
+        .expect("Unable to refresh credentials");
+    assert_eq!(
+        results[0].as_ref().map(|f| f.id.as_str()).ok(),
+        Some("flaky")
+    );
+    assert!(
+        matches!(&results[1], Err(ApiError::ApiStatus { status: 400, body, .. }) if body.contains("Invalid field selection"))
+    );
+}
+
+#[tokio::test]
+async fn test_get_files_metadata_refresh_error() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "error": "invalid_grant"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let creds = Credentials {
+        requested_at: Utc::now() - chrono::Duration::hours(2),
+        refresh_token: Some(RefreshToken::new("refresh-token".into())),
+        expires_in: Some(Duration::from_secs(3600)),
+        ..Default::default()
+    };
+    let ids = ["a", "b"].map(String::from);
+    let err = common::refreshing_client(&server, creds)
+        .get_files_metadata(&ids, &DriveScope::default(), 2)
+        .await
+        .expect_err("Expected a refresh error");
+    assert!(err.is_auth_error());
+}
+
+#[tokio::test]
+async fn test_list_changes() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/changes/startPageToken"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "startPageToken": "100" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/changes"))
+        .and(query_param("pageToken", "100"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "nextPageToken": "101",
+            "changes": [
+                {
+                    "fileId": "file-1",
+                    "removed": false,
+                    "time": "2024-01-01T00:00:00Z",
+                    "file": {
+                        "id": "file-1",
+                        "name": "notes.txt",
+                        "mimeType": "text/plain",
+                        "createdTime": "2023-12-01T00:00:00Z"
+                    }
+                },
+                { "fileId": "file-2", "removed": true, "time": "2024-01-02T00:00:00Z" }
+            ]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/changes"))
+        .and(query_param("pageToken", "101"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "newStartPageToken": "102",
+            "changes": []
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server, ClientType::Drive);
+    let token = client
+        .get_start_page_token()
+        .await
+        .expect("Unable to get start page token");
+    assert_eq!(token, "100");
+
+    let changes = client
+        .list_changes(&token, &DriveScope::default())
+        .await
+        .expect("Unable to list changes");
+    assert_eq!(changes.next_page_token.as_deref(), Some("101"));
+    assert_eq!(changes.new_start_page_token, None);
+    assert_eq!(changes.changes.len(), 2);
+    assert_eq!(
+        changes.changes[0]
+            .file
+            .as_ref()
+            .map(|file| file.name.as_str()),
+        Some("notes.txt")
+    );
+    assert!(changes.changes[1].removed);
+    assert!(changes.changes[1].file.is_none());
+
+    let changes = client
+        .list_changes("101", &DriveScope::default())
+        .await
+        .expect("Unable to list changes");
+    assert!(changes.changes.is_empty());
+    assert_eq!(changes.new_start_page_token.as_deref(), Some("102"));
+}
+
+#[tokio::test]
+async fn test_list_files_shared_drive() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/files"))
+        .and(query_param("supportsAllDrives", "true"))
+        .and(query_param("includeItemsFromAllDrives", "true"))
+        .and(query_param("corpora", "drive"))
+        .and(query_param("driveId", "team-drive"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "files": [{
+                "kind": "drive#file",
+                "id": "team-file",
+                "name": "roadmap",
+                "mimeType": "application/vnd.google-apps.document"
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/files/team-file"))
+        .and(query_param("supportsAllDrives", "true"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "team-file",
+            "name": "roadmap",
+            "createdTime": "2024-01-01T00:00:00Z"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let scope = DriveScope {
+        drive_id: Some("team-drive".into()),
+        ..Default::default()
+    };
+
+    let mut client = common::client(&server, ClientType::Drive);
+    let files = client
+        .list_files(None, None, &[], &scope)
+        .await
+        .expect("Unable to list files");
+    assert_eq!(files.files.len(), 1);
+
+    let file = client
+        .get_file_metadata(&files.files[0].id, &scope)
+        .await
+        .expect("Unable to get file metadata");
+    assert_eq!(file.name, "roadmap");
+}
+
+#[tokio::test]
+async fn test_shared_drive_download_and_changes() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/files/team-file"))
+        .and(query_param("supportsAllDrives", "true"))
+        .and(query_param("alt", "media"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("roadmap"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/files/team-file"))
+        .and(query_param("supportsAllDrives", "true"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "team-file",
+            "name": "roadmap",
+            "mimeType": "text/plain"
+        })))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/changes"))
+        .and(query_param("supportsAllDrives", "true"))
+        .and(query_param("includeItemsFromAllDrives", "true"))
+        .and(query_param("driveId", "team-drive"))
+        .and(query_param_is_missing("corpora"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "newStartPageToken": "102",
+            "changes": []
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let scope = DriveScope {
+        drive_id: Some("team-drive".into()),
+        ..Default::default()
+    };
+
+    let mut client = common::client(&server, ClientType::Drive);
+    let data = client
+        .download_file("team-file", &scope)
+        .await
+        .expect("Unable to download file");
+    assert_eq!(&data[..], b"roadmap");
+
+    let changes = client
+        .list_changes("101", &scope)
+        .await
+        .expect("Unable to list changes");
+    assert_eq!(changes.new_start_page_token.as_deref(), Some("102"));
+}
+
+#[tokio::test]
+async fn test_list_files_drive_query() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/files"))
+        .and(query_param(
+            "q",
+            "modifiedTime > '2024-01-01T00:00:00Z' and trashed = false",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "files": [] })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let query = DriveQuery::new()
+        .modified_after(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap())
+        .trashed(false);
+
+    let mut client = common::client(&server, ClientType::Drive);
+    let files = client
+        .list_files(None, query.build(), &[], &DriveScope::default())
+        .await
+        .expect("Unable to list files");
+    assert!(files.files.is_empty());
+}
@@ -0,0 +1,229 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use libauth::{
+    oauth_client, ApiClient, ApiError, ClientConfig, Credentials, OAuthParams, TokenError,
+};
+use libgoog::types::DriveScope;
+use libgoog::{ClientType, GoogClient};
+use oauth2::{AccessToken, RefreshToken};
+use wiremock::matchers::{body_string_contains, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_concurrent_refresh() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .and(body_string_contains("refresh_token=refresh-token"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({
+                    "access_token": "new-token",
+                    "token_type": "bearer",
+                    "expires_in": 3600,
+                    "refresh_token": "new-refresh-token"
+                }))
+                .set_delay(Duration::from_millis(50)),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let creds = Credentials {
+        requested_at: Utc::now() - chrono::Duration::hours(2),
+        access_token: AccessToken::new("old-token".into()),
+        refresh_token: Some(RefreshToken::new("refresh-token".into())),
+        expires_in: Some(Duration::from_secs(3600)),
+        ..Default::default()
+    };
+
+    let mut client = common::refreshing_client(&server, creds);
+
+    let mut other = client.clone();
+    let (first, second) = tokio::join!(client.get_check_client(), other.get_check_client());
+    first.expect("Unable to refresh");
+    second.expect("Unable to refresh");
+
+    assert_eq!(client.credentials().access_token.secret(), "new-token");
+    assert_eq!(other.credentials().access_token.secret(), "new-token");
+}
+
+#[tokio::test]
+async fn test_clone_shares_refresh_state() {
+    let server = MockServer::start().await;
+
+    let mut client = common::client(&server, ClientType::Drive);
+    let mut other = client.clone();
+
+    assert!(Arc::ptr_eq(&client.refresh_lock(), &other.refresh_lock()));
+    assert!(client
+        .watch_on_refresh()
+        .same_channel(&other.watch_on_refresh()));
+}
+
+#[tokio::test]
+async fn test_timeout_survives_set_credentials() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/files/file-id"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "id": "file-id" }))
+                .set_delay(Duration::from_secs(2)),
+        )
+        .mount(&server)
+        .await;
+
+    let mut client = GoogClient::new_with_config(
+        ClientType::Drive,
+        common::CLIENT_ID,
+        common::CLIENT_SECRET,
+        common::REDIRECT_URL,
+        Default::default(),
+        ClientConfig {
+            timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        },
+    )
+    .expect("Unable to create client");
+    client.set_endpoint(&server.uri());
+    client
+        .set_credentials(&Credentials {
+            access_token: AccessToken::new("new-token".into()),
+            ..Default::default()
+        })
+        .expect("Unable to set credentials");
+
+    let err = client
+        .get_file_metadata("file-id", &DriveScope::default())
+        .await
+        .expect_err("Request should time out");
+    assert!(matches!(
+        err,
+        ApiError::EndpointError { ref source, .. } if source.is_timeout()
+    ));
+}
+
+#[tokio::test]
+async fn test_proxy_survives_set_credentials() {
+    // The mock server stands in for the proxy, receiving requests for a host
+    // that doesn't exist.
+    let proxy = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/files/file-id"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "id": "file-id" })),
+        )
+        .expect(1)
+        .mount(&proxy)
+        .await;
+
+    let mut client = GoogClient::new_with_config(
+        ClientType::Drive,
+        common::CLIENT_ID,
+        common::CLIENT_SECRET,
+        common::REDIRECT_URL,
+        Default::default(),
+        ClientConfig {
+            proxy: Some(proxy.uri()),
+            no_proxy: Some("localhost".into()),
+            ..Default::default()
+        },
+    )
+    .expect("Unable to create client");
+    client.set_endpoint("http://drive.invalid");
+    client
+        .set_credentials(&Credentials {
+            access_token: AccessToken::new("new-token".into()),
+            ..Default::default()
+        })
+        .expect("Unable to set credentials");
+
+    let file = client
+        .get_file_metadata("file-id", &DriveScope::default())
+        .await
+        .expect("Unable to get file through proxy");
+    assert_eq!(file.id, "file-id");
+}
+
+#[tokio::test]
+async fn test_refresh_invalid_grant() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "error": "invalid_grant",
+            "error_description": "Token has been expired or revoked."
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let creds = Credentials {
+        requested_at: Utc::now() - chrono::Duration::hours(2),
+        access_token: AccessToken::new("old-token".into()),
+        refresh_token: Some(RefreshToken::new("refresh-token".into())),
+        expires_in: Some(Duration::from_secs(3600)),
+        ..Default::default()
+    };
+
+    let mut client = common::refreshing_client(&server, creds);
+
+    match client.refresh_credentials().await {
+        Err(TokenError::InvalidGrant(description)) => assert_eq!(
+            description.as_deref(),
+            Some("Token has been expired or revoked.")
+        ),
+        res => panic!("Expected invalid_grant, got {res:?}"),
+    }
+
+    let err = client
+        .get_check_client()
+        .await
+        .expect_err("Expected an error");
+    assert!(err.is_auth_error());
+}
+
+#[tokio::test]
+async fn test_revoke_url() {
+    let server = MockServer::start().await;
+
+    let client = common::client(&server, ClientType::Drive);
+    assert_eq!(
+        client.oauth.revocation_url().map(|url| url.as_str()),
+        Some("https://oauth2.googleapis.com/revoke")
+    );
+}
+
+#[tokio::test]
+async fn test_revoke_insecure_url() {
+    let server = MockServer::start().await;
+
+    let creds = Credentials {
+        access_token: AccessToken::new("access-token".into()),
+        refresh_token: Some(RefreshToken::new("refresh-token".into())),
+        ..Default::default()
+    };
+    let mut client = common::client_with_credentials(&server, ClientType::Drive, creds);
+    client.oauth = oauth_client(&OAuthParams {
+        client_id: common::CLIENT_ID.into(),
+        client_secret: Some(common::CLIENT_SECRET.into()),
+        auth_url: "https://accounts.google.com/o/oauth2/v2/auth".into(),
+        revoke_url: Some(format!("{}/revoke", server.uri())),
+        ..Default::default()
+    })
+    .expect("Unable to create oauth client");
+
+    // oauth2 only sends tokens to an HTTPS revocation endpoint (RFC 7009)
+    let err = client.revoke().await.expect_err("Expected revoke to fail");
+    assert!(err.to_string().contains("HTTPS"), "{err}");
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
@@ -0,0 +1,307 @@
+use libauth::ApiError;
+use libgoog::services::spreadsheets::types::{
+    DateTimeRenderOption, ReadOptions, ValueRenderOption,
+};
+use libgoog::services::spreadsheets::Sheets;
+use libgoog::ClientType;
+use serde::Deserialize;
+use wiremock::matchers::{body_partial_json, method, path, path_regex, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_batch_update_values() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/spreadsheets/sheet-id/values:batchUpdate"))
+        .and(body_partial_json(serde_json::json!({
+            "valueInputOption": "USER_ENTERED",
+            "data": [
+                { "range": "Sheet1!A1:B1", "values": [["a", "b"]] },
+                { "range": "Sheet1!D4", "values": [["c"]] }
+            ]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "spreadsheetId": "sheet-id",
+            "totalUpdatedRows": 2,
+            "totalUpdatedColumns": 3,
+            "totalUpdatedCells": 3,
+            "totalUpdatedSheets": 1,
+            "responses": [
+                {
+                    "spreadsheetId": "sheet-id",
+                    "updatedRange": "Sheet1!A1:B1",
+                    "updatedRows": 1,
+                    "updatedColumns": 2,
+                    "updatedCells": 2
+                },
+                {
+                    "spreadsheetId": "sheet-id",
+                    "updatedRange": "Sheet1!D4",
+                    "updatedRows": 1,
+                    "updatedColumns": 1,
+                    "updatedCells": 1
+                }
+            ]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut sheets = Sheets::new(common::client(&server, ClientType::Sheets));
+    let resp = sheets
+        .batch_update_values(
+            "sheet-id",
+            vec![
+                (
+                    "Sheet1!A1:B1".to_string(),
+                    vec![vec!["a".to_string(), "b".to_string()]],
+                ),
+                ("Sheet1!D4".to_string(), vec![vec!["c".to_string()]]),
+            ],
+            &Default::default(),
+        )
+        .await
+        .expect("Unable to update values");
+
+    assert_eq!(resp.total_updated_cells, 3);
+    assert_eq!(resp.responses.len(), 2);
+}
+
+#[tokio::test]
+async fn test_update_range() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("PUT"))
+        .and(path("/spreadsheets/sheet-id/values/Sheet1!A1:B1"))
+        .and(query_param("valueInputOption", "USER_ENTERED"))
+        .and(query_param("includeValuesInResponse", "false"))
+        .and(body_partial_json(
+            serde_json::json!({ "values": [["a", "b"]] }),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "spreadsheetId": "sheet-id",
+            "updatedRange": "Sheet1!A1:B1",
+            "updatedRows": 1,
+            "updatedColumns": 2,
+            "updatedCells": 2
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut sheets = Sheets::new(common::client(&server, ClientType::Sheets));
+    sheets
+        .update_range(
+            "sheet-id",
+            "Sheet1",
+            "A1:B1",
+            &vec![vec!["a".to_string(), "b".to_string()]],
+            &Default::default(),
+        )
+        .await
+        .expect("Unable to update range");
+}
+
+#[tokio::test]
+async fn test_update_range_error_body() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("PUT"))
+        .and(path("/spreadsheets/sheet-id/values/Sheet1!A1:B1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "error": { "code": 401, "message": "Invalid Credentials" }
+        })))
+        .mount(&server)
+        .await;
+
+    let mut sheets = Sheets::new(common::client(&server, ClientType::Sheets));
+    let res = sheets
+        .update_range(
+            "sheet-id",
+            "Sheet1",
+            "A1:B1",
+            &vec![vec!["a".to_string()]],
+            &Default::default(),
+        )
+        .await;
+    assert!(matches!(res, Err(ApiError::AuthError(msg)) if msg == "Invalid Credentials"));
+}
+
+#[derive(Debug, Deserialize)]
+struct Contact {
+    name: String,
+    age: u32,
+    active: bool,
+    zip: String,
+    notes: Option<String>,
+}
+
+#[tokio::test]
+async fn test_read_records() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/spreadsheets/sheet-id/values/'Contacts'"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "range": "Contacts!A1:Z1000",
+            "majorDimension": "ROWS",
+            "values": [
+                ["name", "age", "active", "zip", "notes"],
+                ["Jane", "34", "TRUE", "02134", "Met at conference"],
+                // Sheets omits empty trailing cells
+                ["John", "41", "FALSE", "94107"]
+            ]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut sheets = Sheets::new(common::client(&server, ClientType::Sheets));
+    let records = sheets
+        .read_records::<Contact>("sheet-id", "Contacts")
+        .await
+        .expect("Unable to read records");
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].name, "Jane");
+    assert_eq!(records[0].age, 34);
+    assert!(records[0].active);
+    assert_eq!(records[0].zip, "02134");
+    assert_eq!(records[0].notes.as_deref(), Some("Met at conference"));
+    assert!(!records[1].active);
+    assert_eq!(records[1].notes, None);
+}
+
+#[tokio::test]
+async fn test_read_rows_as_map() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/spreadsheets/sheet-id/values:batchGet"))
+        .and(query_param("ranges", "Sheet1!1:1"))
+        .and(query_param("ranges", "Sheet1!2:3"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "spreadsheetId": "sheet-id",
+            "valueRanges": [
+                { "range": "Sheet1!A1:Z1", "majorDimension": "ROWS", "values": [["name", "lang"]] },
+                {
+                    "range": "Sheet1!A2:Z3",
+                    "majorDimension": "ROWS",
+                    "values": [["ferris", "rust"], ["gopher"]]
+                }
+            ]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut sheets = Sheets::new(common::client(&server, ClientType::Sheets));
+    let rows = sheets
+        .read_rows_as_map("sheet-id", "Sheet1", 2, 3)
+        .await
+        .expect("Unable to read rows");
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["lang"], "rust");
+    assert_eq!(rows[1]["_idx"], "3");
+    assert!(!rows[1].contains_key("lang"));
+}
+
+#[tokio::test]
+async fn test_append() {
+    let server = MockServer::start().await;
+
+    // 3 rows x 2 columns
+    Mock::given(method("POST"))
+        .and(path("/spreadsheets/sheet-id/values/Sheet1!A1:B3:append"))
+        .and(body_partial_json(serde_json::json!({
+            "values": [["a", "b"], ["c", "d"], ["e"]]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "spreadsheetId": "sheet-id",
+            "tableRange": "Sheet1!A1:B10",
+            "updates": {
+                "spreadsheetId": "sheet-id",
+                "updatedRange": "Sheet1!A11:B13",
+                "updatedRows": 3,
+                "updatedColumns": 2,
+                "updatedCells": 5
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let values = [vec!["a", "b"], vec!["c", "d"], vec!["e"]]
+        .map(|row| row.into_iter().map(String::from).collect::<Vec<_>>());
+    let mut sheets = Sheets::new(common::client(&server, ClientType::Sheets));
+    sheets
+        .append("sheet-id", "Sheet1", &values, &Default::default())
+        .await
+        .expect("Unable to append values");
+}
+
+#[tokio::test]
+async fn test_read_range_unformatted() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/spreadsheets/sheet-id/values/Sheet1!A1:C1"))
+        .and(query_param("valueRenderOption", "UNFORMATTED_VALUE"))
+        .and(query_param("dateTimeRenderOption", "FORMATTED_STRING"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "range": "Sheet1!A1:C1",
+            "majorDimension": "ROWS",
+            "values": [[1234.5, true, "2024-02-05"]]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut sheets = Sheets::new(common::client(&server, ClientType::Sheets));
+    let range = sheets
+        .read_range_with_options(
+            "sheet-id",
+            "Sheet1",
+            "A1:C1",
+            &ReadOptions {
+                value_render_option: Some(ValueRenderOption::UnformattedValue),
+                date_time_render_option: Some(DateTimeRenderOption::FormattedString),
+                major_dimension: None,
+            },
+        )
+        .await
+        .expect("Unable to read range");
+    assert_eq!(range.values, vec![vec!["1234.5", "true", "2024-02-05"]]);
+}
+
+#[tokio::test]
+async fn test_range_sheet_name_encoded() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path_regex(r"^/spreadsheets/sheet-id/values/.*A1:B1$"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "range": "'Q1 Sales/EU #2'!A1:B1",
+            "values": [["a", "b"]]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut sheets = Sheets::new(common::client(&server, ClientType::Sheets));
+    sheets
+        .read_range("sheet-id", "Q1 Sales/EU #2", "A1:B1")
+        .await
+        .expect("Unable to read range");
+
+    let requests = server.received_requests().await.unwrap_or_default();
+    let url_path = requests[0].url.path();
+    assert!(
+        url_path.starts_with("/spreadsheets/sheet-id/values/")
+            && url_path.contains("Q1%20Sales%2FEU%20%232"),
+        "{url_path}"
+    );
+}
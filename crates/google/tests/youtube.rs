@@ -0,0 +1,94 @@
+use libgoog::services::youtube::YouTube;
+use libgoog::ClientType;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_list_subscriptions() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/subscriptions"))
+        .and(query_param("mine", "true"))
+        .and(query_param("pageToken", "page-2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "kind": "youtube#subscriptionListResponse",
+            "pageInfo": { "totalResults": 1, "resultsPerPage": 50 },
+            "items": [{
+                "kind": "youtube#subscription",
+                "id": "sub-id",
+                "snippet": {
+                    "publishedAt": "2023-04-01T12:00:00Z",
+                    "title": "Rust Channel",
+                    "description": "",
+                    "resourceId": { "kind": "youtube#channel", "channelId": "UC123" },
+                    "channelId": "UCme"
+                }
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut youtube = YouTube::new(common::client(&server, ClientType::YouTube));
+    let page = youtube
+        .list_subscriptions(Some("page-2".into()))
+        .await
+        .expect("Unable to list subscriptions");
+
+    assert_eq!(page.next_page_token, None);
+    assert_eq!(page.items.len(), 1);
+    let subscription = &page.items[0];
+    assert_eq!(subscription.snippet.title, "Rust Channel");
+    assert_eq!(subscription.channel_id(), Some("UC123"));
+    assert_eq!(
+        subscription.snippet.published_at.to_rfc3339(),
+        "2023-04-01T12:00:00+00:00"
+    );
+}
+
+#[tokio::test]
+async fn test_list_playlist_items() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/playlistItems"))
+        .and(query_param("playlistId", "PL123"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "kind": "youtube#playlistItemListResponse",
+            "nextPageToken": "page-2",
+            "pageInfo": { "totalResults": 2, "resultsPerPage": 1 },
+            "items": [{
+                "kind": "youtube#playlistItem",
+                "id": "item-id",
+                "snippet": {
+                    "publishedAt": "2023-04-02T08:30:00Z",
+                    "channelId": "UCme",
+                    "title": "Intro to async",
+                    "description": "Futures & tasks",
+                    "playlistId": "PL123",
+                    "position": 0,
+                    "resourceId": { "kind": "youtube#video", "videoId": "vid123" },
+                    "videoOwnerChannelTitle": "Rust Channel",
+                    "videoOwnerChannelId": "UC123"
+                }
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut youtube = YouTube::new(common::client(&server, ClientType::YouTube));
+    let page = youtube
+        .list_playlist_items("PL123", None)
+        .await
+        .expect("Unable to list playlist items");
+
+    assert_eq!(page.next_page_token, Some("page-2".into()));
+    let item = &page.items[0];
+    assert_eq!(item.snippet.title, "Intro to async");
+    assert_eq!(item.video_id(), Some("vid123"));
+    assert_eq!(item.snippet.video_owner_channel_id, Some("UC123".into()));
+}
@@ -1,6 +1,7 @@
 use dotenv_codegen::dotenv;
 
 use libauth::helpers::load_credentials;
+use libauth::store::FileStore;
 use libhubspot::{types::AuthScope, HubspotClient};
 
 const REDIRECT_URL: &str = "http://localhost:8080";
@@ -17,11 +18,18 @@ async fn main() -> anyhow::Result<()> {
 
     let mut client = HubspotClient::new(client_id, secret, REDIRECT_URL, Default::default())?;
 
-    load_credentials(&mut client, &scopes, false).await;
+    load_credentials(&mut client, &FileStore::default(), &scopes, false).await;
 
     println!("--- NOTES ---");
     let notes = client
-        .list_objects::<libhubspot::types::Note>(libhubspot::CrmObject::Notes, &[], &[], None, None)
+        .list_objects::<libhubspot::types::Note>(
+            libhubspot::CrmObject::Notes,
+            &[],
+            &[],
+            false,
+            None,
+            None,
+        )
         .await?;
     for (idx, note) in notes.results.iter().enumerate() {
         println!("{idx}: {}", note.raw_body());
@@ -33,6 +41,7 @@ async fn main() -> anyhow::Result<()> {
             libhubspot::CrmObject::Calls,
             &["hs_call_recording_url".into()],
             &[],
+            false,
             None,
             None,
         )
@@ -49,7 +58,14 @@ async fn main() -> anyhow::Result<()> {
 
     println!("--- Tasks ---");
     let tasks = client
-        .list_objects::<libhubspot::types::Task>(libhubspot::CrmObject::Tasks, &[], &[], None, None)
+        .list_objects::<libhubspot::types::Task>(
+            libhubspot::CrmObject::Tasks,
+            &[],
+            &[],
+            false,
+            None,
+            None,
+        )
         .await?;
     for (idx, task) in tasks.results.iter().enumerate() {
         println!(
@@ -68,6 +84,7 @@ async fn main() -> anyhow::Result<()> {
             libhubspot::CrmObject::Emails,
             &[],
             &[],
+            false,
             None,
             None,
         )
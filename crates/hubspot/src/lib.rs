@@ -1,22 +1,28 @@
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use async_trait::async_trait;
+use futures::stream::Stream;
 use libauth::{
-    auth_http_client, oauth_client, ApiClient, ApiError, AuthorizationRequest, AuthorizeOptions,
-    Credentials, OAuthParams,
+    oauth_client, paginate_stream, AccountInfo, ApiClient, ApiError, AuthorizationRequest,
+    AuthorizeOptions, ClientConfig, ClientSettings, Credentials, OAuthParams, RetryPolicy,
+    TokenError,
 };
 use oauth2::{
     basic::{BasicClient, BasicTokenResponse},
     reqwest::async_http_client,
-    AuthorizationCode, CsrfToken, RequestTokenError, Scope, TokenResponse,
+    AuthorizationCode, CsrfToken, Scope, TokenResponse,
 };
 use reqwest::Client;
 use serde::de::DeserializeOwned;
 use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
 use strum_macros::{Display, EnumString};
-use tokio::sync::watch;
+use tokio::sync::{watch, Mutex};
 use types::HubSpotMetaData;
+pub use webhook::verify_signature;
 
 pub mod types;
+pub mod webhook;
 
 const AUTH_URL: &str = "https://app.hubspot.com/oauth/authorize";
 const TOKEN_URL: &str = "https://api.hubapi.com/oauth/v1/token";
@@ -86,7 +92,18 @@ const DEFAULT_PROPERTIES: &[(CrmObject, &[&str])] = &[
     ),
 ];
 
-#[derive(Display, EnumString, PartialEq, Eq, Clone)]
+/// Max number of inputs accepted by the batch read endpoints.
+const BATCH_READ_LIMIT: usize = 100;
+const STREAM_PAGE_SIZE: usize = 100;
+const ENGAGEMENT_OBJECTS: &[CrmObject] = &[
+    CrmObject::Calls,
+    CrmObject::Emails,
+    CrmObject::Meetings,
+    CrmObject::Notes,
+    CrmObject::Tasks,
+];
+
+#[derive(Debug, Display, EnumString, PartialEq, Eq, Hash, Clone)]
 pub enum CrmObject {
     #[strum(serialize = "calls")]
     Calls,
@@ -102,13 +119,33 @@ pub enum CrmObject {
     Tasks,
 }
 
+impl CrmObject {
+    /// Numeric object type id used by HubSpot, e.g. in web app URLs.
+    pub fn object_type_id(&self) -> &'static str {
+        match self {
+            CrmObject::Calls => "0-48",
+            CrmObject::Contacts => "0-1",
+            CrmObject::Emails => "0-49",
+            CrmObject::Meetings => "0-47",
+            CrmObject::Notes => "0-46",
+            CrmObject::Tasks => "0-27",
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct HubspotClient {
+    endpoint: String,
     http: Client,
     pub oauth: BasicClient,
     pub secret: String,
     pub credentials: Credentials,
     pub on_refresh_tx: watch::Sender<Credentials>,
     pub on_refresh_rx: watch::Receiver<Credentials>,
+    settings: ClientSettings,
+    refresh_lock: Arc<Mutex<()>>,
+    pub portal_id: Option<String>,
+    details: Option<types::AccountDetails>,
 }
 
 #[async_trait]
@@ -118,16 +155,30 @@ impl ApiClient for HubspotClient {
     }
 
     async fn account_id(&mut self) -> anyhow::Result<String> {
-        let details = self.account_details().await?;
-        Ok(details.portal_id.to_string())
+        if let Some(portal_id) = &self.portal_id {
+            Ok(portal_id.clone())
+        } else {
+            let portal_id = self.account_details().await?.portal_id.to_string();
+            self.portal_id = Some(portal_id.clone());
+            Ok(portal_id)
+        }
     }
 
     async fn account_metadata(&mut self) -> Option<Value> {
-        self.account_details().await.ok().map(|details| {
-            serde_json::to_value(HubSpotMetaData {
-                portal_id: details.portal_id,
-            })
-            .unwrap()
+        self.account_details()
+            .await
+            .ok()
+            .map(|details| serde_json::to_value(HubSpotMetaData::from(&details)).unwrap())
+    }
+
+    async fn account_info(&mut self) -> anyhow::Result<AccountInfo> {
+        let details = self.account_details().await?;
+        Ok(AccountInfo {
+            id: details.portal_id.to_string(),
+            display_name: details.ui_domain.clone(),
+            email: None,
+            avatar_url: None,
+            metadata: serde_json::to_value(&details)?,
         })
     }
 
@@ -141,7 +192,9 @@ impl ApiClient for HubspotClient {
 
     fn set_credentials(&mut self, credentials: &Credentials) -> anyhow::Result<()> {
         self.credentials = credentials.clone();
-        self.http = auth_http_client(credentials.access_token.secret())?;
+        self.http = self
+            .settings
+            .auth_http_client(credentials.access_token.secret())?;
         Ok(())
     }
 
@@ -149,6 +202,18 @@ impl ApiClient for HubspotClient {
         self.on_refresh_rx.clone()
     }
 
+    fn refresh_lock(&self) -> Arc<Mutex<()>> {
+        self.refresh_lock.clone()
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        self.settings.retry_policy.clone()
+    }
+
+    fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.settings.retry_policy = policy;
+    }
+
     fn authorize(&self, scopes: &[String], options: &AuthorizeOptions) -> AuthorizationRequest {
         let scopes = scopes
             .iter()
@@ -179,7 +244,7 @@ impl ApiClient for HubspotClient {
         &self,
         code: &str,
         _pkce_verifier: Option<String>,
-    ) -> anyhow::Result<BasicTokenResponse> {
+    ) -> Result<BasicTokenResponse, TokenError> {
         let code = AuthorizationCode::new(code.to_owned());
         let exchange = self
             .oauth
@@ -187,21 +252,10 @@ impl ApiClient for HubspotClient {
             .add_extra_param("client_id", self.oauth.client_id().to_string())
             .add_extra_param("client_secret", self.secret.clone());
 
-        match exchange.request_async(async_http_client).await {
-            Ok(val) => Ok(val),
-            Err(err) => match err {
-                RequestTokenError::Parse(err, og) => {
-                    let msg = std::str::from_utf8(&og)
-                        .map(|x| x.to_string())
-                        .unwrap_or(err.to_string());
-                    Err(anyhow!(msg))
-                }
-                x => Err(anyhow!(x.to_string())),
-            },
-        }
+        Ok(exchange.request_async(async_http_client).await?)
     }
 
-    async fn refresh_credentials(&mut self) -> Result<()> {
+    async fn refresh_credentials(&mut self) -> Result<(), TokenError> {
         if let Some(refresh_token) = &self.credentials.refresh_token {
             let req = self
                 .oauth
@@ -209,25 +263,16 @@ impl ApiClient for HubspotClient {
                 .add_extra_param("client_id", self.oauth.client_id().to_string())
                 .add_extra_param("client_secret", self.secret.clone());
 
-            let new_token = match req.request_async(async_http_client).await {
-                Ok(token) => token,
-                Err(err) => {
-                    return match err {
-                        RequestTokenError::Parse(err, og) => {
-                            let msg = std::str::from_utf8(&og)
-                                .map(|x| x.to_string())
-                                .unwrap_or(err.to_string());
-                            Err(anyhow!(msg))
-                        }
-                        x => Err(anyhow!(x.to_string())),
-                    };
-                }
-            };
+            let new_token = req.request_async(async_http_client).await?;
 
             self.credentials.refresh_token(&new_token);
-            self.http = auth_http_client(new_token.access_token().secret())?;
+            self.http = self
+                .settings
+                .auth_http_client(new_token.access_token().secret())?;
             // Let any listeners know the credentials have been updated.
-            self.on_refresh_tx.send(self.credentials.clone())?;
+            self.on_refresh_tx
+                .send(self.credentials.clone())
+                .map_err(anyhow::Error::from)?;
         }
 
         Ok(())
@@ -240,6 +285,23 @@ impl HubspotClient {
         client_secret: &str,
         redirect_url: &str,
         creds: Credentials,
+    ) -> anyhow::Result<Self> {
+        Self::new_with_config(
+            client_id,
+            client_secret,
+            redirect_url,
+            creds,
+            ClientConfig::default(),
+        )
+    }
+
+    /// Same as `new`, with timeouts etc. applied to every request.
+    pub fn new_with_config(
+        client_id: &str,
+        client_secret: &str,
+        redirect_url: &str,
+        creds: Credentials,
+        config: ClientConfig,
     ) -> anyhow::Result<Self> {
         let params = OAuthParams {
             client_id: client_id.to_string(),
@@ -250,34 +312,277 @@ impl HubspotClient {
             revoke_url: None,
         };
 
+        let settings = ClientSettings::new(config);
         let (tx, rx) = watch::channel(creds.clone());
         Ok(HubspotClient {
-            http: auth_http_client(creds.access_token.secret())?,
-            oauth: oauth_client(&params),
+            endpoint: API_ENDPOINT.to_string(),
+            http: settings.auth_http_client(creds.access_token.secret())?,
+            oauth: oauth_client(&params)?,
             secret: client_secret.to_string(),
             credentials: creds,
             on_refresh_tx: tx,
             on_refresh_rx: rx,
+            settings,
+            refresh_lock: Arc::new(Mutex::new(())),
+            portal_id: None,
+            details: None,
         })
     }
 
+    /// Override the base API URL, e.g. to target a mock server.
+    pub fn set_endpoint(&mut self, endpoint: &str) {
+        self.endpoint = endpoint.trim_end_matches('/').to_string();
+    }
+
+    /// Override the user agent sent with API requests.
+    pub fn set_user_agent(&mut self, user_agent: &str) -> Result<()> {
+        self.settings.user_agent = user_agent.to_string();
+        self.http = self
+            .settings
+            .auth_http_client(self.credentials.access_token.secret())?;
+        Ok(())
+    }
+
     pub async fn account_details(&mut self) -> Result<types::AccountDetails, ApiError> {
-        let endpoint = format!("{API_ENDPOINT}/account-info/v3/details");
-        serde_json::from_value::<types::AccountDetails>(self.call_json(&endpoint, &[]).await?)
-            .map_err(ApiError::SerdeError)
+        let endpoint = format!("{}/account-info/v3/details", self.endpoint);
+        let details =
+            serde_json::from_value::<types::AccountDetails>(self.call_json(&endpoint, &[]).await?)
+                .map_err(ApiError::SerdeError)?;
+
+        self.details = Some(details.clone());
+        Ok(details)
     }
 
+    /// Link to view a CRM object in the HubSpot web app.
+    pub async fn object_url(&mut self, object: CrmObject, id: &str) -> Result<String, ApiError> {
+        let details = match &self.details {
+            Some(details) => details.clone(),
+            None => self.account_details().await?,
+        };
+
+        Ok(format!(
+            "https://{}/contacts/{}/record/{}/{id}",
+            details.ui_domain,
+            details.portal_id,
+            object.object_type_id()
+        ))
+    }
+
+    /// Fetch a single object, `None` if no object exists with that `id`.
     pub async fn get_object<T>(
         &mut self,
         object: CrmObject,
         id: &str,
         properties: &[String],
         associations: &[String],
-    ) -> Result<T, ApiError>
+        archived: bool,
+    ) -> Result<Option<T>, ApiError>
+    where
+        T: DeserializeOwned,
+    {
+        let value = match self
+            .get_object_raw(object, id, properties, associations, archived)
+            .await
+        {
+            Ok(value) => value,
+            Err(err) if err.status() == Some(reqwest::StatusCode::NOT_FOUND) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        serde_json::from_value(value)
+            .map(Some)
+            .map_err(ApiError::SerdeError)
+    }
+
+    /// Fetch an object along with the change history of `properties`, e.g. to
+    /// find what changed since the last sync.
+    pub async fn get_object_with_history<T>(
+        &mut self,
+        object: CrmObject,
+        id: &str,
+        properties: &[String],
+    ) -> Result<types::ObjectWithHistory<T>, ApiError>
+    where
+        T: DeserializeOwned,
+    {
+        let endpoint = format!("{}/crm/v3/objects/{object}/{id}", self.endpoint);
+        let query = vec![
+            (
+                "properties".to_string(),
+                with_default_props(&object, properties).join(","),
+            ),
+            ("propertiesWithHistory".to_string(), properties.join(",")),
+        ];
+
+        serde_json::from_value(self.call_json(&endpoint, &query).await?)
+            .map_err(ApiError::SerdeError)
+    }
+
+    /// Fetch an object along with the ids of its associated objects for each of
+    /// the requested `assoc_objects` types.
+    pub async fn get_object_with_associations<T>(
+        &mut self,
+        object: CrmObject,
+        id: &str,
+        properties: &[String],
+        assoc_objects: &[CrmObject],
+    ) -> Result<types::ObjectWithAssociations<T>, ApiError>
+    where
+        T: DeserializeOwned,
+    {
+        let result = serde_json::from_value(
+            self.get_object_raw(object.clone(), id, properties, &[], false)
+                .await?,
+        )
+        .map_err(ApiError::SerdeError)?;
+
+        let mut associations = HashMap::new();
+        for to_object in assoc_objects {
+            let ids = self.list_association_ids(&object, id, to_object).await?;
+            associations.insert(to_object.clone(), ids);
+        }
+
+        Ok(types::ObjectWithAssociations {
+            object: result,
+            associations,
+        })
+    }
+
+    /// List the ids of all `to_object` objects associated with an object, following
+    /// pagination until exhausted.
+    pub async fn list_association_ids(
+        &mut self,
+        object: &CrmObject,
+        id: &str,
+        to_object: &CrmObject,
+    ) -> Result<Vec<String>, ApiError> {
+        let associations = self
+            .get_associations(object.clone(), id, to_object.clone())
+            .await?;
+        Ok(associations
+            .iter()
+            .map(|obj| obj.to_object_id.to_string())
+            .collect())
+    }
+
+    /// List all `to_object` objects associated with an object along with the
+    /// association types (& labels), without fetching the objects themselves.
+    pub async fn get_associations(
+        &mut self,
+        from_object: CrmObject,
+        from_id: &str,
+        to_object: CrmObject,
+    ) -> Result<Vec<types::AssociatedObject>, ApiError> {
+        let endpoint = format!(
+            "{}/crm/v4/objects/{from_object}/{from_id}/associations/{to_object}",
+            self.endpoint
+        );
+
+        let mut associations = Vec::new();
+        let mut after: Option<String> = None;
+        loop {
+            let mut query = vec![("limit".to_string(), "500".to_string())];
+            if let Some(after) = after {
+                query.push(("after".into(), after));
+            }
+
+            let page = serde_json::from_value::<types::PagedResults<types::AssociatedObject>>(
+                self.call_json(&endpoint, &query).await?,
+            )
+            .map_err(ApiError::SerdeError)?;
+            associations.extend(page.results);
+
+            after = page
+                .paging
+                .map(|paging| paging.next.after)
+                .filter(|after| !after.is_empty());
+            if after.is_none() {
+                break;
+            }
+        }
+
+        Ok(associations)
+    }
+
+    /// Read up to `ids.len()` objects of the same type, batching requests to
+    /// stay within HubSpot's per-request limit.
+    pub async fn batch_read_objects<T>(
+        &mut self,
+        object: &CrmObject,
+        ids: &[String],
+        properties: &[String],
+    ) -> Result<Vec<T>, ApiError>
     where
         T: DeserializeOwned,
     {
-        let endpoint = format!("{API_ENDPOINT}/crm/v3/objects/{}/{id}", object);
+        let endpoint = format!("{}/crm/v3/objects/{object}/batch/read", self.endpoint);
+
+        let props = with_default_props(object, properties);
+
+        let mut objects = Vec::new();
+        for chunk in ids.chunks(BATCH_READ_LIMIT) {
+            let inputs = chunk
+                .iter()
+                .map(|id| serde_json::json!({ "id": id }))
+                .collect::<Vec<_>>();
+            let body = serde_json::json!({ "properties": props, "inputs": inputs });
+
+            let batch = serde_json::from_value::<types::BatchResults<T>>(
+                self.post_json(&endpoint, body).await?,
+            )
+            .map_err(ApiError::SerdeError)?;
+            objects.extend(batch.results);
+        }
+
+        Ok(objects)
+    }
+
+    /// Calls, emails, meetings, notes & tasks associated with a contact, oldest
+    /// first with undated engagements last. HubSpot has no combined timeline
+    /// endpoint and associations aren't ordered by date, so every engagement is
+    /// fetched and sorted.
+    pub async fn get_engagements(
+        &mut self,
+        contact_id: &str,
+    ) -> Result<Vec<types::Engagement>, ApiError> {
+        let timestamp = ["hs_timestamp".to_string()];
+        let mut engagements = Vec::new();
+        for object in ENGAGEMENT_OBJECTS {
+            let ids = self
+                .list_association_ids(&CrmObject::Contacts, contact_id, object)
+                .await?;
+            if ids.is_empty() {
+                continue;
+            }
+
+            for value in self
+                .batch_read_objects::<Value>(object, &ids, &timestamp)
+                .await?
+            {
+                engagements.extend(
+                    types::Engagement::from_value(object, value).map_err(ApiError::SerdeError)?,
+                );
+            }
+        }
+
+        engagements.sort_by_cached_key(|engagement| {
+            let timestamp = engagement.timestamp();
+            (timestamp.is_none(), timestamp)
+        });
+        Ok(engagements)
+    }
+
+    /// Fetch a single CRM object as JSON, with property values left as HubSpot
+    /// sends them. Useful for custom properties whose type we can't map.
+    pub async fn get_object_raw(
+        &mut self,
+        object: CrmObject,
+        id: &str,
+        properties: &[String],
+        associations: &[String],
+        archived: bool,
+    ) -> Result<Value, ApiError> {
+        let endpoint = format!("{}/crm/v3/objects/{}/{id}", self.endpoint, object);
 
         let props = properties.join(",");
         let default_props = default_prop_as_string(&object);
@@ -299,8 +604,12 @@ impl HubspotClient {
             query.push(("associations".into(), associations.join(",").to_string()))
         }
 
-        serde_json::from_value(self.call_json(&endpoint, &query).await?)
-            .map_err(ApiError::SerdeError)
+        // Only return archived objects
+        if archived {
+            query.push(("archived".into(), "true".into()));
+        }
+
+        self.call_json(&endpoint, &query).await
     }
 
     pub async fn list_objects<T>(
@@ -308,13 +617,107 @@ impl HubspotClient {
         object: CrmObject,
         properties: &[String],
         associations: &[String],
+        archived: bool,
         after: Option<String>,
         limit: Option<usize>,
     ) -> Result<types::PagedResults<T>, ApiError>
     where
         T: DeserializeOwned,
     {
-        let endpoint = format!("{API_ENDPOINT}/crm/v3/objects/{}", object);
+        serde_json::from_value(
+            self.list_objects_raw(object, properties, associations, archived, after, limit)
+                .await?,
+        )
+        .map_err(ApiError::SerdeError)
+    }
+
+    /// Search objects using HubSpot's filter syntax, e.g. contacts with an email
+    /// ending in a domain. HubSpot returns at most 200 objects per page.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn search_objects<T>(
+        &mut self,
+        object: CrmObject,
+        filter_groups: &[types::FilterGroup],
+        sorts: &[types::SearchSort],
+        query: Option<&str>,
+        properties: &[String],
+        after: Option<String>,
+        limit: usize,
+    ) -> Result<types::PagedResults<T>, ApiError>
+    where
+        T: DeserializeOwned,
+    {
+        let endpoint = format!("{}/crm/v3/objects/{object}/search", self.endpoint);
+
+        let mut body = serde_json::json!({
+            "filterGroups": filter_groups,
+            "sorts": sorts,
+            "properties": with_default_props(&object, properties),
+            "limit": limit.clamp(1, 200),
+        });
+        if let Some(query) = query {
+            body["query"] = Value::String(query.to_string());
+        }
+        if let Some(after) = after.filter(|after| !after.is_empty()) {
+            body["after"] = Value::String(after);
+        }
+
+        serde_json::from_value(self.post_json(&endpoint, body).await?).map_err(ApiError::SerdeError)
+    }
+
+    /// Stream every object of a type, lazily fetching pages of `STREAM_PAGE_SIZE`
+    /// objects as the stream is consumed. The stream ends after the first error.
+    pub fn stream_objects<'a, T>(
+        &'a mut self,
+        object: CrmObject,
+        properties: &[String],
+        associations: &[String],
+    ) -> impl Stream<Item = Result<T, ApiError>> + 'a
+    where
+        T: DeserializeOwned + Send + 'a,
+    {
+        let properties = properties.to_vec();
+        let associations = associations.to_vec();
+        let client = Arc::new(Mutex::new(self));
+        paginate_stream(move |after: Option<String>| {
+            let client = client.clone();
+            let object = object.clone();
+            let properties = properties.clone();
+            let associations = associations.clone();
+            async move {
+                let page = client
+                    .lock()
+                    .await
+                    .list_objects::<T>(
+                        object,
+                        &properties,
+                        &associations,
+                        false,
+                        after,
+                        Some(STREAM_PAGE_SIZE),
+                    )
+                    .await?;
+                let after = page
+                    .paging
+                    .map(|paging| paging.next.after)
+                    .filter(|after| !after.is_empty());
+                Ok((page.results, after))
+            }
+        })
+    }
+
+    /// A page of CRM objects as JSON, the cursor for the next page is at
+    /// `paging.next.after`. See `list_objects` for the parameters.
+    pub async fn list_objects_raw(
+        &mut self,
+        object: CrmObject,
+        properties: &[String],
+        associations: &[String],
+        archived: bool,
+        after: Option<String>,
+        limit: Option<usize>,
+    ) -> Result<Value, ApiError> {
+        let endpoint = format!("{}/crm/v3/objects/{}", self.endpoint, object);
         let props = properties.to_vec();
         let default_props = default_prop_as_string(&object);
 
@@ -338,17 +741,41 @@ impl HubspotClient {
 
         query.push(("limit".into(), limit.unwrap_or(10).to_string()));
 
-        if let Some(after) = after {
-            query.push(("after".into(), after.clone()));
+        // HubSpot rejects an empty cursor, so only send it when there's one to send.
+        if let Some(after) = after.filter(|after| !after.is_empty()) {
+            query.push(("after".into(), after));
         }
 
         if !associations.is_empty() {
             query.push(("associations".into(), associations.join(",").to_string()))
         }
 
-        serde_json::from_value(self.call_json(&endpoint, &query).await?)
-            .map_err(ApiError::SerdeError)
+        // Only return archived objects
+        if archived {
+            query.push(("archived".into(), "true".into()));
+        }
+
+        self.call_json(&endpoint, &query).await
+    }
+}
+
+fn default_props(object: &CrmObject) -> Vec<String> {
+    DEFAULT_PROPERTIES
+        .iter()
+        .find(|(obj, _)| obj == object)
+        .map(|(_, props)| props.iter().map(|prop| prop.to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// Default properties for `object` followed by any extra `properties`.
+fn with_default_props(object: &CrmObject, properties: &[String]) -> Vec<String> {
+    let mut props = default_props(object);
+    for prop in properties {
+        if !props.contains(prop) {
+            props.push(prop.to_string());
+        }
     }
+    props
 }
 
 pub fn default_prop_as_string(object: &CrmObject) -> Option<String> {
@@ -1,10 +1,15 @@
 use std::collections::HashMap;
 
+use chrono::{DateTime, Utc};
+use libauth::helpers::html_to_text;
+use libauth::Document;
+
+use crate::CrmObject;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use strum_macros::{AsRefStr, Display};
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(default, rename_all = "camelCase")]
 pub struct AccountDetails {
     pub portal_id: i32,
@@ -58,6 +63,7 @@ impl AuthScope {
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
 pub struct NextPage {
     pub after: String,
     pub link: String,
@@ -74,6 +80,86 @@ pub struct PagedResults<T> {
     pub results: Vec<T>,
 }
 
+/// Comparison used by a `SearchFilter`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Operator {
+    Eq,
+    Neq,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    /// Between `value` and `high_value`, inclusive.
+    Between,
+    /// Any of `values`.
+    In,
+    NotIn,
+    HasProperty,
+    NotHasProperty,
+    ContainsToken,
+    NotContainsToken,
+}
+
+/// A single condition in a CRM search.
+/// https://developers.hubspot.com/docs/api/crm/search#filter-search-results
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFilter {
+    pub property_name: String,
+    pub operator: Operator,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub high_value: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>,
+}
+
+impl SearchFilter {
+    pub fn new(property_name: &str, operator: Operator, value: &str) -> Self {
+        SearchFilter {
+            property_name: property_name.to_string(),
+            operator,
+            value: Some(value.to_string()),
+            high_value: None,
+            values: Vec::new(),
+        }
+    }
+
+    /// Filter for objects that have (or with `NotHasProperty`, don't have) a
+    /// value for `property_name`.
+    pub fn exists(property_name: &str, operator: Operator) -> Self {
+        SearchFilter {
+            property_name: property_name.to_string(),
+            operator,
+            value: None,
+            high_value: None,
+            values: Vec::new(),
+        }
+    }
+}
+
+/// Filters within a group are ANDed together, groups are ORed.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct FilterGroup {
+    pub filters: Vec<SearchFilter>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSort {
+    pub property_name: String,
+    pub direction: SortDirection,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct AssociationResult {
     pub results: Vec<Association>,
@@ -86,6 +172,53 @@ pub struct Association {
     pub association_type: String,
 }
 
+/// An object associated with another, as returned by the v4 associations API.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AssociatedObject {
+    pub to_object_id: u64,
+    pub association_types: Vec<AssociationType>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AssociationType {
+    /// Either "HUBSPOT_DEFINED" or "USER_DEFINED".
+    pub category: String,
+    pub type_id: u32,
+    pub label: Option<String>,
+}
+
+/// An object along with the ids of its associated objects, grouped by type.
+#[derive(Debug, Default)]
+pub struct ObjectWithAssociations<T> {
+    pub object: T,
+    pub associations: HashMap<CrmObject, Vec<String>>,
+}
+
+/// A previous (or the current) value of a property.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PropertyHistoryEntry {
+    pub value: String,
+    pub timestamp: DateTime<Utc>,
+    /// What made the change, e.g. "CRM_UI", "API" or "CALCULATED".
+    pub source_type: String,
+    pub source_id: Option<String>,
+    pub updated_by_user_id: Option<u64>,
+}
+
+/// An object along with the change history of the requested properties,
+/// most recent change first.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectWithHistory<T> {
+    #[serde(flatten)]
+    pub object: T,
+    #[serde(default)]
+    pub properties_with_history: HashMap<String, Vec<PropertyHistoryEntry>>,
+}
+
 /// Note: That the CRM objects "Call", "Email", "Meeting", etc. all have
 /// pretty much the same structure. This is separated out for type safety and
 /// in case there's any specific impl details for a particular object (e.g. note
@@ -263,6 +396,37 @@ impl Note {
     }
 }
 
+impl Document for Note {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    /// Notes don't have a title, so the first line of the body text is used instead.
+    fn title(&self) -> String {
+        html_to_text(&self.raw_body())
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    }
+
+    /// The note body with any HTML stripped.
+    fn body_text(&self) -> String {
+        html_to_text(&self.raw_body())
+    }
+
+    fn url(&self) -> Option<String> {
+        None
+    }
+
+    fn updated_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.updated_at)
+            .ok()
+            .map(|date| date.with_timezone(&Utc))
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(default, rename_all = "camelCase")]
 pub struct Task {
@@ -356,10 +520,100 @@ impl Task {
     }
 }
 
+/// Response from the batch read endpoints.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct BatchResults<T> {
+    pub results: Vec<T>,
+}
+
+/// A single activity associated with a CRM record, used to build an activity
+/// feed across the different engagement types.
+#[derive(Clone, Debug)]
+pub enum Engagement {
+    Call(Call),
+    Email(Email),
+    Meeting(Meeting),
+    Note(Note),
+    Task(Task),
+}
+
+impl Engagement {
+    /// Deserialize an object of an engagement type, `None` for other types
+    /// such as contacts.
+    pub fn from_value(object: &CrmObject, value: Value) -> Result<Option<Self>, serde_json::Error> {
+        let engagement = match object {
+            CrmObject::Calls => Engagement::Call(serde_json::from_value(value)?),
+            CrmObject::Emails => Engagement::Email(serde_json::from_value(value)?),
+            CrmObject::Meetings => Engagement::Meeting(serde_json::from_value(value)?),
+            CrmObject::Notes => Engagement::Note(serde_json::from_value(value)?),
+            CrmObject::Tasks => Engagement::Task(serde_json::from_value(value)?),
+            CrmObject::Contacts => return Ok(None),
+        };
+        Ok(Some(engagement))
+    }
+
+    pub fn object(&self) -> CrmObject {
+        match self {
+            Engagement::Call(_) => CrmObject::Calls,
+            Engagement::Email(_) => CrmObject::Emails,
+            Engagement::Meeting(_) => CrmObject::Meetings,
+            Engagement::Note(_) => CrmObject::Notes,
+            Engagement::Task(_) => CrmObject::Tasks,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        match self {
+            Engagement::Call(call) => &call.id,
+            Engagement::Email(email) => &email.id,
+            Engagement::Meeting(meeting) => &meeting.id,
+            Engagement::Note(note) => &note.id,
+            Engagement::Task(task) => &task.id,
+        }
+    }
+
+    fn parts(&self) -> (&HashMap<String, Value>, &str) {
+        match self {
+            Engagement::Call(call) => (&call.properties, &call.created_at),
+            Engagement::Email(email) => (&email.properties, &email.created_at),
+            Engagement::Meeting(meeting) => (&meeting.properties, &meeting.created_at),
+            Engagement::Note(note) => (&note.properties, &note.created_at),
+            Engagement::Task(task) => (&task.properties, &task.created_at),
+        }
+    }
+
+    /// When the activity happened, taken from `hs_timestamp` and falling back
+    /// to the creation date.
+    pub fn timestamp(&self) -> Option<DateTime<Utc>> {
+        let (properties, created_at) = self.parts();
+        properties
+            .get("hs_timestamp")
+            .and_then(|s| s.as_str())
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .or_else(|| DateTime::parse_from_rfc3339(created_at).ok())
+            .map(|date| date.with_timezone(&Utc))
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(default, rename_all = "camelCase")]
 pub struct HubSpotMetaData {
     pub portal_id: i32,
+    pub time_zone: String,
+    pub company_currency: String,
+    /// Domain of the HubSpot web app for this account, e.g. "app.hubspot.com".
+    pub ui_domain: String,
+}
+
+impl From<&AccountDetails> for HubSpotMetaData {
+    fn from(details: &AccountDetails) -> Self {
+        HubSpotMetaData {
+            portal_id: details.portal_id,
+            time_zone: details.time_zone.clone(),
+            company_currency: details.company_currency.clone(),
+            ui_domain: details.ui_domain.clone(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -378,3 +632,39 @@ pub struct WebhookEvent {
     pub change_source: String,
     pub source_id: String,
 }
+
+#[cfg(test)]
+mod test {
+    use super::Note;
+    use libauth::Document;
+
+    #[test]
+    pub fn test_note_body_text() {
+        let note = Note {
+            properties: [(
+                "hs_note_body".to_string(),
+                serde_json::json!("<div><p>Follow up <b>next week</b></p></div>"),
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+
+        assert_eq!(note.body_text(), "Follow up next week");
+    }
+
+    #[test]
+    pub fn test_note_title() {
+        let note = Note {
+            properties: [(
+                "hs_note_body".to_string(),
+                serde_json::json!("<div><p>Follow up <b>next week</b></p>\n<p>Call Bob</p></div>"),
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+
+        assert_eq!(note.title(), "Follow up next week");
+    }
+}
@@ -0,0 +1,35 @@
+use libauth::ApiClient;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_account_info() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/account-info/v3/details"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "portalId": 62515,
+            "timeZone": "America/New_York",
+            "companyCurrency": "USD",
+            "utcOffset": "-05:00",
+            "utcOffsetMilliseconds": -18000000,
+            "uiDomain": "app.hubspot.com",
+            "dataHostingLocation": "na1"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let info = common::client(&server)
+        .account_info()
+        .await
+        .expect("Unable to get account info");
+    assert_eq!(info.id, "62515");
+    assert_eq!(info.display_name, "app.hubspot.com");
+    assert_eq!(info.email, None);
+    assert_eq!(info.avatar_url, None);
+    assert_eq!(info.metadata["timeZone"], "America/New_York");
+}
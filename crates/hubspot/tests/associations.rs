@@ -0,0 +1,108 @@
+use libhubspot::types::Contact;
+use libhubspot::CrmObject;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_get_object_with_associations() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/crm/v3/objects/contacts/51"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "51",
+            "properties": { "email": "jane@example.com" }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/crm/v4/objects/contacts/51/associations/notes"))
+        .and(query_param("after", "cursor"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": [{ "toObjectId": 102, "associationTypes": [] }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/crm/v4/objects/contacts/51/associations/notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": [{
+                "toObjectId": 101,
+                "associationTypes": [
+                    { "category": "HUBSPOT_DEFINED", "typeId": 202, "label": null }
+                ]
+            }],
+            "paging": { "next": { "after": "cursor" } }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/crm/v4/objects/contacts/51/associations/calls"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": []
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let result = client
+        .get_object_with_associations::<Contact>(
+            CrmObject::Contacts,
+            "51",
+            &[],
+            &[CrmObject::Notes, CrmObject::Calls],
+        )
+        .await
+        .expect("Unable to get object");
+
+    assert_eq!(result.object.id, "51");
+    assert_eq!(
+        result.associations.get(&CrmObject::Notes),
+        Some(&vec!["101".to_string(), "102".to_string()])
+    );
+    assert_eq!(result.associations.get(&CrmObject::Calls), Some(&vec![]));
+}
+
+#[tokio::test]
+async fn test_get_associations() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/crm/v4/objects/calls/301/associations/contacts"))
+        .and(query_param("limit", "500"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": [{
+                "toObjectId": 51,
+                "associationTypes": [
+                    { "category": "HUBSPOT_DEFINED", "typeId": 194, "label": null },
+                    { "category": "USER_DEFINED", "typeId": 12, "label": "Decision maker" }
+                ]
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let associations = client
+        .get_associations(CrmObject::Calls, "301", CrmObject::Contacts)
+        .await
+        .expect("Unable to get associations");
+    assert_eq!(associations.len(), 1);
+    assert_eq!(associations[0].to_object_id, 51);
+    assert_eq!(
+        associations[0].association_types[1].label.as_deref(),
+        Some("Decision maker")
+    );
+}
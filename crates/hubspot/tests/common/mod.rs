@@ -0,0 +1,5 @@
+#![allow(dead_code)]
+
+use libhubspot::HubspotClient;
+
+libauth::mock_client_factory!(HubspotClient);
@@ -0,0 +1,204 @@
+use chrono::{TimeZone, Utc};
+use libhubspot::types::Engagement;
+use libhubspot::CrmObject;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+mod common;
+
+async fn mock_associations(server: &MockServer, object: &str, ids: &[u64]) {
+    let results = ids
+        .iter()
+        .map(|id| serde_json::json!({ "toObjectId": id, "associationTypes": [] }))
+        .collect::<Vec<_>>();
+
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "/crm/v4/objects/contacts/51/associations/{object}"
+        )))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "results": results })),
+        )
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+/// Returns a note for every id in a batch read, dated by its id.
+struct NotesResponder;
+
+impl Respond for NotesResponder {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let body: serde_json::Value = request.body_json().expect("Invalid body");
+        let results = body["inputs"]
+            .as_array()
+            .expect("Missing inputs")
+            .iter()
+            .map(|input| {
+                let id = input["id"].as_str().unwrap().parse::<i64>().unwrap();
+                let timestamp = Utc.timestamp_opt(id * 60, 0).unwrap();
+                serde_json::json!({
+                    "id": id.to_string(),
+                    "properties": { "hs_timestamp": timestamp.to_rfc3339() }
+                })
+            })
+            .collect::<Vec<_>>();
+        ResponseTemplate::new(200)
+            .set_body_json(serde_json::json!({ "status": "COMPLETE", "results": results }))
+    }
+}
+
+#[tokio::test]
+async fn test_get_engagements() {
+    let server = MockServer::start().await;
+
+    mock_associations(&server, "calls", &[11]).await;
+    mock_associations(&server, "emails", &[]).await;
+    mock_associations(&server, "meetings", &[31]).await;
+    mock_associations(&server, "notes", &[41, 42]).await;
+    mock_associations(&server, "tasks", &[]).await;
+
+    Mock::given(method("POST"))
+        .and(path("/crm/v3/objects/calls/batch/read"))
+        .and(body_partial_json(
+            serde_json::json!({ "inputs": [{ "id": "11" }] }),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "status": "COMPLETE",
+            "results": [{
+                "id": "11",
+                "createdAt": "2023-01-05T00:00:00Z",
+                "properties": { "hs_timestamp": "2023-01-02T10:00:00.000Z" }
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/crm/v3/objects/meetings/batch/read"))
+        .and(body_partial_json(serde_json::json!({
+            "properties": ["hs_timestamp"],
+            "inputs": [{ "id": "31" }]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "status": "COMPLETE",
+            "results": [{
+                "id": "31",
+                "createdAt": "2023-01-03T00:00:00Z",
+                "properties": {}
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/crm/v3/objects/notes/batch/read"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "status": "COMPLETE",
+            "results": [
+                {
+                    "id": "41",
+                    "properties": { "hs_timestamp": "2023-01-04T00:00:00Z" }
+                },
+                {
+                    "id": "42",
+                    "properties": { "hs_timestamp": "2023-01-01T00:00:00Z" }
+                }
+            ]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let engagements = client
+        .get_engagements("51")
+        .await
+        .expect("Unable to get engagements");
+
+    assert_eq!(
+        engagements
+            .iter()
+            .map(|engagement| engagement.id())
+            .collect::<Vec<_>>(),
+        vec!["42", "11", "31", "41"]
+    );
+    assert!(matches!(engagements[2], Engagement::Meeting(_)));
+    assert_eq!(engagements[1].object(), CrmObject::Calls);
+}
+
+#[tokio::test]
+async fn test_get_engagements_undated_last() {
+    let server = MockServer::start().await;
+
+    mock_associations(&server, "calls", &[]).await;
+    mock_associations(&server, "emails", &[]).await;
+    mock_associations(&server, "meetings", &[]).await;
+    mock_associations(&server, "notes", &[41, 42]).await;
+    mock_associations(&server, "tasks", &[]).await;
+
+    Mock::given(method("POST"))
+        .and(path("/crm/v3/objects/notes/batch/read"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "status": "COMPLETE",
+            "results": [
+                { "id": "41", "properties": {} },
+                {
+                    "id": "42",
+                    "properties": { "hs_timestamp": "2023-01-01T00:00:00Z" }
+                }
+            ]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let engagements = common::client(&server)
+        .get_engagements("51")
+        .await
+        .expect("Unable to get engagements");
+
+    assert_eq!(
+        engagements
+            .iter()
+            .map(|engagement| engagement.id())
+            .collect::<Vec<_>>(),
+        vec!["42", "41"]
+    );
+    assert_eq!(engagements[1].timestamp(), None);
+}
+
+#[tokio::test]
+async fn test_get_engagements_batches() {
+    let server = MockServer::start().await;
+
+    let ids = (1..=101u64).rev().collect::<Vec<_>>();
+    mock_associations(&server, "calls", &[]).await;
+    mock_associations(&server, "emails", &[]).await;
+    mock_associations(&server, "meetings", &[]).await;
+    mock_associations(&server, "notes", &ids).await;
+    mock_associations(&server, "tasks", &[]).await;
+
+    // Batch reads are limited to 100 inputs.
+    Mock::given(method("POST"))
+        .and(path("/crm/v3/objects/notes/batch/read"))
+        .respond_with(NotesResponder)
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let engagements = common::client(&server)
+        .get_engagements("51")
+        .await
+        .expect("Unable to get engagements");
+    assert_eq!(
+        engagements
+            .iter()
+            .map(|engagement| engagement.id())
+            .collect::<Vec<_>>(),
+        (1..=101).map(|id| id.to_string()).collect::<Vec<_>>()
+    );
+}
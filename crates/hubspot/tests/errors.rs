@@ -0,0 +1,105 @@
+use libauth::{ApiClient, ApiError};
+use libhubspot::types::Note;
+use libhubspot::CrmObject;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_error_body_is_kept() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/crm/v3/objects/notes/1"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "status": "error",
+            "message": "Property values were not valid",
+            "category": "VALIDATION_ERROR"
+        })))
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let err = client
+        .get_object::<Note>(CrmObject::Notes, "1", &[], &[], false)
+        .await
+        .expect_err("Expected an error");
+
+    match err {
+        ApiError::ApiStatus { status, body, url } => {
+            assert_eq!(status, 400);
+            let body: serde_json::Value = serde_json::from_str(&body).expect("Invalid body");
+            assert_eq!(body["category"], "VALIDATION_ERROR");
+            assert_eq!(
+                url,
+                Some(format!("GET {}/crm/v3/objects/notes/1", server.uri()))
+            );
+        }
+        err => panic!("Unexpected error: {err}"),
+    }
+}
+
+#[tokio::test]
+async fn test_missing_object() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/crm/v3/objects/notes/1"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "status": "error",
+            "message": "Object not found.  objectId are usually numeric.",
+            "category": "OBJECT_NOT_FOUND"
+        })))
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let note = client
+        .get_object::<Note>(CrmObject::Notes, "1", &[], &[], false)
+        .await
+        .expect("Unable to get object");
+    assert!(note.is_none());
+}
+
+#[tokio::test]
+async fn test_revoke_unsupported() {
+    let server = MockServer::start().await;
+    let client = common::client(&server);
+
+    let err = client
+        .revoke()
+        .await
+        .expect_err("Revoke should not be supported");
+    assert!(err.to_string().contains("revocation not supported"));
+}
+
+#[tokio::test]
+async fn test_invalid_json_body() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/crm/v3/objects/notes/1"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw("<html><body>Service Unavailable</body></html>", "text/html"),
+        )
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let err = client
+        .get_object::<Note>(CrmObject::Notes, "1", &[], &[], false)
+        .await
+        .expect_err("Expected an error");
+
+    match err {
+        ApiError::InvalidJson { body, .. } => {
+            assert_eq!(body, "<html><body>Service Unavailable</body></html>");
+        }
+        err => panic!("Expected InvalidJson, got {err:?}"),
+    }
+}
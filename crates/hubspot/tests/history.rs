@@ -0,0 +1,66 @@
+use libhubspot::types::Contact;
+use libhubspot::CrmObject;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_get_object_with_history() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/crm/v3/objects/contacts/51"))
+        .and(query_param("propertiesWithHistory", "email,lifecyclestage"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "51",
+            "properties": { "email": "ferris@example.com", "lifecyclestage": "customer" },
+            "propertiesWithHistory": {
+                "email": [{
+                    "value": "ferris@example.com",
+                    "timestamp": "2024-02-05T08:00:00.000Z",
+                    "sourceType": "CRM_UI",
+                    "updatedByUserId": 42
+                }],
+                "lifecyclestage": [
+                    {
+                        "value": "customer",
+                        "timestamp": "2024-02-06T08:00:00.000Z",
+                        "sourceType": "API"
+                    },
+                    {
+                        "value": "lead",
+                        "timestamp": "2024-02-05T08:00:00.000Z",
+                        "sourceType": "CRM_UI"
+                    }
+                ]
+            },
+            "createdAt": "2024-02-05T08:00:00Z",
+            "updatedAt": "2024-02-06T08:00:00Z",
+            "archived": false
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let contact = client
+        .get_object_with_history::<Contact>(
+            CrmObject::Contacts,
+            "51",
+            &["email".to_string(), "lifecyclestage".to_string()],
+        )
+        .await
+        .expect("Unable to get contact");
+
+    assert_eq!(contact.object.id, "51");
+    let stages = &contact.properties_with_history["lifecyclestage"];
+    assert_eq!(stages.len(), 2);
+    assert_eq!(stages[1].value, "lead");
+    assert_eq!(stages[0].source_type, "API");
+    assert_eq!(
+        contact.properties_with_history["email"][0].updated_by_user_id,
+        Some(42)
+    );
+}
@@ -0,0 +1,221 @@
+use futures::StreamExt;
+use libhubspot::types::Note;
+use libhubspot::{default_prop_as_string, CrmObject};
+use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+fn fixture(body: &str) -> serde_json::Value {
+    serde_json::from_str(body).expect("Invalid fixture")
+}
+
+#[tokio::test]
+async fn test_list_objects_paginates() {
+    let server = MockServer::start().await;
+    let default_props = default_prop_as_string(&CrmObject::Notes).expect("No default props");
+
+    Mock::given(method("GET"))
+        .and(path("/crm/v3/objects/notes"))
+        .and(query_param(
+            "properties",
+            format!("{default_props},custom_prop"),
+        ))
+        .and(query_param("limit", "1"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(fixture(include_str!("../fixtures/notes_page_1.json"))),
+        )
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/crm/v3/objects/notes"))
+        .and(query_param("after", "102"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(fixture(include_str!("../fixtures/notes_page_2.json"))),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let properties = vec!["custom_prop".to_string()];
+    let first = client
+        .list_objects::<Note>(CrmObject::Notes, &properties, &[], false, None, Some(1))
+        .await
+        .expect("Unable to list notes");
+    assert_eq!(first.results.len(), 1);
+    assert_eq!(first.results[0].raw_body(), "First note");
+
+    let after = first.paging.map(|paging| paging.next.after);
+    assert_eq!(after, Some("102".to_string()));
+
+    let second = client
+        .list_objects::<Note>(CrmObject::Notes, &properties, &[], false, after, Some(1))
+        .await
+        .expect("Unable to list notes");
+    assert!(second.paging.is_none());
+    assert_eq!(second.results[0].id, "102");
+}
+
+#[tokio::test]
+async fn test_list_objects_skips_empty_cursor() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/crm/v3/objects/notes"))
+        .and(query_param_is_missing("after"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(fixture(include_str!("../fixtures/notes_page_2.json"))),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let results = client
+        .list_objects::<Note>(CrmObject::Notes, &[], &[], false, Some(String::new()), None)
+        .await
+        .expect("Unable to list notes");
+    assert_eq!(results.results.len(), 1);
+}
+
+#[tokio::test]
+async fn test_stream_objects() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/crm/v3/objects/notes"))
+        .and(query_param("limit", "100"))
+        .and(query_param_is_missing("after"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(fixture(include_str!("../fixtures/notes_page_1.json"))),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/crm/v3/objects/notes"))
+        .and(query_param("after", "102"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(fixture(include_str!("../fixtures/notes_page_2.json"))),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let notes = client
+        .stream_objects::<Note>(CrmObject::Notes, &[], &[])
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .expect("Unable to stream notes");
+
+    let ids = notes
+        .iter()
+        .map(|note| note.id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["101", "102"]);
+}
+
+#[tokio::test]
+async fn test_get_object_raw() {
+    let server = MockServer::start().await;
+
+    // `archived` isn't a bool, so only the raw call can read this note.
+    Mock::given(method("GET"))
+        .and(path("/crm/v3/objects/notes/1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "1",
+            "archived": "unknown",
+            "properties": { "hs_note_body": "Hello" }
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+    assert!(client
+        .get_object::<Note>(CrmObject::Notes, "1", &[], &[], false)
+        .await
+        .is_err());
+
+    let note = client
+        .get_object_raw(CrmObject::Notes, "1", &[], &[], false)
+        .await
+        .expect("Unable to get note");
+    assert_eq!(note["properties"]["hs_note_body"], "Hello");
+}
+
+#[tokio::test]
+async fn test_archived_objects() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/crm/v3/objects/notes/1"))
+        .and(query_param("archived", "true"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "1",
+            "archived": true,
+            "archivedAt": "2023-06-01T00:00:00Z"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/crm/v3/objects/notes"))
+        .and(query_param("archived", "true"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": [{ "id": "1", "archived": true }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    // Archived objects are only returned when asked for.
+    Mock::given(method("GET"))
+        .and(path("/crm/v3/objects/notes"))
+        .and(query_param_is_missing("archived"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "results": []
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let note = client
+        .get_object::<Note>(CrmObject::Notes, "1", &[], &[], true)
+        .await
+        .expect("Unable to get note")
+        .expect("Missing note");
+    assert!(note.archived);
+    assert_eq!(note.archived_at.as_deref(), Some("2023-06-01T00:00:00Z"));
+
+    let archived = client
+        .list_objects::<Note>(CrmObject::Notes, &[], &[], true, None, None)
+        .await
+        .expect("Unable to list notes");
+    assert_eq!(archived.results.len(), 1);
+    assert!(archived.results[0].archived);
+
+    let active = client
+        .list_objects::<Note>(CrmObject::Notes, &[], &[], false, None, None)
+        .await
+        .expect("Unable to list notes");
+    assert!(active.results.is_empty());
+}
@@ -0,0 +1,40 @@
+use libhubspot::CrmObject;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_object_url() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/account-info/v3/details"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "portalId": 1234,
+            "timeZone": "US/Eastern",
+            "companyCurrency": "USD",
+            "uiDomain": "app.hubspot.com"
+        })))
+        // Account details should only be fetched once
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    assert_eq!(
+        client
+            .object_url(CrmObject::Contacts, "51")
+            .await
+            .expect("Unable to build url"),
+        "https://app.hubspot.com/contacts/1234/record/0-1/51"
+    );
+    assert_eq!(
+        client
+            .object_url(CrmObject::Notes, "52")
+            .await
+            .expect("Unable to build url"),
+        "https://app.hubspot.com/contacts/1234/record/0-46/52"
+    );
+}
@@ -0,0 +1,68 @@
+use libhubspot::types::{Contact, FilterGroup, Operator, SearchFilter, SearchSort, SortDirection};
+use libhubspot::CrmObject;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_search_objects() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/crm/v3/objects/contacts/search"))
+        .and(body_partial_json(serde_json::json!({
+            "filterGroups": [{
+                "filters": [
+                    { "propertyName": "email", "operator": "CONTAINS_TOKEN", "value": "*@example.com" },
+                    { "propertyName": "phone", "operator": "HAS_PROPERTY" }
+                ]
+            }],
+            "sorts": [{ "propertyName": "lastmodifieddate", "direction": "DESCENDING" }],
+            "query": "ferris",
+            "after": "20",
+            "limit": 200
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "total": 21,
+            "results": [{
+                "id": "51",
+                "properties": { "email": "ferris@example.com" },
+                "createdAt": "2024-02-05T08:00:00Z",
+                "updatedAt": "2024-02-05T08:00:00Z",
+                "archived": false
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let filters = FilterGroup {
+        filters: vec![
+            SearchFilter::new("email", Operator::ContainsToken, "*@example.com"),
+            SearchFilter::exists("phone", Operator::HasProperty),
+        ],
+    };
+    let sort = SearchSort {
+        property_name: "lastmodifieddate".into(),
+        direction: SortDirection::Descending,
+    };
+
+    let page = client
+        .search_objects::<Contact>(
+            CrmObject::Contacts,
+            &[filters],
+            &[sort],
+            Some("ferris"),
+            &["email".to_string()],
+            Some("20".into()),
+            500,
+        )
+        .await
+        .expect("Unable to search contacts");
+    assert!(page.paging.is_none());
+    assert_eq!(page.results.len(), 1);
+    assert_eq!(page.results[0].id, "51");
+}
@@ -1,8 +1,9 @@
 use chrono::{DateTime, Utc};
 use dotenv_codegen::dotenv;
 use libauth::helpers::load_credentials;
+use libauth::store::FileStore;
 use libmicrosoft::{
-    types::{AuthScopes, CreateTaskList, Task, TaskBody},
+    types::{AuthScopes, CreateTaskList, GraphQuery, Task, TaskBody},
     MicrosoftClient,
 };
 
@@ -26,7 +27,7 @@ async fn main() -> anyhow::Result<()> {
         AuthScopes::OfflineAccess.to_string(),
         AuthScopes::MailRead.to_string(),
     ];
-    load_credentials(&mut client, &scopes, false).await;
+    load_credentials(&mut client, &FileStore::default(), &scopes, false).await;
 
     let user = client.get_user().await?;
     println!(
@@ -40,13 +41,15 @@ async fn main() -> anyhow::Result<()> {
         serde_json::to_string_pretty(&default_task_list).unwrap()
     );
 
-    let task_lists = client.get_task_lists().await?;
+    let task_lists = client.get_task_lists(false).await?;
     println!(
         "Task Lists: {}",
         serde_json::to_string_pretty(&task_lists).unwrap()
     );
     for list in task_lists.value {
-        let tasks = client.get_tasks(list.id.as_str()).await?;
+        let tasks = client
+            .get_tasks(list.id.as_str(), &GraphQuery::default(), false)
+            .await?;
         println!("Tasks: {}", serde_json::to_string_pretty(&tasks).unwrap());
 
         let added = client
@@ -94,7 +97,7 @@ async fn main() -> anyhow::Result<()> {
         serde_json::to_string_pretty(&created_list).unwrap()
     );
 
-    let emails = client.get_new_emails(None).await?;
+    let emails = client.get_new_emails(None, &GraphQuery::default()).await?;
 
     println!(
         "Response Email {}",
@@ -122,11 +125,14 @@ async fn main() -> anyhow::Result<()> {
     }
 
     let emails = client
-        .get_new_emails(Some(
-            DateTime::parse_from_rfc3339("2024-02-13T10:00:00-08:00")
-                .unwrap()
-                .with_timezone(&Utc),
-        ))
+        .get_new_emails(
+            Some(
+                DateTime::parse_from_rfc3339("2024-02-13T10:00:00-08:00")
+                    .unwrap()
+                    .with_timezone(&Utc),
+            ),
+            &GraphQuery::default(),
+        )
         .await?;
 
     println!(
@@ -135,11 +141,14 @@ async fn main() -> anyhow::Result<()> {
     );
 
     let emails = client
-        .get_new_emails(Some(
-            DateTime::parse_from_rfc3339("2024-02-13T16:00:00-08:00")
-                .unwrap()
-                .with_timezone(&Utc),
-        ))
+        .get_new_emails(
+            Some(
+                DateTime::parse_from_rfc3339("2024-02-13T16:00:00-08:00")
+                    .unwrap()
+                    .with_timezone(&Utc),
+            ),
+            &GraphQuery::default(),
+        )
         .await?;
 
     // let emails = client.get_delta_email_page("https://graph.microsoft.com/v1.0/me/mailFolders('inbox')/messages/delta?$deltatoken=LztZwWjo5IivWBhyxw5rAOaF1aEpmIIXoTpgdnuLDugJJcwY-HKfZ3v_-5_2IYETBwWhHtMQ0h601TKBsp82L98T6l9U8bA4uixRm4jUqfY.g_m7pG94df2RVzL9ZiLwx2YlssVSx2U7V0pAvWrRq_8").await?;
@@ -1,16 +1,23 @@
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use async_trait::async_trait;
+use bytes::Bytes;
 use chrono::{DateTime, SecondsFormat, Utc};
 use libauth::{
-    auth_http_client, oauth_client, ApiClient, ApiError, AuthorizationRequest, AuthorizeOptions,
-    Credentials, OAuthParams,
+    call_with_headers, check_status, json_response, oauth_client, AccountInfo, ApiClient, ApiError,
+    AuthorizationRequest, AuthorizeOptions, ClientConfig, ClientSettings, Credentials, OAuthParams,
+    RetryPolicy, TokenError,
 };
 use oauth2::basic::{BasicClient, BasicTokenResponse};
 use oauth2::{AuthorizationCode, CsrfToken, PkceCodeVerifier, Scope, TokenResponse};
 
-use reqwest::Client;
+use libauth::helpers::html_to_text;
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::{Client, Method, StatusCode};
+use serde::de::DeserializeOwned;
 use serde_json::Value;
-use tokio::sync::watch;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::{watch, Mutex};
 use types::MessageCollection;
 
 pub mod types;
@@ -21,14 +28,20 @@ const TOKEN_URL: &str = "https://login.microsoftonline.com/common/oauth2/v2.0/to
 const API_ENDPOINT: &str = "https://graph.microsoft.com/v1.0";
 
 pub const DEFAULT_LIST_NAME: &str = "defaultList";
+/// Largest `$top` accepted by Graph for most resources.
+pub const MAX_PAGE_SIZE: u32 = 1000;
 
+#[derive(Clone)]
 pub struct MicrosoftClient {
     pub credentials: Credentials,
+    endpoint: String,
     http: Client,
     api_id: String,
     pub oauth: BasicClient,
     pub on_refresh_tx: watch::Sender<Credentials>,
     pub on_refresh_rx: watch::Receiver<Credentials>,
+    settings: ClientSettings,
+    refresh_lock: Arc<Mutex<()>>,
     pub username: Option<String>,
 }
 
@@ -53,6 +66,21 @@ impl ApiClient for MicrosoftClient {
         None
     }
 
+    async fn account_info(&mut self) -> Result<AccountInfo> {
+        let user = self.get_user().await?;
+        Ok(AccountInfo {
+            id: user.id.clone(),
+            display_name: user.display_name.clone(),
+            email: user
+                .mail
+                .clone()
+                .or_else(|| user.user_principal_name.clone()),
+            // Graph only serves profile photos as binary content.
+            avatar_url: None,
+            metadata: serde_json::to_value(&user)?,
+        })
+    }
+
     fn credentials(&self) -> Credentials {
         self.credentials.clone()
     }
@@ -63,7 +91,9 @@ impl ApiClient for MicrosoftClient {
 
     fn set_credentials(&mut self, credentials: &Credentials) -> Result<()> {
         self.credentials = credentials.clone();
-        self.http = auth_http_client(credentials.access_token.secret())?;
+        self.http = self
+            .settings
+            .auth_http_client(credentials.access_token.secret())?;
         Ok(())
     }
 
@@ -71,6 +101,18 @@ impl ApiClient for MicrosoftClient {
         self.on_refresh_rx.clone()
     }
 
+    fn refresh_lock(&self) -> Arc<Mutex<()>> {
+        self.refresh_lock.clone()
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        self.settings.retry_policy.clone()
+    }
+
+    fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.settings.retry_policy = policy;
+    }
+
     fn authorize(&self, scopes: &[String], _: &AuthorizeOptions) -> AuthorizationRequest {
         let scopes = scopes
             .iter()
@@ -96,7 +138,7 @@ impl ApiClient for MicrosoftClient {
         &self,
         code: &str,
         pkce_verifier: Option<String>,
-    ) -> Result<BasicTokenResponse> {
+    ) -> Result<BasicTokenResponse, TokenError> {
         let code = AuthorizationCode::new(code.to_owned());
 
         let mut exchange = self.oauth.exchange_code(code);
@@ -106,11 +148,11 @@ impl ApiClient for MicrosoftClient {
 
         match exchange.request_async(Self::http_client).await {
             Ok(val) => Ok(val),
-            Err(err) => Err(anyhow!(format!("Token Exchange Error {:?}", err))),
+            Err(err) => Err(err.into()),
         }
     }
 
-    async fn refresh_credentials(&mut self) -> Result<()> {
+    async fn refresh_credentials(&mut self) -> Result<(), TokenError> {
         if let Some(refresh_token) = &self.credentials.refresh_token {
             let new_token = self
                 .oauth
@@ -119,9 +161,13 @@ impl ApiClient for MicrosoftClient {
                 .await?;
 
             self.credentials.refresh_token(&new_token);
-            self.http = auth_http_client(new_token.access_token().secret())?;
+            self.http = self
+                .settings
+                .auth_http_client(new_token.access_token().secret())?;
             // Let any listeners know the credentials have been updated.
-            self.on_refresh_tx.send(self.credentials.clone())?;
+            self.on_refresh_tx
+                .send(self.credentials.clone())
+                .map_err(anyhow::Error::from)?;
         }
 
         Ok(())
@@ -135,6 +181,25 @@ impl MicrosoftClient {
         redirect_url: &str,
         api_id: &str,
         creds: Credentials,
+    ) -> anyhow::Result<Self> {
+        Self::new_with_config(
+            client_id,
+            client_secret,
+            redirect_url,
+            api_id,
+            creds,
+            ClientConfig::default(),
+        )
+    }
+
+    /// Same as `new`, with timeouts etc. applied to every request.
+    pub fn new_with_config(
+        client_id: &str,
+        client_secret: &str,
+        redirect_url: &str,
+        api_id: &str,
+        creds: Credentials,
+        config: ClientConfig,
     ) -> anyhow::Result<Self> {
         let params = OAuthParams {
             client_id: client_id.to_owned(),
@@ -145,21 +210,39 @@ impl MicrosoftClient {
             ..Default::default()
         };
 
+        let settings = ClientSettings::new(config);
         let (tx, rx) = watch::channel(creds.clone());
 
         Ok(MicrosoftClient {
             credentials: creds.clone(),
-            http: auth_http_client(creds.access_token.secret())?,
-            oauth: oauth_client(&params),
+            endpoint: API_ENDPOINT.to_string(),
+            http: settings.auth_http_client(creds.access_token.secret())?,
+            oauth: oauth_client(&params)?,
             on_refresh_tx: tx,
             on_refresh_rx: rx,
+            settings,
+            refresh_lock: Arc::new(Mutex::new(())),
             api_id: api_id.to_string(),
             username: None,
         })
     }
 
+    /// Override the base API URL, e.g. to target a mock server.
+    pub fn set_endpoint(&mut self, endpoint: &str) {
+        self.endpoint = endpoint.trim_end_matches('/').to_string();
+    }
+
+    /// Override the user agent sent with API requests.
+    pub fn set_user_agent(&mut self, user_agent: &str) -> Result<()> {
+        self.settings.user_agent = user_agent.to_string();
+        self.http = self
+            .settings
+            .auth_http_client(self.credentials.access_token.secret())?;
+        Ok(())
+    }
+
     pub async fn get_user(&mut self) -> Result<types::User, ApiError> {
-        let mut endpoint = API_ENDPOINT.to_string();
+        let mut endpoint = self.endpoint.to_string();
         endpoint.push_str("/me");
 
         let resp = self.call_json(&endpoint, &Vec::new()).await?;
@@ -172,16 +255,17 @@ impl MicrosoftClient {
         oauth2::reqwest::async_http_client(request).await
     }
 
-    pub async fn get_task_lists(&mut self) -> Result<types::TaskLists, ApiError> {
-        let mut endpoint = API_ENDPOINT.to_string();
+    /// Request the total number of lists with `with_count`, see `TaskLists::odata_count`.
+    pub async fn get_task_lists(&mut self, with_count: bool) -> Result<types::TaskLists, ApiError> {
+        let mut endpoint = self.endpoint.to_string();
         endpoint.push_str("/me/todo/lists");
 
-        let resp = self.call_json(&endpoint, &Vec::new()).await?;
+        let resp = self.call_json_with_count(&endpoint, with_count).await?;
         serde_json::from_value::<types::TaskLists>(resp).map_err(ApiError::SerdeError)
     }
 
     pub async fn get_default_task_list(&mut self) -> Result<Option<types::TaskListsDef>, ApiError> {
-        let mut endpoint = API_ENDPOINT.to_string();
+        let mut endpoint = self.endpoint.to_string();
         endpoint.push_str("/me/todo/lists");
 
         let resp = self.call_json(&endpoint, &Vec::new()).await?;
@@ -196,15 +280,87 @@ impl MicrosoftClient {
             })
     }
 
+    /// Retrieve the first page of tasks in the list, limited by `query`. Set
+    /// `with_count` to also get the total number of tasks in the list.
     pub async fn get_tasks(
         &mut self,
         task_list_id: &str,
+        query: &types::GraphQuery,
+        with_count: bool,
     ) -> Result<types::TaskListTasks, ApiError> {
-        let mut endpoint = API_ENDPOINT.to_string();
+        let resp = self.get_tasks_raw(task_list_id, query, with_count).await?;
+        serde_json::from_value::<types::TaskListTasks>(resp).map_err(ApiError::SerdeError)
+    }
+
+    pub async fn get_next_tasks_page(
+        &mut self,
+        tasks: &types::TaskListTasks,
+    ) -> Result<Option<types::TaskListTasks>, ApiError> {
+        if let Some(next) = &tasks.odata_next_link {
+            let resp = self.call_json(next, &[]).await?;
+            serde_json::from_value::<types::TaskListTasks>(resp)
+                .map_err(ApiError::SerdeError)
+                .map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Retrieve every task in the list, following `@odata.nextLink` until all
+    /// pages have been fetched. Stops early if Graph returns the same link twice.
+    pub async fn get_all_tasks(
+        &mut self,
+        task_list_id: &str,
+        page_size: Option<u32>,
+    ) -> Result<Vec<types::Task>, ApiError> {
+        let query = types::GraphQuery {
+            top: page_size,
+            ..Default::default()
+        };
+        let endpoint = self.tasks_endpoint(task_list_id, &query);
+        self.list_all(&endpoint).await
+    }
+
+    /// The page of tasks as Graph returns it, including `@odata.nextLink`.
+    /// `get_tasks` deserializes this.
+    pub async fn get_tasks_raw(
+        &mut self,
+        task_list_id: &str,
+        query: &types::GraphQuery,
+        with_count: bool,
+    ) -> Result<Value, ApiError> {
+        let endpoint = self.tasks_endpoint(task_list_id, query);
+        self.call_json_with_count(&endpoint, with_count).await
+    }
+
+    fn tasks_endpoint(&self, task_list_id: &str, query: &types::GraphQuery) -> String {
+        let mut endpoint = self.endpoint.to_string();
         endpoint.push_str(format!("/me/todo/lists/{}/tasks", task_list_id).as_str());
+        push_graph_query(&mut endpoint, query, Vec::new());
+        endpoint
+    }
 
-        let resp = self.call_json(&endpoint, &Vec::new()).await?;
-        serde_json::from_value::<types::TaskListTasks>(resp).map_err(ApiError::SerdeError)
+    /// Same as `call_json`, but asks Graph to include `@odata.count` in the
+    /// response when `with_count` is set. Counting requires the
+    /// `ConsistencyLevel: eventual` header.
+    async fn call_json_with_count(
+        &mut self,
+        endpoint: &str,
+        with_count: bool,
+    ) -> Result<Value, ApiError> {
+        if !with_count {
+            return self.call_json(endpoint, &[]).await;
+        }
+
+        let separator = if endpoint.contains('?') { '&' } else { '?' };
+        let endpoint = format!("{endpoint}{separator}$count=true");
+
+        let mut headers = HeaderMap::new();
+        headers.insert("ConsistencyLevel", HeaderValue::from_static("eventual"));
+        let resp = call_with_headers(self, &endpoint, &[], headers).await?;
+        json_response(resp)
+            .await
+            .map_err(|err| err.with_endpoint(Method::GET, &endpoint))
     }
 
     pub async fn add_task(
@@ -212,7 +368,7 @@ impl MicrosoftClient {
         task_list_id: &str,
         task: types::Task,
     ) -> Result<types::Task, ApiError> {
-        let mut endpoint = API_ENDPOINT.to_string();
+        let mut endpoint = self.endpoint.to_string();
         endpoint.push_str(format!("/me/todo/lists/{}/tasks", task_list_id).as_str());
 
         let resp = self
@@ -227,7 +383,7 @@ impl MicrosoftClient {
         task_id: &str,
         link: types::ResourceLink,
     ) -> Result<types::ResourceLink, ApiError> {
-        let mut endpoint = API_ENDPOINT.to_string();
+        let mut endpoint = self.endpoint.to_string();
         endpoint.push_str(
             format!(
                 "/me/todo/lists/{}/tasks/{}/linkedResources",
@@ -246,7 +402,7 @@ impl MicrosoftClient {
         &mut self,
         task_list: types::CreateTaskList,
     ) -> Result<types::TaskListsDef, ApiError> {
-        let mut endpoint = API_ENDPOINT.to_string();
+        let mut endpoint = self.endpoint.to_string();
         endpoint.push_str("/me/todo/lists");
 
         let resp = self
@@ -255,28 +411,61 @@ impl MicrosoftClient {
         serde_json::from_value::<types::TaskListsDef>(resp).map_err(ApiError::SerdeError)
     }
 
+    /// Start a delta sync of the inbox, optionally only including messages
+    /// received `after` a point in time. A larger `query.top` reduces the number
+    /// of round trips for busy mailboxes, and `query.select` the size of each page.
     pub async fn get_new_emails(
         &mut self,
         after: Option<DateTime<Utc>>,
+        query: &types::GraphQuery,
     ) -> Result<types::MessageCollection, ApiError> {
-        let mut endpoint = API_ENDPOINT.to_string();
+        let mut endpoint = self.endpoint.to_string();
 
         // The microsoft API requires the + in the syntax and not the url encoded %2B that
         // reqwest would put in if we added the query in the query array. This is why the
         // query string is added manually instead of the proper array style.
         endpoint.push_str("/me/mailfolders/inbox/messages/delta?$orderby=receivedDateTime+desc");
 
+        let mut filters = Vec::new();
         if let Some(after) = after {
-            endpoint.push_str(&format!(
-                "&$filter=receivedDateTime+gt+{}",
+            filters.push(format!(
+                "receivedDateTime+gt+{}",
                 after.to_rfc3339_opts(SecondsFormat::Millis, true)
             ));
         }
+        push_graph_query(&mut endpoint, query, filters);
 
         let resp = self.call_json(&endpoint, &[]).await?;
         serde_json::from_value::<types::MessageCollection>(resp).map_err(ApiError::SerdeError)
     }
 
+    /// Retrieve messages from a mail folder (e.g. "inbox"), newest first, that
+    /// match the filter. Set `with_count` to also get the total number of
+    /// matching messages.
+    pub async fn get_messages(
+        &mut self,
+        folder_id: &str,
+        filter: &types::MessageFilter,
+        with_count: bool,
+    ) -> Result<types::MessageCollection, ApiError> {
+        let mut endpoint = self.endpoint.to_string();
+
+        // See get_new_emails for why the query string is built manually.
+        endpoint.push_str(&format!(
+            "/me/mailfolders/{folder_id}/messages?$orderby=receivedDateTime+desc"
+        ));
+
+        if let Some(filter) = filter.to_odata() {
+            // Graph requires properties used in $orderby to come first in $filter.
+            endpoint.push_str(&format!(
+                "&$filter=receivedDateTime+ge+1900-01-01T00:00:00Z+and+{filter}"
+            ));
+        }
+
+        let resp = self.call_json_with_count(&endpoint, with_count).await?;
+        serde_json::from_value::<types::MessageCollection>(resp).map_err(ApiError::SerdeError)
+    }
+
     pub async fn get_next_email_page(
         &mut self,
         msg: &MessageCollection,
@@ -291,11 +480,284 @@ impl MicrosoftClient {
         }
     }
 
+    /// Retrieve a page of a delta sync from a `@odata.nextLink` or `@odata.deltaLink`.
+    /// Returns `ApiError::SyncTokenExpired` if Graph no longer knows the delta
+    /// token (`syncStateNotFound`), a new sync has to be started.
     pub async fn get_delta_email_page(
         &mut self,
         delta_url: &str,
     ) -> Result<types::MessageCollection, ApiError> {
-        let resp = self.call_json(delta_url, &[]).await?;
-        serde_json::from_value::<types::MessageCollection>(resp).map_err(ApiError::SerdeError)
+        match self.call_json(delta_url, &[]).await {
+            Ok(resp) => serde_json::from_value::<types::MessageCollection>(resp)
+                .map_err(ApiError::SerdeError),
+            Err(err) if err.status() == Some(StatusCode::GONE) => Err(ApiError::SyncTokenExpired),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Resume an inbox delta sync from a token returned by `MessageCollection::delta_token`.
+    /// Returns `ApiError::SyncTokenExpired` if the token has expired.
+    pub async fn get_emails_since_delta(
+        &mut self,
+        token: &str,
+    ) -> Result<types::MessageCollection, ApiError> {
+        let mut endpoint = reqwest::Url::parse(&format!(
+            "{}/me/mailfolders/inbox/messages/delta",
+            self.endpoint
+        ))
+        .map_err(|err| ApiError::Other(err.into()))?;
+        // The token is decoded by `delta_token`, so it has to be encoded again.
+        endpoint.query_pairs_mut().append_pair("$deltatoken", token);
+
+        self.get_delta_email_page(endpoint.as_str()).await
+    }
+
+    /// Retrieve a page of the events between `start` and `end`, ordered by
+    /// start time, from `calendar_id` or the user's default calendar if `None`.
+    /// Unlike listing events, recurring events are expanded into their occurrences.
+    /// Pass the `@odata.nextLink` of a previous page as `next_page` to continue.
+    pub async fn get_calendar_view(
+        &mut self,
+        calendar_id: Option<&str>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        next_page: Option<String>,
+    ) -> Result<types::EventCollection, ApiError> {
+        // The next link already includes the query
+        let endpoint = match next_page {
+            Some(next_page) => next_page,
+            None => self.calendar_view_endpoint(calendar_id, start, end)?,
+        };
+
+        let resp = self.call_json(&endpoint, &[]).await?;
+        serde_json::from_value::<types::EventCollection>(resp).map_err(ApiError::SerdeError)
+    }
+
+    fn calendar_view_endpoint(
+        &self,
+        calendar_id: Option<&str>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<String, ApiError> {
+        let path = match calendar_id {
+            Some(calendar_id) => format!("/me/calendars/{calendar_id}/calendarView"),
+            None => "/me/calendarView".to_string(),
+        };
+
+        let mut endpoint = reqwest::Url::parse(&format!("{}{path}", self.endpoint))
+            .map_err(|err| ApiError::Other(err.into()))?;
+        endpoint
+            .query_pairs_mut()
+            .append_pair(
+                "startDateTime",
+                &start.to_rfc3339_opts(SecondsFormat::Secs, true),
+            )
+            .append_pair(
+                "endDateTime",
+                &end.to_rfc3339_opts(SecondsFormat::Secs, true),
+            )
+            .append_pair("$orderby", "start/dateTime");
+        Ok(endpoint.to_string())
+    }
+
+    /// List the calendars the user has access to.
+    pub async fn get_calendars(&mut self) -> Result<Vec<types::OutlookCalendar>, ApiError> {
+        self.list_all(&self.path_endpoint("/me/calendars")).await
+    }
+
+    /// List all the events in a calendar between `start` and `end`, ordered by
+    /// start time, following `@odata.nextLink` until all pages have been fetched.
+    /// Recurring events are expanded into their occurrences.
+    pub async fn list_events(
+        &mut self,
+        calendar_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<types::OutlookEvent>, ApiError> {
+        let endpoint = self.calendar_view_endpoint(Some(calendar_id), start, end)?;
+        self.list_all(&endpoint).await
+    }
+
+    pub async fn get_event(
+        &mut self,
+        calendar_id: &str,
+        event_id: &str,
+    ) -> Result<types::OutlookEvent, ApiError> {
+        let mut endpoint = self.endpoint.to_string();
+        endpoint.push_str(&format!("/me/calendars/{calendar_id}/events/{event_id}"));
+
+        let resp = self.call_json(&endpoint, &[]).await?;
+        serde_json::from_value::<types::OutlookEvent>(resp).map_err(ApiError::SerdeError)
+    }
+
+    /// RSVP to a meeting invite. The organizer is notified of the response,
+    /// along with the optional `comment`.
+    pub async fn respond_to_event(
+        &mut self,
+        event_id: &str,
+        response: types::EventResponse,
+        comment: Option<String>,
+    ) -> Result<(), ApiError> {
+        let mut endpoint = self.endpoint.to_string();
+        endpoint.push_str(&format!("/me/events/{event_id}/{response}"));
+
+        let mut body = serde_json::json!({ "sendResponse": true });
+        if let Some(comment) = comment {
+            body["comment"] = Value::String(comment);
+        }
+
+        // Graph replies with an empty 202, so there's no JSON to parse.
+        let client = self.get_check_client().await?;
+        let resp = client
+            .post(&endpoint)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|err| ApiError::from(err).with_endpoint(Method::POST, &endpoint))?;
+        check_status(resp)
+            .await
+            .map_err(|err| err.with_endpoint(Method::POST, &endpoint))?;
+        Ok(())
+    }
+
+    /// List the items in a OneDrive folder, defaulting to the root folder.
+    pub async fn list_drive_items(
+        &mut self,
+        folder_id: Option<&str>,
+    ) -> Result<Vec<types::DriveItem>, ApiError> {
+        let path = match folder_id {
+            Some(folder_id) => format!("/me/drive/items/{folder_id}/children"),
+            None => "/me/drive/root/children".to_string(),
+        };
+
+        self.list_all(&self.path_endpoint(&path)).await
+    }
+
+    /// Download the contents of a OneDrive file.
+    pub async fn download_drive_item(&mut self, item_id: &str) -> Result<Bytes, ApiError> {
+        let mut endpoint = self.endpoint.to_string();
+        endpoint.push_str(&format!("/me/drive/items/{item_id}"));
+
+        let resp = self.call_json(&endpoint, &[]).await?;
+        let item =
+            serde_json::from_value::<types::DriveItem>(resp).map_err(ApiError::SerdeError)?;
+        let Some(download_url) = item.download_url else {
+            return Err(ApiError::BadRequest(format!(
+                "{} has no content to download",
+                item.name
+            )));
+        };
+
+        // The download url is pre-authenticated & rejects bearer tokens.
+        let client = self
+            .settings
+            .client_builder()
+            .map_err(ApiError::Other)?
+            .build()?;
+        let resp = client
+            .get(&download_url)
+            .send()
+            .await
+            .map_err(|err| ApiError::from(err).with_endpoint(Method::GET, &download_url))?;
+        let resp = check_status(resp)
+            .await
+            .map_err(|err| err.with_endpoint(Method::GET, &download_url))?;
+        Ok(resp.bytes().await?)
+    }
+
+    /// Collect every item of a paged Graph listing starting at `endpoint`,
+    /// following `@odata.nextLink`. Stops early if Graph returns the same link twice.
+    async fn list_all<T>(&mut self, endpoint: &str) -> Result<Vec<T>, ApiError>
+    where
+        T: DeserializeOwned + Default,
+    {
+        let mut items = Vec::new();
+        let mut seen_links = HashSet::new();
+        // The next link already includes the query
+        let mut page = self.call_json(endpoint, &[]).await?;
+        loop {
+            let collection = serde_json::from_value::<types::Collection<T>>(page)
+                .map_err(ApiError::SerdeError)?;
+            items.extend(collection.value);
+            match collection.odata_next_link {
+                Some(link) if !seen_links.insert(link.clone()) => {
+                    log::warn!("{endpoint} returned next link {link} twice, stopping");
+                    break;
+                }
+                Some(link) => page = self.call_json(&link, &[]).await?,
+                None => break,
+            }
+        }
+
+        Ok(items)
+    }
+
+    fn path_endpoint(&self, path: &str) -> String {
+        format!("{}{path}", self.endpoint)
+    }
+
+    pub async fn list_notebooks(&mut self) -> Result<Vec<types::OneNoteNotebook>, ApiError> {
+        self.list_all(&self.path_endpoint("/me/onenote/notebooks"))
+            .await
+    }
+
+    pub async fn list_sections(
+        &mut self,
+        notebook_id: &str,
+    ) -> Result<Vec<types::OneNoteSection>, ApiError> {
+        let path = format!("/me/onenote/notebooks/{notebook_id}/sections");
+        self.list_all(&self.path_endpoint(&path)).await
+    }
+
+    pub async fn list_pages(
+        &mut self,
+        section_id: &str,
+    ) -> Result<Vec<types::OneNotePage>, ApiError> {
+        let path = format!("/me/onenote/sections/{section_id}/pages");
+        self.list_all(&self.path_endpoint(&path)).await
+    }
+
+    /// Fetch the HTML content of a OneNote page.
+    pub async fn get_page_content(&mut self, page_id: &str) -> Result<String, ApiError> {
+        let mut endpoint = self.endpoint.to_string();
+        endpoint.push_str(&format!("/me/onenote/pages/{page_id}/content"));
+
+        let resp = self.call(&endpoint, &Vec::new()).await?;
+        let resp = check_status(resp)
+            .await
+            .map_err(|err| err.with_endpoint(Method::GET, &endpoint))?;
+        Ok(resp.text().await?)
+    }
+
+    /// Same as `get_page_content` but with the HTML converted to plain text.
+    pub async fn get_page_text(&mut self, page_id: &str) -> Result<String, ApiError> {
+        let html = self.get_page_content(page_id).await?;
+        Ok(html_to_text(&html))
+    }
+}
+
+/// Append the `$select`, `$top` & `$filter` options of `query` to `endpoint`.
+/// `filters` are combined with `query.filter`. Like the rest of the OData params,
+/// spaces are sent as "+" rather than being url encoded.
+fn push_graph_query(endpoint: &mut String, query: &types::GraphQuery, mut filters: Vec<String>) {
+    let mut params = Vec::new();
+    if !query.select.is_empty() {
+        params.push(format!("$select={}", query.select.join(",")));
+    }
+
+    if let Some(top) = query.top {
+        params.push(format!("$top={}", top.clamp(1, MAX_PAGE_SIZE)));
+    }
+
+    if let Some(filter) = &query.filter {
+        filters.push(filter.replace(' ', "+"));
+    }
+    if !filters.is_empty() {
+        params.push(format!("$filter={}", filters.join("+and+")));
+    }
+
+    for param in params {
+        endpoint.push(if endpoint.contains('?') { '&' } else { '?' });
+        endpoint.push_str(&param);
     }
 }
@@ -1,3 +1,6 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use libauth::helpers::html_to_text;
+use libauth::Document;
 use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumString};
 
@@ -16,11 +19,25 @@ pub enum AuthScopes {
     /// Grant read access to outlook emails.
     #[strum(serialize = "Mail.Read")]
     MailRead,
+    /// Grant read access to OneNote notebooks.
+    #[strum(serialize = "Notes.Read")]
+    NotesRead,
     /// Grants access when user is offline (refresh token given)
     #[strum(serialize = "offline_access")]
     OfflineAccess,
 }
 
+impl AuthScopes {
+    /// Read-only scopes for the user's profile and email, including a refresh token.
+    pub fn default_read_scopes() -> Vec<String> {
+        vec![
+            AuthScopes::UserRead.to_string(),
+            AuthScopes::MailRead.to_string(),
+            AuthScopes::OfflineAccess.to_string(),
+        ]
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct User {
@@ -44,6 +61,9 @@ pub struct User {
 pub struct TaskLists {
     #[serde(rename = "@odata.context")]
     pub odata_context: String,
+    /// Total number of items, only set when the count was requested.
+    #[serde(rename = "@odata.count")]
+    pub odata_count: Option<u64>,
     pub value: Vec<TaskListsDef>,
 }
 
@@ -93,7 +113,12 @@ pub struct CreateTaskList {
 pub struct TaskListTasks {
     #[serde(rename = "@odata.context")]
     pub odata_context: String,
+    /// Total number of items, only set when the count was requested.
+    #[serde(rename = "@odata.count")]
+    pub odata_count: Option<u64>,
     pub value: Vec<Task>,
+    #[serde(rename = "@odata.nextLink")]
+    pub odata_next_link: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
@@ -155,7 +180,7 @@ pub enum TaskStatus {
     Deferred,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TaskDateTime {
     // example:  "dateTime": "2024-02-05T08:00:00.0000000",
@@ -164,6 +189,23 @@ pub struct TaskDateTime {
     pub time_zone: String,
 }
 
+impl TaskDateTime {
+    /// Parse the date time, if it's in UTC. Graph returns UTC unless a
+    /// different time zone is requested with the `Prefer` header.
+    pub fn to_utc(&self) -> Option<DateTime<Utc>> {
+        if self.time_zone != "UTC" {
+            return None;
+        }
+
+        NaiveDateTime::parse_from_str(&self.date_time, "%Y-%m-%dT%H:%M:%S%.f")
+            .ok()
+            .map(|dt| DateTime::from_utc(dt, Utc))
+    }
+}
+
+/// Graph's `dateTimeTimeZone` resource, shared by tasks and calendar events.
+pub type OutlookDateTime = TaskDateTime;
+
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TaskBody {
@@ -248,35 +290,36 @@ enum RecurrencePatternType {
     RelativeYearly,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
 pub struct MessageAddress {
     pub email_address: EmailAddress,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
 pub struct EmailAddress {
     pub name: String,
     pub address: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
 pub struct Flag {
     pub flag_status: FlagStatus,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum FlagStatus {
+    #[default]
     NotFlagged,
     Flagged,
     Complete,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
 pub struct Body {
     pub content_type: String,
     pub content: String,
@@ -288,8 +331,9 @@ pub struct MessageRemovedReason {
     pub reason: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
+/// Fields not requested with `$select` are left empty.
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(default, rename_all = "camelCase")]
 pub struct GenericMessage {
     #[serde(rename = "@odata.etag")]
     pub odata_etag: String,
@@ -323,10 +367,42 @@ pub struct GenericMessage {
     pub flag: Flag,
 }
 
+impl Document for GenericMessage {
+    fn id(&self) -> String {
+        self.internet_message_id.clone()
+    }
+
+    fn title(&self) -> String {
+        self.subject.clone()
+    }
+
+    /// The full body with any HTML stripped, falling back to the preview if
+    /// the body wasn't returned.
+    fn body_text(&self) -> String {
+        if self.body.content.is_empty() {
+            self.body_preview.clone()
+        } else if self.body.content_type.eq_ignore_ascii_case("html") {
+            html_to_text(&self.body.content)
+        } else {
+            self.body.content.clone()
+        }
+    }
+
+    fn url(&self) -> Option<String> {
+        Some(self.web_link.clone())
+    }
+
+    fn updated_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.last_modified_date_time)
+            .ok()
+            .map(|date| date.with_timezone(&Utc))
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Message {
-    #[serde(rename = "@odata.type")]
+    #[serde(rename = "@odata.type", default)]
     pub odata_type: String,
     #[serde(rename = "@removed")]
     pub removed: Option<MessageRemovedReason>,
@@ -335,14 +411,270 @@ pub struct Message {
     pub message: Option<GenericMessage>,
 }
 
+impl Message {
+    pub fn is_read(&self) -> bool {
+        self.message.as_ref().is_some_and(|msg| msg.is_read)
+    }
+
+    pub fn is_flagged(&self) -> bool {
+        self.message
+            .as_ref()
+            .is_some_and(|msg| matches!(msg.flag.flag_status, FlagStatus::Flagged))
+    }
+
+    pub fn importance(&self) -> Option<String> {
+        self.message.as_ref().map(|msg| msg.importance.clone())
+    }
+}
+
+#[derive(Debug, Display, Clone)]
+pub enum Importance {
+    #[strum(serialize = "low")]
+    Low,
+    #[strum(serialize = "normal")]
+    Normal,
+    #[strum(serialize = "high")]
+    High,
+}
+
+/// OData query options for Graph list calls, used to limit the size of the
+/// response.
+#[derive(Debug, Default, Clone)]
+pub struct GraphQuery {
+    /// Properties to return, e.g. "subject". Everything is returned if empty.
+    pub select: Vec<String>,
+    /// Page size, clamped to `MAX_PAGE_SIZE`. Graph's default is used if `None`.
+    pub top: Option<u32>,
+    /// OData `$filter` expression, e.g. "isRead eq false".
+    pub filter: Option<String>,
+}
+
+/// Filters for `MicrosoftClient::get_messages`, all conditions are combined.
+#[derive(Debug, Default, Clone)]
+pub struct MessageFilter {
+    pub unread_only: bool,
+    pub flagged_only: bool,
+    pub importance: Option<Importance>,
+}
+
+impl MessageFilter {
+    /// OData `$filter` expression for this filter, using "+" for spaces.
+    pub fn to_odata(&self) -> Option<String> {
+        let mut conditions = Vec::new();
+        if self.unread_only {
+            conditions.push("isRead+eq+false".to_string());
+        }
+
+        if self.flagged_only {
+            conditions.push("flag/flagStatus+eq+'flagged'".to_string());
+        }
+
+        if let Some(importance) = &self.importance {
+            conditions.push(format!("importance+eq+'{importance}'"));
+        }
+
+        if conditions.is_empty() {
+            None
+        } else {
+            Some(conditions.join("+and+"))
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct MessageCollection {
     #[serde(rename = "@odata.context")]
     pub odata_context: Option<String>,
+    /// Total number of items, only set when the count was requested.
+    #[serde(rename = "@odata.count")]
+    pub odata_count: Option<u64>,
     pub value: Vec<Message>,
     #[serde(rename = "@odata.deltaLink")]
     pub odata_delta_link: Option<String>,
     #[serde(rename = "@odata.nextLink")]
     pub odata_next_link: Option<String>,
 }
+
+impl MessageCollection {
+    /// The `$deltatoken` of the delta link, only set on the last page of a delta
+    /// sync. Store it & pass it to `get_emails_since_delta` to resume the sync.
+    pub fn delta_token(&self) -> Option<String> {
+        let link = reqwest::Url::parse(self.odata_delta_link.as_deref()?).ok()?;
+        link.query_pairs()
+            .find(|(key, _)| key == "$deltatoken")
+            .map(|(_, token)| token.into_owned())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Location {
+    pub display_name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OnlineMeetingInfo {
+    pub join_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ResponseStatus {
+    /// One of none, organizer, tentativelyAccepted, accepted, declined, notResponded.
+    pub response: String,
+    pub time: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Attendee {
+    pub email_address: EmailAddress,
+    /// One of required, optional, resource.
+    #[serde(rename = "type")]
+    pub attendee_type: String,
+    #[serde(default)]
+    pub status: ResponseStatus,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Event {
+    #[serde(rename = "@odata.etag")]
+    pub odata_etag: Option<String>,
+    pub id: String,
+    pub subject: Option<String>,
+    pub body_preview: Option<String>,
+    pub body: Option<Body>,
+    pub start: OutlookDateTime,
+    pub end: OutlookDateTime,
+    pub is_all_day: bool,
+    pub is_cancelled: bool,
+    pub location: Option<Location>,
+    pub organizer: Option<MessageAddress>,
+    pub attendees: Vec<Attendee>,
+    pub web_link: Option<String>,
+    pub online_meeting: Option<OnlineMeetingInfo>,
+    /// One of singleInstance, occurrence, exception, seriesMaster.
+    #[serde(rename = "type")]
+    pub event_type: Option<String>,
+    /// For occurrences of a recurring event, the id of the series master.
+    pub series_master_id: Option<String>,
+    pub recurrence: Option<TaskPatternedRecurrence>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EventCollection {
+    #[serde(rename = "@odata.context")]
+    pub odata_context: Option<String>,
+    pub value: Vec<Event>,
+    #[serde(rename = "@odata.nextLink")]
+    pub odata_next_link: Option<String>,
+}
+
+/// Calendar event, as returned by `MicrosoftClient::list_events`.
+pub type OutlookEvent = Event;
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OutlookCalendar {
+    pub id: String,
+    pub name: String,
+    /// Hex color, e.g. "#E07A00". Empty when the default theme color is used.
+    pub hex_color: String,
+    pub is_default_calendar: bool,
+    pub can_edit: bool,
+    pub owner: Option<EmailAddress>,
+}
+
+/// Reply to a meeting invite, used with `MicrosoftClient::respond_to_event`.
+#[derive(Debug, Display, Clone)]
+pub enum EventResponse {
+    #[strum(serialize = "accept")]
+    Accept,
+    #[strum(serialize = "decline")]
+    Decline,
+    #[strum(serialize = "tentativelyAccept")]
+    TentativelyAccept,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OneNoteNotebook {
+    pub id: String,
+    pub display_name: String,
+    pub created_date_time: Option<DateTime<Utc>>,
+    pub last_modified_date_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OneNoteSection {
+    pub id: String,
+    pub display_name: String,
+    pub created_date_time: Option<DateTime<Utc>>,
+    pub last_modified_date_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OneNotePage {
+    pub id: String,
+    pub title: String,
+    pub created_date_time: Option<DateTime<Utc>>,
+    pub last_modified_date_time: Option<DateTime<Utc>>,
+    /// Endpoint for the HTML content of the page.
+    pub content_url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FileFacet {
+    pub mime_type: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FolderFacet {
+    pub child_count: u64,
+}
+
+/// A OneDrive file or folder.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DriveItem {
+    pub id: String,
+    pub name: String,
+    /// Size in bytes, for folders the total size of its contents.
+    pub size: u64,
+    pub web_url: String,
+    pub created_date_time: Option<DateTime<Utc>>,
+    pub last_modified_date_time: Option<DateTime<Utc>>,
+    /// Only set for files.
+    pub file: Option<FileFacet>,
+    /// Only set for folders.
+    pub folder: Option<FolderFacet>,
+    /// Short-lived, pre-authenticated url for the file contents.
+    #[serde(rename = "@microsoft.graph.downloadUrl")]
+    pub download_url: Option<String>,
+}
+
+impl DriveItem {
+    pub fn is_folder(&self) -> bool {
+        self.folder.is_some()
+    }
+}
+
+/// A page of items from a Graph listing, e.g. OneNote notebooks or calendars.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct Collection<T> {
+    pub value: Vec<T>,
+    #[serde(rename = "@odata.nextLink")]
+    pub odata_next_link: Option<String>,
+}
+
+/// A page of OneNote notebooks, sections or pages.
+pub type OneNoteCollection<T> = Collection<T>;
@@ -0,0 +1,62 @@
+use libauth::ApiClient;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+fn user(mail: Option<&str>) -> serde_json::Value {
+    serde_json::json!({
+        "@odata.context": "https://graph.microsoft.com/v1.0/$metadata#users/$entity",
+        "businessPhones": [],
+        "displayName": "Adele Vance",
+        "mail": mail,
+        "userPrincipalName": "adelev@contoso.onmicrosoft.com",
+        "id": "87d349ed-44d7-43e1-9a83-5f2406dee5bd"
+    })
+}
+
+#[tokio::test]
+async fn test_account_info() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(user(Some("adelev@contoso.com"))))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let info = common::client(&server)
+        .account_info()
+        .await
+        .expect("Unable to get account info");
+    assert_eq!(info.id, "87d349ed-44d7-43e1-9a83-5f2406dee5bd");
+    assert_eq!(info.display_name, "Adele Vance");
+    assert_eq!(info.email.as_deref(), Some("adelev@contoso.com"));
+    assert_eq!(info.avatar_url, None);
+    assert_eq!(
+        info.metadata["userPrincipalName"],
+        "adelev@contoso.onmicrosoft.com"
+    );
+}
+
+#[tokio::test]
+async fn test_account_info_without_mail() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(user(None)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let info = common::client(&server)
+        .account_info()
+        .await
+        .expect("Unable to get account info");
+    assert_eq!(
+        info.email.as_deref(),
+        Some("adelev@contoso.onmicrosoft.com")
+    );
+}
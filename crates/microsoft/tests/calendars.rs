@@ -0,0 +1,186 @@
+use chrono::{TimeZone, Utc};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+fn event(id: &str, start: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "subject": "Standup",
+        "start": { "dateTime": start, "timeZone": "UTC" },
+        "end": { "dateTime": "2024-02-05T09:30:00.0000000", "timeZone": "UTC" },
+        "isAllDay": false,
+        "attendees": []
+    })
+}
+
+#[tokio::test]
+async fn test_get_calendars() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/calendars"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{
+                "id": "calendar-id",
+                "name": "Calendar",
+                "hexColor": "",
+                "isDefaultCalendar": true,
+                "canEdit": true,
+                "owner": { "name": "Ferris", "address": "ferris@example.com" }
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let calendars = common::client(&server)
+        .get_calendars()
+        .await
+        .expect("Unable to get calendars");
+    assert_eq!(calendars.len(), 1);
+    assert!(calendars[0].is_default_calendar);
+    assert_eq!(
+        calendars[0].owner.as_ref().map(|o| o.address.as_str()),
+        Some("ferris@example.com")
+    );
+}
+
+#[tokio::test]
+async fn test_get_calendars_repeated_next_link() {
+    let server = MockServer::start().await;
+
+    // Every page links to the second page, which would otherwise loop forever.
+    let next = format!("{}/me/calendars?$skip=1", server.uri());
+    Mock::given(method("GET"))
+        .and(path("/me/calendars"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{ "id": "calendar-id", "name": "Calendar" }],
+            "@odata.nextLink": next
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let calendars = common::client(&server)
+        .get_calendars()
+        .await
+        .expect("Unable to get calendars");
+    assert_eq!(calendars.len(), 2);
+}
+
+#[tokio::test]
+async fn test_list_events() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/calendars/calendar-id/calendarView"))
+        .and(query_param("startDateTime", "2024-02-05T00:00:00Z"))
+        .and(query_param("endDateTime", "2024-02-06T00:00:00Z"))
+        .and(query_param("$orderby", "start/dateTime"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [event("first", "2024-02-05T09:00:00.0000000")],
+            "@odata.nextLink": format!("{}/me/calendars/calendar-id/calendarView?$skip=1", server.uri())
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/calendars/calendar-id/calendarView"))
+        .and(query_param("$skip", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [event("second", "2024-02-05T10:00:00.0000000")]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let events = common::client(&server)
+        .list_events(
+            "calendar-id",
+            Utc.with_ymd_and_hms(2024, 2, 5, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 2, 6, 0, 0, 0).unwrap(),
+        )
+        .await
+        .expect("Unable to list events");
+    let ids = events.iter().map(|e| e.id.as_str()).collect::<Vec<_>>();
+    assert_eq!(ids, vec!["first", "second"]);
+    assert_eq!(
+        events[0].start.to_utc(),
+        Some(Utc.with_ymd_and_hms(2024, 2, 5, 9, 0, 0).unwrap())
+    );
+}
+
+#[tokio::test]
+async fn test_get_event() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/calendars/calendar-id/events/event-id"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(event("event-id", "2024-02-05T09:00:00.0000000")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let event = common::client(&server)
+        .get_event("calendar-id", "event-id")
+        .await
+        .expect("Unable to get event");
+    assert_eq!(event.subject.as_deref(), Some("Standup"));
+}
+
+#[tokio::test]
+async fn test_get_calendar_view() {
+    let server = MockServer::start().await;
+    let next_link = format!("{}/me/calendarView?$skip=1", server.uri());
+
+    let mut occurrence = event("occurrence", "2024-02-05T09:00:00.0000000");
+    occurrence["type"] = "occurrence".into();
+    occurrence["seriesMasterId"] = "series".into();
+
+    Mock::given(method("GET"))
+        .and(path("/me/calendarView"))
+        .and(query_param("startDateTime", "2024-02-05T00:00:00Z"))
+        .and(query_param("endDateTime", "2024-02-12T00:00:00Z"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [occurrence],
+            "@odata.nextLink": next_link
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/calendarView"))
+        .and(query_param("$skip", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [event("single", "2024-02-06T09:00:00.0000000")]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+    let start = Utc.with_ymd_and_hms(2024, 2, 5, 0, 0, 0).unwrap();
+    let end = Utc.with_ymd_and_hms(2024, 2, 12, 0, 0, 0).unwrap();
+
+    let first = client
+        .get_calendar_view(None, start, end, None)
+        .await
+        .expect("Unable to get calendar view");
+    assert_eq!(first.value[0].event_type.as_deref(), Some("occurrence"));
+    assert_eq!(first.value[0].series_master_id.as_deref(), Some("series"));
+    assert_eq!(first.odata_next_link, Some(next_link));
+
+    let second = client
+        .get_calendar_view(None, start, end, first.odata_next_link)
+        .await
+        .expect("Unable to get calendar view");
+    assert_eq!(second.value[0].id, "single");
+    assert_eq!(second.odata_next_link, None);
+}
@@ -0,0 +1,5 @@
+#![allow(dead_code)]
+
+use libmicrosoft::MicrosoftClient;
+
+libauth::mock_client_factory!(MicrosoftClient, "graph.microsoft.com");
@@ -0,0 +1,90 @@
+use libauth::{ApiClient, RetryPolicy};
+use libmicrosoft::types::MessageFilter;
+use std::time::Duration;
+use wiremock::matchers::{header, method, path, query_param, query_param_is_missing};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_get_messages_with_count() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/mailfolders/inbox/messages"))
+        .and(query_param("$count", "true"))
+        .and(header("ConsistencyLevel", "eventual"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "@odata.count": 120,
+            "value": []
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let messages = common::client(&server)
+        .get_messages("inbox", &MessageFilter::default(), true)
+        .await
+        .expect("Unable to get messages");
+    assert_eq!(messages.odata_count, Some(120));
+}
+
+#[tokio::test]
+async fn test_get_task_lists_without_count() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/todo/lists"))
+        .and(query_param_is_missing("$count"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "@odata.context": "https://graph.microsoft.com/v1.0/$metadata#lists",
+            "value": []
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let lists = common::client(&server)
+        .get_task_lists(false)
+        .await
+        .expect("Unable to get task lists");
+    assert_eq!(lists.odata_count, None);
+}
+
+#[tokio::test]
+async fn test_get_messages_with_count_retries() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/mailfolders/inbox/messages"))
+        .and(header("ConsistencyLevel", "eventual"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/mailfolders/inbox/messages"))
+        .and(header("ConsistencyLevel", "eventual"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "@odata.count": 3,
+            "value": []
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+    client.set_retry_policy(RetryPolicy {
+        max_retries: 1,
+        base_delay: Duration::from_millis(1),
+        ..Default::default()
+    });
+
+    let messages = client
+        .get_messages("inbox", &MessageFilter::default(), true)
+        .await
+        .expect("Unable to get messages");
+    assert_eq!(messages.odata_count, Some(3));
+}
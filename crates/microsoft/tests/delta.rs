@@ -0,0 +1,95 @@
+use libauth::ApiError;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_resume_from_delta_token() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/mailfolders/inbox/messages/delta"))
+        .and(query_param("$orderby", "receivedDateTime desc"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "@odata.context": "https://graph.microsoft.com/v1.0/$metadata#Collection(message)",
+            "value": [],
+            "@odata.deltaLink": "https://graph.microsoft.com/v1.0/me/mailFolders('inbox')/messages/delta?$deltatoken=LztZwWjo5Iiv.g_m7pG94"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/mailfolders/inbox/messages/delta"))
+        .and(query_param("$deltatoken", "LztZwWjo5Iiv.g_m7pG94"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [],
+            "@odata.deltaLink": "https://graph.microsoft.com/v1.0/me/mailFolders('inbox')/messages/delta?$deltatoken=next-token"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let emails = client
+        .get_new_emails(None, &Default::default())
+        .await
+        .expect("Unable to get emails");
+    let token = emails.delta_token().expect("No delta token");
+    assert_eq!(token, "LztZwWjo5Iiv.g_m7pG94");
+
+    let emails = client
+        .get_emails_since_delta(&token)
+        .await
+        .expect("Unable to resume delta");
+    assert_eq!(emails.delta_token().as_deref(), Some("next-token"));
+    assert_eq!(emails.odata_next_link, None);
+}
+
+#[tokio::test]
+async fn test_expired_delta_token() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/mailfolders/inbox/messages/delta"))
+        .and(query_param("$deltatoken", "stale-token"))
+        .respond_with(ResponseTemplate::new(410).set_body_json(serde_json::json!({
+            "error": {
+                "code": "syncStateNotFound",
+                "message": "The sync state could not be found."
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+    let err = client
+        .get_emails_since_delta("stale-token")
+        .await
+        .expect_err("Expected an expired token");
+    assert!(matches!(err, ApiError::SyncTokenExpired));
+}
+
+#[tokio::test]
+async fn test_delta_token_is_encoded() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/mailfolders/inbox/messages/delta"))
+        .and(query_param("$deltatoken", "a+b/c=&d"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": []
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+    client
+        .get_emails_since_delta("a+b/c=&d")
+        .await
+        .expect("Unable to resume delta");
+}
@@ -0,0 +1,132 @@
+use libauth::ApiError;
+use wiremock::matchers::{header_exists, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_list_drive_items() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/drive/root/children"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{
+                "id": "folder-id",
+                "name": "Documents",
+                "size": 2048,
+                "webUrl": "https://onedrive.live.com/?id=folder-id",
+                "lastModifiedDateTime": "2024-02-05T08:00:00Z",
+                "folder": { "childCount": 1 }
+            }],
+            "@odata.nextLink": format!("{}/me/drive/items/folder-id/children", server.uri())
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/drive/items/folder-id/children"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{
+                "id": "file-id",
+                "name": "notes.txt",
+                "size": 5,
+                "file": { "mimeType": "text/plain" }
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let items = common::client(&server)
+        .list_drive_items(None)
+        .await
+        .expect("Unable to list items");
+    assert_eq!(items.len(), 2);
+    assert!(items[0].is_folder());
+    assert_eq!(
+        items[1].file.as_ref().map(|f| f.mime_type.as_str()),
+        Some("text/plain")
+    );
+}
+
+#[tokio::test]
+async fn test_download_drive_item() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/drive/items/file-id"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "file-id",
+            "name": "notes.txt",
+            "size": 5,
+            "file": { "mimeType": "text/plain" },
+            "@microsoft.graph.downloadUrl": format!("{}/download/file-id", server.uri())
+        })))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/download/file-id"))
+        .and(header_exists("user-agent"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("hello"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/drive/items/folder-id"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "folder-id",
+            "name": "Documents",
+            "folder": { "childCount": 1 }
+        })))
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+    let data = client
+        .download_drive_item("file-id")
+        .await
+        .expect("Unable to download item");
+    assert_eq!(&data[..], b"hello");
+
+    let err = client.download_drive_item("folder-id").await;
+    assert!(matches!(err, Err(ApiError::BadRequest(_))));
+}
+
+#[tokio::test]
+async fn test_download_drive_item_error_status() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/drive/items/file-id"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "file-id",
+            "name": "notes.txt",
+            "file": { "mimeType": "text/plain" },
+            "@microsoft.graph.downloadUrl": format!("{}/download/file-id", server.uri())
+        })))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/download/file-id"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("try again later"))
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+    let err = client
+        .download_drive_item("file-id")
+        .await
+        .expect_err("Expected an error");
+    match err {
+        ApiError::ApiStatus { status, body, .. } => {
+            assert_eq!(status, 503);
+            assert_eq!(body, "try again later");
+        }
+        err => panic!("Unexpected error: {err:?}"),
+    }
+}
@@ -0,0 +1,65 @@
+use libauth::ApiError;
+use libmicrosoft::types::EventResponse;
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_respond_to_event() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/me/events/event-id/tentativelyAccept"))
+        .and(body_json(serde_json::json!({
+            "comment": "Might be a few minutes late",
+            "sendResponse": true
+        })))
+        .respond_with(ResponseTemplate::new(202))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/me/events/event-id/decline"))
+        .and(body_json(serde_json::json!({ "sendResponse": true })))
+        .respond_with(ResponseTemplate::new(202))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+    client
+        .respond_to_event(
+            "event-id",
+            EventResponse::TentativelyAccept,
+            Some("Might be a few minutes late".into()),
+        )
+        .await
+        .expect("Unable to respond to event");
+    client
+        .respond_to_event("event-id", EventResponse::Decline, None)
+        .await
+        .expect("Unable to respond to event");
+}
+
+#[tokio::test]
+async fn test_respond_to_missing_event() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/me/events/missing/accept"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "error": { "code": "ErrorItemNotFound", "message": "The specified object was not found in the store." }
+        })))
+        .mount(&server)
+        .await;
+
+    let err = common::client(&server)
+        .respond_to_event("missing", EventResponse::Accept, None)
+        .await
+        .expect_err("Expected an error");
+    assert!(err.to_string().contains("/me/events/missing/accept"));
+    assert_eq!(err.status(), Some(reqwest::StatusCode::NOT_FOUND));
+    assert!(matches!(err, ApiError::ApiStatus { body, .. } if body.contains("ErrorItemNotFound")));
+}
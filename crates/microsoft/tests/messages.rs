@@ -0,0 +1,146 @@
+use libauth::Document;
+use libmicrosoft::types::{Importance, MessageFilter};
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+fn address(name: &str) -> Value {
+    json!({ "emailAddress": { "name": name, "address": format!("{name}@example.com") } })
+}
+
+fn message(id: &str, is_read: bool, flag_status: &str, importance: &str) -> Value {
+    json!({
+        "@odata.etag": "W/\"etag\"",
+        "id": id,
+        "createdDateTime": "2023-03-01T10:00:00Z",
+        "lastModifiedDateTime": "2023-03-01T10:00:00Z",
+        "changeKey": "key",
+        "categories": [],
+        "receivedDateTime": "2023-03-01T10:00:00Z",
+        "sentDateTime": "2023-03-01T09:59:00Z",
+        "hasAttachments": false,
+        "internetMessageId": format!("<{id}@example.com>"),
+        "subject": "Quarterly report",
+        "bodyPreview": "Please review",
+        "importance": importance,
+        "parentFolderId": "inbox",
+        "conversationId": "conversation",
+        "conversationIndex": "index",
+        "isDeliveryReceiptRequested": false,
+        "isReadReceiptRequested": false,
+        "isRead": is_read,
+        "isDraft": false,
+        "webLink": "https://outlook.office365.com/owa/",
+        "inferenceClassification": "focused",
+        "body": { "contentType": "text", "content": "Please review" },
+        "sender": address("alice"),
+        "from": address("alice"),
+        "toRecipients": [address("bob")],
+        "ccRecipients": [],
+        "bccRecipients": [],
+        "replyTo": [],
+        "flag": { "flagStatus": flag_status }
+    })
+}
+
+#[tokio::test]
+async fn test_get_messages_with_filter() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/mailfolders/inbox/messages"))
+        .and(query_param("$orderby", "receivedDateTime desc"))
+        .and(query_param(
+            "$filter",
+            "receivedDateTime ge 1900-01-01T00:00:00Z and isRead eq false \
+             and flag/flagStatus eq 'flagged' and importance eq 'high'",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "value": [message("msg-1", false, "flagged", "high")]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let filter = MessageFilter {
+        unread_only: true,
+        flagged_only: true,
+        importance: Some(Importance::High),
+    };
+    let messages = common::client(&server)
+        .get_messages("inbox", &filter, false)
+        .await
+        .expect("Unable to get messages");
+
+    assert_eq!(messages.value.len(), 1);
+    let msg = &messages.value[0];
+    assert_eq!(msg.id, "msg-1");
+    assert!(!msg.is_read());
+    assert!(msg.is_flagged());
+    assert_eq!(msg.importance().as_deref(), Some("high"));
+}
+
+#[tokio::test]
+async fn test_get_messages_without_filter() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/mailfolders/inbox/messages"))
+        .and(query_param("$orderby", "receivedDateTime desc"))
+        .and(query_param_is_missing("$filter"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "value": [message("msg-2", true, "notFlagged", "normal")]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let messages = common::client(&server)
+        .get_messages("inbox", &MessageFilter::default(), false)
+        .await
+        .expect("Unable to get messages");
+
+    let msg = &messages.value[0];
+    assert!(msg.is_read());
+    assert!(!msg.is_flagged());
+    assert_eq!(msg.importance().as_deref(), Some("normal"));
+}
+
+#[tokio::test]
+async fn test_message_body_text() {
+    let server = MockServer::start().await;
+
+    let mut html = message("msg-3", true, "notFlagged", "normal");
+    html["body"] = json!({
+        "contentType": "html",
+        "content": "<html><body><p>Please review the <b>attached</b> report</p></body></html>"
+    });
+    let mut empty = message("msg-4", true, "notFlagged", "normal");
+    empty["body"] = json!({ "contentType": "html", "content": "" });
+
+    Mock::given(method("GET"))
+        .and(path("/me/mailfolders/inbox/messages"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "value": [html, empty]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let messages = common::client(&server)
+        .get_messages("inbox", &MessageFilter::default(), false)
+        .await
+        .expect("Unable to get messages");
+
+    let bodies = messages
+        .value
+        .iter()
+        .map(|msg| msg.message.as_ref().expect("Missing message").body_text())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        bodies,
+        vec!["Please review the attached report", "Please review"]
+    );
+}
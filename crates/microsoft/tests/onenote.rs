@@ -0,0 +1,102 @@
+use libauth::ApiError;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_list_pages() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/onenote/sections/section-id/pages"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{
+                "id": "page-1",
+                "title": "Meeting notes",
+                "createdDateTime": "2023-04-01T10:00:00Z",
+                "contentUrl": "https://graph.microsoft.com/v1.0/me/onenote/pages/page-1/content"
+            }],
+            "@odata.nextLink": format!("{}/next-pages", server.uri())
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/next-pages"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{ "id": "page-2", "title": "Ideas" }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+    let pages = client
+        .list_pages("section-id")
+        .await
+        .expect("Unable to list pages");
+
+    assert_eq!(pages.len(), 2);
+    assert_eq!(pages[0].title, "Meeting notes");
+    assert!(pages[0].created_date_time.is_some());
+    assert_eq!(pages[1].id, "page-2");
+}
+
+#[tokio::test]
+async fn test_get_page_text() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/onenote/pages/page-1/content"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("<div><p>Ship the <b>beta</b></p><p> on Friday</p></div>"),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+    let text = client
+        .get_page_text("page-1")
+        .await
+        .expect("Unable to get page content");
+    assert_eq!(text, "Ship the beta on Friday");
+}
+
+#[tokio::test]
+async fn test_get_page_content_errors() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/onenote/pages/expired/content"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/onenote/pages/missing/content"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "error": { "code": "20102", "message": "The requested resource does not exist." }
+        })))
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+    let err = client
+        .get_page_content("expired")
+        .await
+        .expect_err("Expected an error");
+    assert!(err.is_auth_error());
+
+    let err = client
+        .get_page_content("missing")
+        .await
+        .expect_err("Expected an error");
+    assert!(matches!(
+        err,
+        ApiError::ApiStatus { status: 404, ref body, .. } if body.contains("does not exist")
+    ));
+}
@@ -0,0 +1,94 @@
+use chrono::{TimeZone, Utc};
+use libmicrosoft::types::GraphQuery;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_get_tasks_page_size_clamped() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/todo/lists/list-id/tasks"))
+        .and(query_param("$top", "1000"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "@odata.context": "https://graph.microsoft.com/v1.0/$metadata#tasks",
+            "value": []
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let tasks = client
+        .get_tasks(
+            "list-id",
+            &GraphQuery {
+                top: Some(5000),
+                ..Default::default()
+            },
+            false,
+        )
+        .await
+        .expect("Unable to get tasks");
+    assert!(tasks.value.is_empty());
+}
+
+#[tokio::test]
+async fn test_get_new_emails_query() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/me/mailfolders/inbox/messages/delta"))
+        .and(query_param(
+            "$select",
+            "subject,from,receivedDateTime,bodyPreview",
+        ))
+        .and(query_param("$top", "50"))
+        .and(query_param(
+            "$filter",
+            "receivedDateTime gt 2024-02-13T18:00:00.000Z and isRead eq false",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "value": [{
+                "@odata.etag": "W/\"etag\"",
+                "id": "msg-1",
+                "subject": "Quarterly report",
+                "from": {
+                    "emailAddress": { "name": "Alice", "address": "alice@example.com" }
+                },
+                "receivedDateTime": "2024-02-13T19:00:00Z",
+                "bodyPreview": "Please review"
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let query = GraphQuery {
+        select: ["subject", "from", "receivedDateTime", "bodyPreview"]
+            .iter()
+            .map(|field| field.to_string())
+            .collect(),
+        top: Some(50),
+        filter: Some("isRead eq false".into()),
+    };
+    let after = Utc.with_ymd_and_hms(2024, 2, 13, 18, 0, 0).unwrap();
+    let emails = client
+        .get_new_emails(Some(after), &query)
+        .await
+        .expect("Unable to get emails");
+    assert_eq!(emails.value.len(), 1);
+    let msg = emails.value[0]
+        .message
+        .as_ref()
+        .expect("Selected fields should still parse");
+    assert_eq!(msg.subject, "Quarterly report");
+    assert_eq!(msg.body_preview, "Please review");
+    assert_eq!(msg.from.email_address.address, "alice@example.com");
+    assert_eq!(msg.received_date_time, "2024-02-13T19:00:00Z");
+}
@@ -1,5 +1,6 @@
 use dotenv_codegen::dotenv;
 use libauth::helpers::load_credentials;
+use libauth::store::FileStore;
 use libreddit::types::{AuthScopes, Post};
 use libreddit::RedditClient;
 
@@ -21,13 +22,8 @@ async fn main() -> anyhow::Result<()> {
     let client_secret = dotenv!("REDDIT_CLIENT_SECRET");
 
     let mut client = RedditClient::new(client_id, client_secret, REDIRECT_URL, Default::default())?;
-    let scopes = vec![
-        AuthScopes::Identity.to_string(),
-        AuthScopes::History.to_string(),
-        AuthScopes::MySubreddits.to_string(),
-        AuthScopes::Read.to_string(),
-    ];
-    load_credentials(&mut client, &scopes, true).await;
+    let scopes = AuthScopes::default_read_scopes();
+    load_credentials(&mut client, &FileStore::default(), &scopes, true).await;
 
     let user = client.get_user().await?;
     println!("Authenticated as user: {}", user.name);
@@ -1,18 +1,24 @@
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use async_trait::async_trait;
+use chrono::Utc;
 use libauth::{
-    auth_http_client, oauth_client, ApiClient, ApiError, AuthorizationRequest, AuthorizeOptions,
-    Credentials, OAuthParams,
+    json_response, oauth_client, oauth_http_client, AccountInfo, ApiClient, ApiError,
+    AuthorizationRequest, AuthorizeOptions, ClientConfig, ClientSettings, Credentials, OAuthParams,
+    RetryPolicy, TokenError,
 };
 use oauth2::basic::{BasicClient, BasicTokenResponse};
 use oauth2::{
     AuthorizationCode, CsrfToken, PkceCodeChallenge, PkceCodeVerifier, Scope, TokenResponse,
 };
 
-use reqwest::Client;
+use reqwest::{Client, Method};
+use serde::de::DeserializeOwned;
 use serde_json::Value;
-use tokio::sync::watch;
-use types::{ApiResponse, DataWrapper, Listing, Post};
+use std::sync::Arc;
+use tokio::sync::{watch, Mutex};
+use types::{
+    ApiResponse, Comment, DataWrapper, Listing, ListingSort, Post, RedditRateLimit, Subreddit,
+};
 
 pub mod types;
 
@@ -20,14 +26,23 @@ const AUTH_URL: &str = "https://www.reddit.com/api/v1/authorize";
 const TOKEN_URL: &str = "https://www.reddit.com/api/v1/access_token";
 
 const API_ENDPOINT: &str = "https://oauth.reddit.com";
+/// Reddit rejects generic user agents, see https://github.com/reddit-archive/reddit/wiki/API
+const USER_AGENT: &str = "desktop:com.athlabs.spyglass:v0.0.1 (by /u/andyndino)";
+/// Max number of fullnames accepted by `/api/info`
+const MAX_INFO_IDS: usize = 100;
 
+#[derive(Clone)]
 pub struct RedditClient {
     pub credentials: Credentials,
+    endpoint: String,
     http: Client,
     pub oauth: BasicClient,
     pub on_refresh_tx: watch::Sender<Credentials>,
     pub on_refresh_rx: watch::Receiver<Credentials>,
+    settings: ClientSettings,
+    refresh_lock: Arc<Mutex<()>>,
     pub username: Option<String>,
+    rate_limit: Option<RedditRateLimit>,
 }
 
 #[async_trait]
@@ -50,6 +65,18 @@ impl ApiClient for RedditClient {
         None
     }
 
+    async fn account_info(&mut self) -> Result<AccountInfo> {
+        let user = self.get_user().await?;
+        Ok(AccountInfo {
+            id: user.id.clone(),
+            display_name: user.name.clone(),
+            // Reddit does not expose the account email.
+            email: None,
+            avatar_url: user.icon_img.clone(),
+            metadata: serde_json::to_value(&user)?,
+        })
+    }
+
     fn credentials(&self) -> Credentials {
         self.credentials.clone()
     }
@@ -60,7 +87,9 @@ impl ApiClient for RedditClient {
 
     fn set_credentials(&mut self, credentials: &Credentials) -> Result<()> {
         self.credentials = credentials.clone();
-        self.http = auth_http_client(credentials.access_token.secret())?;
+        self.http = self
+            .settings
+            .auth_http_client(credentials.access_token.secret())?;
         Ok(())
     }
 
@@ -68,6 +97,18 @@ impl ApiClient for RedditClient {
         self.on_refresh_rx.clone()
     }
 
+    fn refresh_lock(&self) -> Arc<Mutex<()>> {
+        self.refresh_lock.clone()
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        self.settings.retry_policy.clone()
+    }
+
+    fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.settings.retry_policy = policy;
+    }
+
     fn authorize(&self, scopes: &[String], _: &AuthorizeOptions) -> AuthorizationRequest {
         let (pkce_code_challenge, pkce_code_verifier) = PkceCodeChallenge::new_random_sha256();
         let scopes = scopes
@@ -97,7 +138,7 @@ impl ApiClient for RedditClient {
         &self,
         code: &str,
         pkce_verifier: Option<String>,
-    ) -> Result<BasicTokenResponse> {
+    ) -> Result<BasicTokenResponse, TokenError> {
         let code = AuthorizationCode::new(code.to_owned());
 
         let mut exchange = self.oauth.exchange_code(code);
@@ -105,24 +146,61 @@ impl ApiClient for RedditClient {
             exchange = exchange.set_pkce_verifier(PkceCodeVerifier::new(pkce_verifier));
         }
 
-        match exchange.request_async(Self::http_client).await {
+        let user_agent = self.settings.user_agent.clone();
+        match exchange
+            .request_async(|req| oauth_http_client(req, user_agent))
+            .await
+        {
             Ok(val) => Ok(val),
-            Err(err) => Err(anyhow!(err.to_string())),
+            Err(err) => Err(err.into()),
         }
     }
 
-    async fn refresh_credentials(&mut self) -> Result<()> {
+    /// Waits for the rate limit to reset if it's been used up and records the
+    /// limit sent with the response.
+    async fn call_json(
+        &mut self,
+        endpoint: &str,
+        query: &[(String, String)],
+    ) -> Result<Value, ApiError> {
+        // Wait for the rate limit to reset rather than get a 429
+        if let Some(limit) = self
+            .rate_limit
+            .as_ref()
+            .filter(|limit| limit.is_exhausted())
+        {
+            if let Ok(wait) = (limit.reset_at - Utc::now()).to_std() {
+                log::debug!("Rate limit hit, waiting {}s", wait.as_secs());
+                tokio::time::sleep(wait).await;
+            }
+        }
+
+        let resp = self.call(endpoint, &query.to_vec()).await?;
+        if let Some(limit) = RedditRateLimit::from_headers(resp.headers()) {
+            self.rate_limit = Some(limit);
+        }
+
+        reddit_json(resp)
+            .await
+            .map_err(|err| err.with_endpoint(Method::GET, endpoint))
+    }
+
+    async fn refresh_credentials(&mut self) -> Result<(), TokenError> {
         if let Some(refresh_token) = &self.credentials.refresh_token {
             let new_token = self
                 .oauth
                 .exchange_refresh_token(refresh_token)
-                .request_async(Self::http_client)
+                .request_async(|req| oauth_http_client(req, self.settings.user_agent.clone()))
                 .await?;
 
             self.credentials.refresh_token(&new_token);
-            self.http = auth_http_client(new_token.access_token().secret())?;
+            self.http = self
+                .settings
+                .auth_http_client(new_token.access_token().secret())?;
             // Let any listeners know the credentials have been updated.
-            self.on_refresh_tx.send(self.credentials.clone())?;
+            self.on_refresh_tx
+                .send(self.credentials.clone())
+                .map_err(anyhow::Error::from)?;
         }
 
         Ok(())
@@ -135,6 +213,23 @@ impl RedditClient {
         client_secret: &str,
         redirect_url: &str,
         creds: Credentials,
+    ) -> anyhow::Result<Self> {
+        Self::new_with_config(
+            client_id,
+            client_secret,
+            redirect_url,
+            creds,
+            ClientConfig::default(),
+        )
+    }
+
+    /// Same as `new`, with timeouts etc. applied to every request.
+    pub fn new_with_config(
+        client_id: &str,
+        client_secret: &str,
+        redirect_url: &str,
+        creds: Credentials,
+        config: ClientConfig,
     ) -> anyhow::Result<Self> {
         let params = OAuthParams {
             client_id: client_id.to_owned(),
@@ -145,70 +240,163 @@ impl RedditClient {
             ..Default::default()
         };
 
+        let settings = ClientSettings {
+            user_agent: USER_AGENT.to_string(),
+            ..ClientSettings::new(config)
+        };
         let (tx, rx) = watch::channel(creds.clone());
 
         Ok(RedditClient {
             credentials: creds.clone(),
-            http: auth_http_client(creds.access_token.secret())?,
-            oauth: oauth_client(&params),
+            endpoint: API_ENDPOINT.to_string(),
+            http: settings.auth_http_client(creds.access_token.secret())?,
+            oauth: oauth_client(&params)?,
             on_refresh_tx: tx,
             on_refresh_rx: rx,
+            settings,
+            refresh_lock: Arc::new(Mutex::new(())),
             username: None,
+            rate_limit: None,
         })
     }
 
+    /// Override the base API URL, e.g. to target a mock server.
+    pub fn set_endpoint(&mut self, endpoint: &str) {
+        self.endpoint = endpoint.trim_end_matches('/').to_string();
+    }
+
+    /// Override the user agent sent with API requests.
+    pub fn set_user_agent(&mut self, user_agent: &str) -> Result<()> {
+        self.settings.user_agent = user_agent.to_string();
+        self.http = self
+            .settings
+            .auth_http_client(self.credentials.access_token.secret())?;
+        Ok(())
+    }
+
     pub async fn get_user(&mut self) -> Result<types::User, ApiError> {
-        let mut endpoint = API_ENDPOINT.to_string();
+        let mut endpoint = self.endpoint.to_string();
         endpoint.push_str("/api/v1/me");
 
         let resp = self.call_json(&endpoint, &Vec::new()).await?;
         serde_json::from_value::<types::User>(resp).map_err(ApiError::SerdeError)
     }
 
-    pub async fn http_client(
-        mut request: oauth2::HttpRequest,
-    ) -> Result<oauth2::HttpResponse, oauth2::reqwest::Error<reqwest::Error>> {
-        request.headers.insert(
-            "User-Agent",
-            "desktop:com.athlabs.spyglass:v0.0.1 (by /u/andyndino)"
-                .parse()
-                .unwrap(),
-        );
-        oauth2::reqwest::async_http_client(request).await
+    /// Rate limit state as of the last request.
+    pub fn last_rate_limit(&self) -> Option<RedditRateLimit> {
+        self.rate_limit.clone()
     }
 
-    async fn paginate(
+    async fn paginate<T: DeserializeOwned + Default>(
         &mut self,
         endpoint: &str,
         query: &[(String, String)],
-    ) -> Result<ApiResponse<Vec<Post>>, ApiError> {
-        let listing = serde_json::from_value::<types::DataWrapper<Listing<DataWrapper<Post>>>>(
+    ) -> Result<ApiResponse<Vec<T>>, ApiError> {
+        let listing = serde_json::from_value::<types::DataWrapper<Listing<DataWrapper<T>>>>(
             self.call_json(endpoint, query).await?,
         )?;
 
         let after = listing.data.after;
-        let posts = listing
+        let data = listing
             .data
             .children
-            .iter()
-            .map(|x| x.data.to_owned())
+            .into_iter()
+            .map(|x| x.data)
             .collect::<Vec<_>>();
 
-        Ok(ApiResponse { after, data: posts })
+        Ok(ApiResponse { after, data })
     }
 
     pub async fn get_post(&mut self, id: &str) -> Result<Option<Post>, ApiError> {
-        let mut endpoint = API_ENDPOINT.to_string();
+        let posts = self.get_posts(&[id.to_string()]).await?;
+        Ok(posts.first().cloned())
+    }
+
+    /// Fetch multiple posts/comments by their fullnames (e.g. "t3_15bfi0").
+    /// Reddit accepts at most 100 ids per request.
+    pub async fn get_posts(&mut self, ids: &[String]) -> Result<Vec<Post>, ApiError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        } else if ids.len() > MAX_INFO_IDS {
+            return Err(ApiError::BadRequest(format!(
+                "Unable to request more than {MAX_INFO_IDS} ids at once"
+            )));
+        }
+
+        let mut endpoint = self.endpoint.to_string();
         endpoint.push_str("/api/info");
 
-        let query = vec![("id".into(), id.into())];
+        let query = vec![("id".into(), ids.join(","))];
 
         let resp = self.paginate(&endpoint, &query).await?;
-        if let Some(post) = resp.data.first() {
-            Ok(Some(post.to_owned()))
-        } else {
-            Ok(None)
+        Ok(resp.data)
+    }
+
+    /// Fetch the comments on a post as a flattened list, parents before their
+    /// replies. `depth` limits how many levels of replies Reddit returns.
+    pub async fn get_comments(
+        &mut self,
+        subreddit: &str,
+        article_id: &str,
+        depth: Option<u32>,
+    ) -> Result<Vec<Comment>, ApiError> {
+        let mut endpoint = self.endpoint.to_string();
+        endpoint.push_str(&format!("/r/{subreddit}/comments/{article_id}"));
+
+        let mut query = Vec::new();
+        if let Some(depth) = depth {
+            query.push(("depth".into(), depth.to_string()));
+        }
+
+        // The first listing contains the post itself, the second its comments.
+        let (_, comments) = serde_json::from_value::<(
+            Value,
+            DataWrapper<Listing<DataWrapper<Comment>>>,
+        )>(self.call_json(&endpoint, &query).await?)
+        .map_err(ApiError::SerdeError)?;
+
+        Ok(Comment::flatten(comments.data.children))
+    }
+
+    /// Fetch info for multiple subreddits by name (e.g. "rust"). Banned, private
+    /// or unknown subreddits are omitted from the results.
+    pub async fn get_subreddits_by_name(
+        &mut self,
+        names: &[String],
+    ) -> Result<Vec<Subreddit>, ApiError> {
+        let mut endpoint = self.endpoint.to_string();
+        endpoint.push_str("/api/info");
+
+        let mut subreddits = Vec::new();
+        for names in names.chunks(MAX_INFO_IDS) {
+            let query = vec![("sr_name".into(), names.join(","))];
+            let listing = serde_json::from_value::<DataWrapper<Listing<DataWrapper<Subreddit>>>>(
+                self.call_json(&endpoint, &query).await?,
+            )
+            .map_err(ApiError::SerdeError)?;
+
+            subreddits.extend(listing.data.children.into_iter().map(|sr| sr.data));
+        }
+
+        Ok(subreddits)
+    }
+
+    /// List the subreddits the user is subscribed to.
+    pub async fn list_subscribed(
+        &mut self,
+        after: Option<String>,
+        limit: usize,
+    ) -> Result<ApiResponse<Vec<Subreddit>>, ApiError> {
+        let mut endpoint = self.endpoint.to_string();
+        endpoint.push_str("/subreddits/mine/subscriber");
+
+        // Make sure limit is at least 1 & at most 100
+        let mut query = vec![("limit".into(), limit.clamp(1, 100).to_string())];
+        if let Some(after) = after {
+            query.push(("after".into(), after));
         }
+
+        self.paginate(&endpoint, &query).await
     }
 
     pub async fn list_saved(
@@ -216,7 +404,7 @@ impl RedditClient {
         after: Option<String>,
         limit: usize,
     ) -> Result<ApiResponse<Vec<Post>>, ApiError> {
-        let mut endpoint = API_ENDPOINT.to_string();
+        let mut endpoint = self.endpoint.to_string();
         let username = self.account_id().await?;
         endpoint.push_str(&format!("/user/{}/saved", username));
 
@@ -224,7 +412,7 @@ impl RedditClient {
             // for all time
             ("t".into(), "all".into()),
             // Make sure limit is at least 1 & at most 100
-            ("limit".into(), limit.max(1).min(100).to_string()),
+            ("limit".into(), limit.clamp(1, 100).to_string()),
         ];
         if let Some(after) = after {
             query.push(("after".into(), after));
@@ -238,7 +426,7 @@ impl RedditClient {
         after: Option<String>,
         limit: usize,
     ) -> Result<ApiResponse<Vec<Post>>, ApiError> {
-        let mut endpoint = API_ENDPOINT.to_string();
+        let mut endpoint = self.endpoint.to_string();
         let username = self.account_id().await?;
         endpoint.push_str(&format!("/user/{}/upvoted", username));
 
@@ -246,7 +434,7 @@ impl RedditClient {
             // for all time
             ("t".into(), "all".into()),
             // Make sure limit is at least 1 & at most 100
-            ("limit".into(), limit.max(1).min(100).to_string()),
+            ("limit".into(), limit.clamp(1, 100).to_string()),
         ];
 
         if let Some(after) = after {
@@ -255,4 +443,64 @@ impl RedditClient {
 
         self.paginate(&endpoint, &query).await
     }
+
+    /// List posts submitted by `username`, defaulting to the authenticated user.
+    pub async fn list_submitted(
+        &mut self,
+        username: Option<&str>,
+        sort: &ListingSort,
+        after: Option<String>,
+        limit: usize,
+    ) -> Result<ApiResponse<Vec<Post>>, ApiError> {
+        let username = match username {
+            Some(username) => username.to_string(),
+            None => self.account_id().await?,
+        };
+
+        let mut endpoint = self.endpoint.to_string();
+        endpoint.push_str(&format!("/user/{}/submitted", username));
+
+        let mut query = sort.to_query();
+        // Make sure limit is at least 1 & at most 100
+        query.push(("limit".into(), limit.clamp(1, 100).to_string()));
+        if let Some(after) = after {
+            query.push(("after".into(), after));
+        }
+
+        self.paginate(&endpoint, &query).await
+    }
+}
+
+/// `json_response` plus Reddit's error envelope, `{ "error": 403, "message": .. }`,
+/// which may come with a 2xx status. A 403 (suspended, quarantined or over 18
+/// content) is an `ApiStatus` rather than an `AuthError` since re-authorizing
+/// won't help. An HTML page instead of JSON is reported as a `BadRequest`.
+async fn reddit_json(resp: reqwest::Response) -> Result<Value, ApiError> {
+    let status = resp.status();
+    let value = json_response(resp).await.map_err(|err| match err {
+        ApiError::InvalidJson { body, .. } if body.trim_start().starts_with('<') => {
+            ApiError::BadRequest(format!(
+                "Expected JSON but received an HTML page ({status})"
+            ))
+        }
+        err => err,
+    })?;
+
+    let Some(code) = value.get("error").and_then(|code| code.as_u64()) else {
+        return Ok(value);
+    };
+    let message = value
+        .get("message")
+        .and_then(|msg| msg.as_str())
+        .unwrap_or("Unknown error")
+        .to_string();
+    Err(match code {
+        401 => ApiError::AuthError(message),
+        403 => ApiError::ApiStatus {
+            status: 403,
+            body: value.to_string(),
+            url: None,
+        },
+        _ => ApiError::BadRequest(message),
+    })
 }
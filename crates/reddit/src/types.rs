@@ -1,6 +1,8 @@
 use std::str::FromStr;
 
 use chrono::{DateTime, NaiveDateTime, Utc};
+use libauth::Document;
+use reqwest::header::HeaderMap;
 use serde::de::Error;
 use serde::{Deserialize, Deserializer, Serialize};
 use strum_macros::{Display, EnumString};
@@ -23,11 +25,24 @@ pub enum AuthScopes {
     Read,
 }
 
+impl AuthScopes {
+    /// Scopes needed to read the user's profile, saved/upvoted posts & subreddits.
+    pub fn default_read_scopes() -> Vec<String> {
+        vec![
+            AuthScopes::Identity.to_string(),
+            AuthScopes::History.to_string(),
+            AuthScopes::MySubreddits.to_string(),
+            AuthScopes::Read.to_string(),
+        ]
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(default)]
 pub struct User {
     pub id: String,
     pub name: String,
+    pub icon_img: Option<String>,
 }
 
 /// Converts a Reddit UTC timestamp in seconds to chrono::DateTime<Utc>
@@ -43,6 +58,38 @@ where
     Err(D::Error::custom("Unable to deserialize time"))
 }
 
+/// Rate limit state reported by Reddit in the `X-Ratelimit-*` response headers.
+#[derive(Clone, Debug)]
+pub struct RedditRateLimit {
+    /// Approximate number of requests left in the current period.
+    pub remaining: f64,
+    /// Number of requests used in the current period.
+    pub used: u32,
+    /// When the current period ends & the limit resets.
+    pub reset_at: DateTime<Utc>,
+}
+
+impl RedditRateLimit {
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+        let remaining = header("x-ratelimit-remaining")?.parse::<f64>().ok()?;
+        let used = header("x-ratelimit-used")?.parse::<u32>().ok()?;
+        let reset = header("x-ratelimit-reset")?.parse::<i64>().ok()?;
+
+        Some(RedditRateLimit {
+            remaining,
+            used,
+            reset_at: Utc::now() + chrono::Duration::seconds(reset),
+        })
+    }
+
+    /// True if there are no more requests left before the limit resets.
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining < 1.0 && Utc::now() < self.reset_at
+    }
+}
+
 pub struct ApiResponse<T> {
     pub after: Option<String>,
     pub data: T,
@@ -82,6 +129,144 @@ pub struct Post {
     pub body: Option<String>,
     /// Link title the comment is under.
     pub link_title: Option<String>,
+    /// Set when a post was removed, e.g. "moderator", "deleted" or "author".
+    pub removed_by_category: Option<String>,
+}
+
+/// Placeholder used by Reddit for removed content.
+const REMOVED: &str = "[removed]";
+/// Placeholder used by Reddit for deleted content & authors.
+const DELETED: &str = "[deleted]";
+
+impl Post {
+    /// Whether the post/comment content was removed by a moderator or deleted.
+    pub fn is_removed(&self) -> bool {
+        let text = self.body.as_deref().unwrap_or(&self.selftext);
+        self.removed_by_category.is_some() || text == REMOVED || text == DELETED
+    }
+
+    /// Whether the author's account was deleted or suspended.
+    pub fn is_deleted_author(&self) -> bool {
+        self.author == DELETED
+    }
+
+    /// Text content of the post/comment, or `None` if it was removed.
+    pub fn content(&self) -> Option<String> {
+        if self.is_removed() {
+            None
+        } else {
+            Some(self.body_text())
+        }
+    }
+}
+
+impl Document for Post {
+    fn id(&self) -> String {
+        self.name.clone()
+    }
+
+    fn title(&self) -> String {
+        self.title
+            .clone()
+            .or_else(|| self.link_title.clone())
+            .unwrap_or_default()
+    }
+
+    fn body_text(&self) -> String {
+        self.body.clone().unwrap_or_else(|| self.selftext.clone())
+    }
+
+    fn url(&self) -> Option<String> {
+        Some(format!("https://www.reddit.com{}", self.permalink))
+    }
+
+    fn updated_at(&self) -> Option<DateTime<Utc>> {
+        Some(self.created_utc)
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Subreddit {
+    /// Fullname of the subreddit, e.g. "t5_2qh1i".
+    pub name: String,
+    /// Name without the "r/" prefix, e.g. "rust".
+    pub display_name: String,
+    pub title: String,
+    pub public_description: String,
+    /// Hidden by Reddit for some subreddits.
+    pub subscribers: Option<u64>,
+    pub icon_img: String,
+    pub community_icon: String,
+    pub over18: bool,
+    /// "public", "private", "restricted", etc.
+    pub subreddit_type: String,
+    /// Relative url, e.g. "/r/rust/".
+    pub url: String,
+    #[serde(deserialize_with = "from_utc_secs")]
+    pub created_utc: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Comment {
+    /// Fullname of the comment, e.g. "t1_jt4b2xq".
+    pub name: String,
+    /// Fullname of the parent comment or post.
+    pub parent_id: String,
+    /// Fullname of the post this comment is under.
+    pub link_id: String,
+    pub subreddit: String,
+    pub author: String,
+    pub body: String,
+    pub permalink: String,
+    pub score: i32,
+    #[serde(deserialize_with = "from_utc_secs")]
+    pub created_utc: DateTime<Utc>,
+    /// How deeply nested the comment is, top-level comments are 0.
+    pub depth: u32,
+    /// Nested replies, empty once the comment tree has been flattened.
+    #[serde(deserialize_with = "from_replies", skip_serializing)]
+    pub replies: Vec<DataWrapper<Comment>>,
+}
+
+impl Comment {
+    /// Flatten a comment tree into a list, parents before their replies.
+    /// "Load more" stubs are skipped.
+    pub fn flatten(comments: Vec<DataWrapper<Comment>>) -> Vec<Comment> {
+        Self::flatten_at(comments, 0)
+    }
+
+    fn flatten_at(comments: Vec<DataWrapper<Comment>>, depth: u32) -> Vec<Comment> {
+        let mut flattened = Vec::new();
+        for wrapper in comments {
+            if !matches!(wrapper.data_type(), DataType::Comment) {
+                continue;
+            }
+
+            let mut comment = wrapper.data;
+            let replies = std::mem::take(&mut comment.replies);
+            comment.depth = depth;
+            flattened.push(comment);
+            flattened.extend(Self::flatten_at(replies, depth + 1));
+        }
+
+        flattened
+    }
+}
+
+/// Reddit sends an empty string rather than an empty listing when a comment
+/// has no replies.
+fn from_replies<'de, D>(deserializer: D) -> Result<Vec<DataWrapper<Comment>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(_) => Ok(Vec::new()),
+        value => serde_json::from_value::<DataWrapper<Listing<DataWrapper<Comment>>>>(value)
+            .map(|listing| listing.data.children)
+            .map_err(D::Error::custom),
+    }
 }
 
 /// Types are documented here: https://www.reddit.com/dev/api/oauth#fullnames
@@ -105,6 +290,50 @@ pub enum DataType {
     Unknown,
 }
 
+/// Time window used when sorting listings by top/controversial.
+#[derive(Clone, Debug, Default, Display, PartialEq, Eq)]
+pub enum TimeFilter {
+    #[strum(serialize = "hour")]
+    Hour,
+    #[strum(serialize = "day")]
+    Day,
+    #[strum(serialize = "week")]
+    Week,
+    #[strum(serialize = "month")]
+    Month,
+    #[strum(serialize = "year")]
+    Year,
+    #[default]
+    #[strum(serialize = "all")]
+    All,
+}
+
+/// Sort order for user listings such as submitted posts.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ListingSort {
+    Hot,
+    #[default]
+    New,
+    Top(TimeFilter),
+    Controversial(TimeFilter),
+}
+
+impl ListingSort {
+    pub fn to_query(&self) -> Vec<(String, String)> {
+        let (sort, time) = match self {
+            ListingSort::Hot => ("hot", TimeFilter::All),
+            ListingSort::New => ("new", TimeFilter::All),
+            ListingSort::Top(time) => ("top", time.clone()),
+            ListingSort::Controversial(time) => ("controversial", time.clone()),
+        };
+
+        vec![
+            ("sort".to_string(), sort.to_string()),
+            ("t".to_string(), time.to_string()),
+        ]
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 #[serde(default)]
 pub struct DataWrapper<T> {
@@ -124,6 +353,70 @@ impl<T> DataWrapper<T> {
 #[serde(default)]
 pub struct Listing<T> {
     pub after: Option<String>,
-    pub dist: i32,
+    /// Null for comment listings.
+    pub dist: Option<i32>,
     pub children: Vec<T>,
 }
+
+#[cfg(test)]
+mod test {
+    use super::{DataWrapper, ListingSort, Post, RedditRateLimit, TimeFilter};
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    #[test]
+    fn test_rate_limit_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("0.0"));
+        headers.insert("x-ratelimit-used", HeaderValue::from_static("600"));
+        headers.insert("x-ratelimit-reset", HeaderValue::from_static("120"));
+
+        let limit = RedditRateLimit::from_headers(&headers).expect("Unable to parse headers");
+        assert_eq!(limit.used, 600);
+        assert!(limit.is_exhausted());
+
+        assert!(RedditRateLimit::from_headers(&HeaderMap::new()).is_none());
+    }
+
+    #[test]
+    fn test_listing_sort_query() {
+        assert_eq!(
+            ListingSort::Top(TimeFilter::Week).to_query(),
+            vec![
+                ("sort".to_string(), "top".to_string()),
+                ("t".to_string(), "week".to_string())
+            ]
+        );
+        assert_eq!(ListingSort::default().to_query()[0].1, "new");
+    }
+
+    #[test]
+    fn test_removed_post() {
+        let post: DataWrapper<Post> =
+            serde_json::from_str(include_str!("../fixtures/removed_post.json"))
+                .expect("Unable to parse fixture");
+        let post = post.data;
+        assert!(post.is_removed());
+        assert!(!post.is_deleted_author());
+        assert_eq!(post.title.as_deref(), Some("Is anyone else seeing this?"));
+        assert_eq!(post.content(), None);
+    }
+
+    #[test]
+    fn test_deleted_comment() {
+        let comment: DataWrapper<Post> =
+            serde_json::from_str(include_str!("../fixtures/deleted_comment.json"))
+                .expect("Unable to parse fixture");
+        let comment = comment.data;
+        assert!(comment.is_removed());
+        assert!(comment.is_deleted_author());
+        assert_eq!(comment.content(), None);
+
+        let post = Post {
+            author: "spyglass".into(),
+            selftext: "Still here".into(),
+            ..Default::default()
+        };
+        assert!(!post.is_removed());
+        assert_eq!(post.content().as_deref(), Some("Still here"));
+    }
+}
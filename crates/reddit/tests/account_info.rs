@@ -0,0 +1,63 @@
+use libauth::ApiClient;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_account_info() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/v1/me"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "4x6y1",
+            "name": "spez",
+            "icon_img": "https://styles.redditmedia.com/t5_3k30p/styles/profileIcon.png"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let info = common::client(&server)
+        .account_info()
+        .await
+        .expect("Unable to get account info");
+    assert_eq!(info.id, "4x6y1");
+    assert_eq!(info.display_name, "spez");
+    assert_eq!(info.email, None);
+    assert_eq!(
+        info.avatar_url.as_deref(),
+        Some("https://styles.redditmedia.com/t5_3k30p/styles/profileIcon.png")
+    );
+    assert_eq!(info.metadata["name"], "spez");
+}
+
+#[tokio::test]
+async fn test_account_info_records_rate_limit() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/v1/me"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("x-ratelimit-remaining", "95.0")
+                .insert_header("x-ratelimit-used", "5")
+                .insert_header("x-ratelimit-reset", "300")
+                .set_body_json(serde_json::json!({ "id": "4x6y1", "name": "spez" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+    client
+        .account_info()
+        .await
+        .expect("Unable to get account info");
+
+    let limit = client.last_rate_limit().expect("Expected a rate limit");
+    assert_eq!(limit.remaining, 95.0);
+    assert_eq!(limit.used, 5);
+    assert!(!limit.is_exhausted());
+}
@@ -0,0 +1,19 @@
+use std::sync::Arc;
+
+use libauth::ApiClient;
+use wiremock::MockServer;
+
+mod common;
+
+#[tokio::test]
+async fn test_clone_shares_refresh_state() {
+    let server = MockServer::start().await;
+
+    let mut client = common::client(&server);
+    let mut other = client.clone();
+
+    assert!(Arc::ptr_eq(&client.refresh_lock(), &other.refresh_lock()));
+    assert!(client
+        .watch_on_refresh()
+        .same_channel(&other.watch_on_refresh()));
+}
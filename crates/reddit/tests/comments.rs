@@ -0,0 +1,76 @@
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+fn comment(
+    name: &str,
+    parent_id: &str,
+    body: &str,
+    replies: serde_json::Value,
+) -> serde_json::Value {
+    serde_json::json!({
+        "kind": "t1",
+        "data": {
+            "name": name,
+            "parent_id": parent_id,
+            "link_id": "t3_15bfi0",
+            "subreddit": "rust",
+            "author": "ferris",
+            "body": body,
+            "permalink": format!("/r/rust/comments/15bfi0/_/{name}/"),
+            "score": 1,
+            "created_utc": 1690000000.0,
+            "replies": replies
+        }
+    })
+}
+
+fn listing(children: Vec<serde_json::Value>) -> serde_json::Value {
+    serde_json::json!({
+        "kind": "Listing",
+        "data": { "after": null, "dist": null, "children": children }
+    })
+}
+
+#[tokio::test]
+async fn test_get_comments() {
+    let server = MockServer::start().await;
+
+    let reply = comment("t1_b", "t1_a", "reply", serde_json::json!(""));
+    let more = serde_json::json!({
+        "kind": "more",
+        "data": { "count": 3, "name": "t1_c", "parent_id": "t1_a", "children": ["c", "d", "e"] }
+    });
+    let top = comment("t1_a", "t3_15bfi0", "top", listing(vec![reply, more]));
+    let other = comment("t1_f", "t3_15bfi0", "other", serde_json::json!(""));
+
+    Mock::given(method("GET"))
+        .and(path("/r/rust/comments/15bfi0"))
+        .and(query_param("depth", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            listing(vec![serde_json::json!({
+                "kind": "t3",
+                "data": { "name": "t3_15bfi0", "title": "Is anyone else seeing this?" }
+            })]),
+            listing(vec![top, other])
+        ])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let comments = client
+        .get_comments("rust", "15bfi0", Some(2))
+        .await
+        .expect("Unable to get comments");
+
+    let flattened = comments
+        .iter()
+        .map(|c| (c.name.as_str(), c.depth))
+        .collect::<Vec<_>>();
+    assert_eq!(flattened, vec![("t1_a", 0), ("t1_b", 1), ("t1_f", 0)]);
+    assert!(comments.iter().all(|c| c.replies.is_empty()));
+    assert_eq!(comments[1].body, "reply");
+}
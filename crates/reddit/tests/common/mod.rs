@@ -0,0 +1,5 @@
+#![allow(dead_code)]
+
+use libreddit::RedditClient;
+
+libauth::mock_client_factory!(RedditClient);
@@ -0,0 +1,72 @@
+use libauth::ApiError;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_forbidden_envelope() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/v1/me"))
+        .respond_with(ResponseTemplate::new(403).set_body_raw(
+            include_str!("../fixtures/forbidden.json"),
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let Err(err) = common::client(&server).get_user().await else {
+        panic!("Expected an error");
+    };
+    assert!(
+        matches!(&err, ApiError::ApiStatus { status: 403, body, .. } if body.contains("Forbidden")),
+        "{err:?}"
+    );
+    assert!(!err.is_auth_error());
+}
+
+#[tokio::test]
+async fn test_forbidden_envelope_with_ok_status() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/v1/me"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            include_str!("../fixtures/forbidden.json"),
+            "application/json",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let Err(err) = common::client(&server).get_user().await else {
+        panic!("Expected an error");
+    };
+    assert!(
+        matches!(&err, ApiError::ApiStatus { status: 403, .. }),
+        "{err:?}"
+    );
+}
+
+#[tokio::test]
+async fn test_html_response() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/subreddits/mine/subscriber"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(
+            "<!doctype html><html><body>over 18?</body></html>",
+            "text/html",
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let Err(err) = common::client(&server).list_subscribed(None, 25).await else {
+        panic!("Expected an error");
+    };
+    assert!(matches!(err, ApiError::BadRequest(_)), "{err:?}");
+}
@@ -0,0 +1,169 @@
+use libauth::ApiError;
+use libreddit::types::{ListingSort, TimeFilter};
+use std::time::{Duration, Instant};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+fn post(name: &str, title: &str) -> serde_json::Value {
+    serde_json::json!({
+        "kind": "t3",
+        "data": {
+            "name": name,
+            "subreddit": "rust",
+            "author": "ferris",
+            "title": title,
+            "permalink": format!("/r/rust/comments/{name}/"),
+            "created_utc": 1690000000.0
+        }
+    })
+}
+
+fn listing(children: Vec<serde_json::Value>, after: Option<&str>) -> serde_json::Value {
+    serde_json::json!({
+        "kind": "Listing",
+        "data": { "after": after, "dist": children.len(), "children": children }
+    })
+}
+
+#[tokio::test]
+async fn test_get_posts() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/info"))
+        .and(query_param("id", "t3_a,t3_b"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(listing(
+            vec![post("t3_a", "First"), post("t3_b", "Second")],
+            None,
+        )))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let posts = common::client(&server)
+        .get_posts(&["t3_a".to_string(), "t3_b".to_string()])
+        .await
+        .expect("Unable to get posts");
+    let names = posts
+        .iter()
+        .map(|post| post.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["t3_a", "t3_b"]);
+    assert_eq!(posts[1].title.as_deref(), Some("Second"));
+}
+
+#[tokio::test]
+async fn test_get_posts_too_many_ids() {
+    let server = MockServer::start().await;
+
+    let ids = (0..101).map(|id| format!("t3_{id}")).collect::<Vec<_>>();
+    let err = common::client(&server)
+        .get_posts(&ids)
+        .await
+        .expect_err("Expected an error");
+    assert!(matches!(err, ApiError::BadRequest(_)), "{err:?}");
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_rate_limit_waits_for_reset() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/info"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("x-ratelimit-remaining", "0.0")
+                .insert_header("x-ratelimit-used", "100")
+                .insert_header("x-ratelimit-reset", "1")
+                .set_body_json(listing(vec![post("t3_a", "First")], None)),
+        )
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+    assert!(client.last_rate_limit().is_none());
+
+    client
+        .get_posts(&["t3_a".to_string()])
+        .await
+        .expect("Unable to get posts");
+    let limit = client.last_rate_limit().expect("Expected a rate limit");
+    assert_eq!(limit.used, 100);
+    assert!(limit.is_exhausted());
+
+    // The next request is held back until the limit resets
+    let start = Instant::now();
+    client
+        .get_posts(&["t3_a".to_string()])
+        .await
+        .expect("Unable to get posts");
+    assert!(start.elapsed() >= Duration::from_millis(900));
+}
+
+#[tokio::test]
+async fn test_list_submitted() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/user/ferris/submitted"))
+        .and(query_param("sort", "top"))
+        .and(query_param("t", "week"))
+        .and(query_param("limit", "100"))
+        .and(query_param("after", "t3_z"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(listing(vec![post("t3_a", "First")], Some("t3_a"))),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let resp = common::client(&server)
+        .list_submitted(
+            Some("ferris"),
+            &ListingSort::Top(TimeFilter::Week),
+            Some("t3_z".to_string()),
+            500,
+        )
+        .await
+        .expect("Unable to list submitted posts");
+    assert_eq!(resp.after.as_deref(), Some("t3_a"));
+    assert_eq!(resp.data.len(), 1);
+    assert_eq!(resp.data[0].name, "t3_a");
+}
+
+#[tokio::test]
+async fn test_list_submitted_defaults_to_current_user() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/v1/me"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "4x6y1",
+            "name": "spez"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/user/spez/submitted"))
+        .and(query_param("sort", "new"))
+        .and(query_param("t", "all"))
+        .and(query_param("limit", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(listing(Vec::new(), None)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let resp = common::client(&server)
+        .list_submitted(None, &ListingSort::default(), None, 0)
+        .await
+        .expect("Unable to list submitted posts");
+    assert!(resp.after.is_none());
+    assert!(resp.data.is_empty());
+}
@@ -0,0 +1,97 @@
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_get_subreddits_by_name() {
+    let server = MockServer::start().await;
+
+    // "privatesub" is omitted by Reddit since it's not accessible.
+    Mock::given(method("GET"))
+        .and(path("/api/info"))
+        .and(query_param("sr_name", "rust,privatesub"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "kind": "Listing",
+            "data": {
+                "after": null,
+                "dist": 1,
+                "children": [{
+                    "kind": "t5",
+                    "data": {
+                        "name": "t5_2s7lj",
+                        "display_name": "rust",
+                        "title": "The Rust Programming Language",
+                        "public_description": "A place for all things related to Rust",
+                        "subscribers": 300000,
+                        "icon_img": "",
+                        "over18": false,
+                        "subreddit_type": "public",
+                        "url": "/r/rust/",
+                        "created_utc": 1276390000.0
+                    }
+                }]
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let subreddits = client
+        .get_subreddits_by_name(&["rust".to_string(), "privatesub".to_string()])
+        .await
+        .expect("Unable to get subreddits");
+    assert_eq!(subreddits.len(), 1);
+    assert_eq!(subreddits[0].display_name, "rust");
+    assert_eq!(subreddits[0].subscribers, Some(300000));
+
+    assert!(client
+        .get_subreddits_by_name(&[])
+        .await
+        .expect("Unable to get subreddits")
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_list_subscribed() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/subreddits/mine/subscriber"))
+        .and(query_param("limit", "100"))
+        .and(query_param("after", "t5_2qh1i"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "kind": "Listing",
+            "data": {
+                "after": "t5_2s7lj",
+                "dist": 1,
+                "children": [{
+                    "kind": "t5",
+                    "data": {
+                        "name": "t5_2s7lj",
+                        "display_name": "rust",
+                        "title": "The Rust Programming Language",
+                        "public_description": "A place for all things related to Rust",
+                        "subscribers": 300000,
+                        "url": "/r/rust/",
+                        "created_utc": 1276390000.0
+                    }
+                }]
+            }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let resp = client
+        .list_subscribed(Some("t5_2qh1i".into()), 500)
+        .await
+        .expect("Unable to list subreddits");
+    assert_eq!(resp.after.as_deref(), Some("t5_2s7lj"));
+    assert_eq!(resp.data.len(), 1);
+    assert_eq!(resp.data[0].url, "/r/rust/");
+}
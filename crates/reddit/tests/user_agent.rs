@@ -0,0 +1,45 @@
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+#[tokio::test]
+async fn test_user_agent() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/v1/me"))
+        .and(header(
+            "User-Agent",
+            "desktop:com.athlabs.spyglass:v0.0.1 (by /u/andyndino)",
+        ))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "id": "abc", "name": "spez" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/v1/me"))
+        .and(header("User-Agent", "desktop:com.myapp:v1.0 (by /u/me)"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "id": "def", "name": "me" })),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut client = common::client(&server);
+
+    let user = client.get_user().await.expect("Unable to get user");
+    assert_eq!(user.name, "spez");
+
+    client
+        .set_user_agent("desktop:com.myapp:v1.0 (by /u/me)")
+        .expect("Invalid user agent");
+    let user = client.get_user().await.expect("Unable to get user");
+    assert_eq!(user.name, "me");
+}